@@ -6,16 +6,22 @@ use crate::{
     loc::LinesOfCodeView,
     panel::PanelView,
     structure_editor::{
+      breadcrumb::BreadcrumbView,
+      clipboard::{ClipboardEntry, copy_value, paste, paste_target},
       collection_view::SubjectCollectionView,
-      data::{Direction, SelectionPath, SubjectFactCollection},
+      data::{DEFAULT_COLLECTION_MAX_DEPTH, Direction, SelectionPath, SubjectFactCollection},
+      edit::{EditError, add_property, apply_input, commit_edit, editable_value},
       state::StructureEditorMode,
     },
   },
   input::Action,
 };
 
+pub mod breadcrumb;
+pub mod clipboard;
 pub mod collection_view;
 pub mod data;
+pub mod edit;
 pub mod state;
 pub mod subject_editor;
 
@@ -32,13 +38,15 @@ impl StructureEditor {
 
 impl Component for StructureEditor {
   fn construct(&mut self, context: &mut ElementContext) {
-    let collection = SubjectFactCollection::new(
-      SubjectSelector {
-        evaluated: self.query.evaluated,
-        property: self.query.property.clone(),
-        subject: self.query.subject.clone(),
-      },
+    let root_subject = SubjectSelector {
+      evaluated: self.query.evaluated,
+      property: self.query.property.clone(),
+      subject: self.query.subject.clone(),
+    };
+    let mut collection = SubjectFactCollection::new(
+      root_subject.clone(),
       context.app,
+      DEFAULT_COLLECTION_MAX_DEPTH,
     );
 
     let self_path = SelectionPath::empty();
@@ -51,9 +59,59 @@ impl Component for StructureEditor {
       "editor_mode",
     );
 
+    let (clipboard, clipboard_state) =
+      MutableState::<Option<ClipboardEntry>>::new(context, || None, "clipboard");
+
     println!("Selection Path: {:?}", mode);
 
-    #[allow(clippy::single_match)]
+    // The property is added before anything is built, the rebuilt collection already has its row.
+    // Its value is edited right away.
+    let mode = match mode {
+      StructureEditorMode::AddProperty { selection_path } => {
+        let mode = match add_property(context.app, &selection_path, &collection) {
+          Some(new_selection_path) => {
+            collection =
+              SubjectFactCollection::new(root_subject, context.app, DEFAULT_COLLECTION_MAX_DEPTH);
+            match editable_value(&new_selection_path, &collection) {
+              Some((_, buffer)) => StructureEditorMode::Edit {
+                selection_path: new_selection_path,
+                buffer,
+              },
+              None => StructureEditorMode::Select {
+                selection_path: new_selection_path,
+              },
+            }
+          }
+          None => StructureEditorMode::Select { selection_path },
+        };
+        mode_state.set_direct(context, mode.clone());
+        mode
+      }
+      mode => mode,
+    };
+
+    // Typed text is applied right away, so this frame already shows the new buffer
+    let mode = match mode {
+      StructureEditorMode::Edit {
+        selection_path,
+        buffer,
+      } => {
+        let backspace = context
+          .input_state
+          .is_action_pressed(action_hash(Action::EditorEditModeBackspace));
+        let new_buffer = apply_input(&buffer, &context.input_state.typed_text, backspace);
+        let mode = StructureEditorMode::Edit {
+          selection_path,
+          buffer: new_buffer.clone(),
+        };
+        if new_buffer != buffer {
+          mode_state.set_direct(context, mode.clone());
+        }
+        mode
+      }
+      mode => mode,
+    };
+
     match &mode {
       StructureEditorMode::Select { selection_path } => {
         let left_selection_path = selection_path.move_to(Direction::Left, &collection);
@@ -61,6 +119,10 @@ impl Component for StructureEditor {
         let up_selection_path = selection_path.move_to(Direction::Up, &collection);
         let down_selection_path = selection_path.move_to(Direction::Down, &collection);
         let current_selection_path = selection_path.clone();
+        let add_property_selection_path = selection_path.clone();
+        let copied_value = copy_value(selection_path, &collection);
+        let paste_target = paste_target(selection_path, &collection);
+        let editable_value = editable_value(selection_path, &collection);
 
         FocusableInteractiveView::new()
           .with_action_handler(Action::EditorSelectModeLeft, move |context| {
@@ -96,27 +158,102 @@ impl Component for StructureEditor {
             );
           })
           .with_action_handler(Action::EditorSelectModeEdit, move |context| {
+            // Only primitive values can be edited in place
+            if let Some((_, buffer)) = editable_value {
+              mode_state.set_direct(
+                context,
+                StructureEditorMode::Edit {
+                  selection_path: current_selection_path,
+                  buffer,
+                },
+              );
+            }
+          })
+          .with_action_handler(Action::EditorSelectModeCopy, move |context| {
+            if copied_value.is_some() {
+              clipboard_state.set_direct(context, copied_value);
+            }
+          })
+          .with_action_handler(Action::EditorSelectModePaste, move |context| {
+            if let (Some(entry), Some(target)) = (clipboard, paste_target) {
+              paste(context.app, &entry, &target);
+            }
+          })
+          .with_action_handler(Action::EditorSelectModeAddProperty, move |context| {
             mode_state.set_direct(
               context,
-              StructureEditorMode::Edit {
-                selection_path: current_selection_path,
+              StructureEditorMode::AddProperty {
+                selection_path: add_property_selection_path,
               },
             );
           })
           .build(context);
       }
-      _ => {}
+      StructureEditorMode::Edit {
+        selection_path,
+        buffer,
+      } => {
+        let fact_index =
+          editable_value(selection_path, &collection).map(|(fact_index, _)| fact_index);
+        let commit_selection_path = selection_path.clone();
+        let cancel_selection_path = selection_path.clone();
+        let buffer = buffer.clone();
+
+        FocusableInteractiveView::new()
+          .with_action_handler(Action::EditorEditModeCommit, move |context| {
+            let result = match fact_index {
+              Some(fact_index) => commit_edit(context.app, fact_index, &buffer),
+              None => Err(EditError::MissingFact),
+            };
+            // Invalid input keeps the editor open so it can be corrected
+            match result {
+              Ok(_) => mode_state.set_direct(
+                context,
+                StructureEditorMode::Select {
+                  selection_path: commit_selection_path,
+                },
+              ),
+              Err(err) => eprintln!("Failed to commit edit: {:?}", err),
+            }
+          })
+          .with_action_handler(Action::EditorEditModeCancel, move |context| {
+            mode_state.set_direct(
+              context,
+              StructureEditorMode::Select {
+                selection_path: cancel_selection_path,
+              },
+            );
+          })
+          .build(context);
+      }
+      StructureEditorMode::None | StructureEditorMode::AddProperty { .. } => {}
     }
 
+    let breadcrumb_path = mode
+      .selection_path()
+      .cloned()
+      .unwrap_or_else(SelectionPath::empty);
+    let breadcrumb_collection = collection.clone();
+
     PanelView::new()
       .with_content(|context| {
-        let loc_view = LinesOfCodeView::new(vec![0; 10], 2)
+        let breadcrumb_view = BreadcrumbView::new(breadcrumb_path, breadcrumb_collection)
           .with_layout(vec![UserElementConstraints::relative_to_parent(
             context.theme.spacing_large,
             context.theme.spacing_large,
           )])
           .build(context);
 
+        let loc_view = LinesOfCodeView::new(vec![0; 10], 2)
+          .with_layout(vec![
+            UserElementConstraints::relative_to_parent_horizontal(context.theme.spacing_large),
+            UserElementConstraints::anchor_to_bottom_of(
+              breadcrumb_view,
+              context.theme.spacing_default,
+            ),
+          ])
+          .build(context);
+
         SubjectCollectionView::new(collection, mode, self_path)
           .with_layout(vec![
             UserElementConstraints::anchor_to_right_of(loc_view, context.theme.spacing_large),
@@ -0,0 +1,140 @@
+use sapling_app::App;
+use sapling_data_model::Subject;
+use sapling_gui::prelude::*;
+
+use crate::components::structure_editor::data::{
+  SelectionPath, SelectionPathElement, SubjectFactCollection, SubjectFactCollectionFact,
+};
+
+/// Shows where the selection is, e.g. `Person 1 > Best Friend > Person 2 > First Name`.
+#[derive(Debug)]
+pub struct BreadcrumbView {
+  path: SelectionPath,
+  collection: SubjectFactCollection,
+}
+
+impl BreadcrumbView {
+  pub fn new(path: SelectionPath, collection: SubjectFactCollection) -> Self {
+    Self { path, collection }
+  }
+}
+
+impl Component for BreadcrumbView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    let label = breadcrumb_label(&self.path, &self.collection, context.app);
+    TextView::new(FontVariant::Primary, label).build(context);
+  }
+}
+
+/// Names of the hops along `path` joined by `>`. The root subject comes first, followed by the
+/// property of every fact and the subject of every value the path descends into. A terminal
+/// operator or value adds its own segment, a terminal property is already named by its fact.
+pub fn breadcrumb_label(
+  path: &SelectionPath,
+  collection: &SubjectFactCollection,
+  app: &App,
+) -> String {
+  let mut segments = vec![subject_label(&collection.subject.subject, app)];
+  let mut current_collection = Some(collection);
+  let mut current_fact: Option<&SubjectFactCollectionFact> = None;
+
+  for element in path.elements() {
+    match element {
+      SelectionPathElement::Fact { property } => {
+        current_fact = current_collection.and_then(|collection| {
+          collection.facts.iter().find(|fact| {
+            fact
+              .property
+              .as_ref()
+              .is_some_and(|fact_property| fact_property.subject.is_same(property))
+          })
+        });
+        segments.push(app.get_name(property));
+      }
+      SelectionPathElement::Value => {
+        current_collection = current_fact.and_then(|fact| fact.value.as_deref());
+        if let Some(value) = current_collection {
+          segments.push(subject_label(&value.subject.subject, app));
+        }
+      }
+      SelectionPathElement::Operator => {
+        if let Some(operator) = current_fact.and_then(|fact| fact.operator.as_ref()) {
+          segments.push(app.get_name(operator));
+        }
+      }
+      // Subjects are named by the value or root they belong to, properties by their fact
+      SelectionPathElement::Subject | SelectionPathElement::Property => {}
+    }
+  }
+
+  segments.join(" > ")
+}
+
+fn subject_label(subject: &Subject, app: &App) -> String {
+  match subject {
+    Subject::Static { .. } => app.get_name(subject),
+    Subject::String { value } => format!("\"{}\"", value),
+    Subject::Integer { value } => value.to_string(),
+    Subject::Float { value } => value.to_string(),
+    Subject::Boolean { value } => value.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::components::structure_editor::data::{
+    DEFAULT_COLLECTION_MAX_DEPTH,
+    test_fixtures::{add_fact, selector},
+  };
+
+  #[test]
+  fn test_two_level_breadcrumb() {
+    let mut app = App::new(128);
+    let [person1, person2, best_friend, first_name] = app
+      .create_named_subjects(&["Person 1", "Person 2", "Best Friend", "First Name"])
+      .try_into()
+      .unwrap();
+    add_fact(&mut app, &person1, &best_friend, person2.clone());
+    add_fact(
+      &mut app,
+      &person2,
+      &first_name,
+      Subject::String {
+        value: "Bob".to_string(),
+      },
+    );
+    let collection =
+      SubjectFactCollection::new(selector(&person1), &app, DEFAULT_COLLECTION_MAX_DEPTH);
+
+    let first_name_path = SelectionPath::empty()
+      .with(SelectionPathElement::Fact {
+        property: best_friend,
+      })
+      .with(SelectionPathElement::Value)
+      .with(SelectionPathElement::Fact {
+        property: first_name,
+      });
+
+    assert_eq!(
+      breadcrumb_label(
+        &first_name_path.with(SelectionPathElement::Property),
+        &collection,
+        &app
+      ),
+      "Person 1 > Best Friend > Person 2 > First Name"
+    );
+    assert_eq!(
+      breadcrumb_label(
+        &first_name_path.with(SelectionPathElement::Value),
+        &collection,
+        &app
+      ),
+      "Person 1 > Best Friend > Person 2 > First Name > \"Bob\""
+    );
+    assert_eq!(
+      breadcrumb_label(&SelectionPath::default(), &collection, &app),
+      "Person 1"
+    );
+  }
+}
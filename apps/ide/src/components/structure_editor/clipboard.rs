@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+use sapling_app::App;
+use sapling_data_model::{Subject, SubjectSelector};
+use sapling_query_engine::System;
+
+use crate::components::structure_editor::{
+  data::{SelectionPath, SubjectFactCollection},
+  edit::{selected_static_subject, user_fact},
+};
+
+/// A copied fact value together with the property it was copied from.
+#[derive(Debug, Clone)]
+pub struct ClipboardEntry {
+  pub property: Subject,
+  pub operator: Subject,
+  pub value: SubjectFactCollection,
+}
+
+/// Copies the selected value if it is a static subject.
+pub fn copy_value(
+  selection_path: &SelectionPath,
+  collection: &SubjectFactCollection,
+) -> Option<ClipboardEntry> {
+  let fact = selection_path.selected_value_fact(collection)?;
+  let value = fact.value.as_deref()?;
+  if !matches!(value.subject.subject, Subject::Static { .. }) {
+    return None;
+  }
+  Some(ClipboardEntry {
+    property: fact.property.as_ref()?.subject.clone(),
+    operator: fact.operator.clone().unwrap_or(System::CORE_OPERATOR_IS),
+    value: value.clone(),
+  })
+}
+
+/// Subject a paste at the selection would add the clipboard's fact to.
+pub fn paste_target(
+  selection_path: &SelectionPath,
+  collection: &SubjectFactCollection,
+) -> Option<Subject> {
+  selected_static_subject(selection_path, collection).map(|(_, subject)| subject)
+}
+
+/// Adds the clipboard's fact to `target`, with the copied subtree recreated from fresh subjects so
+/// editing the copy doesn't change the original. Returns the subject the copy's root maps to.
+///
+/// Only static subjects which have facts in the copy are recreated, properties and subjects
+/// without facts (e.g. references to other documents or subjects cut off by the collection's
+/// depth limit) are shared. A cycle back to a subject of the copy points to its recreation.
+pub fn paste(app: &mut App, entry: &ClipboardEntry, target: &Subject) -> Subject {
+  let value = paste_collection(app, &entry.value, &mut HashMap::new());
+  app.add_fact(user_fact(
+    target.clone(),
+    entry.property.clone(),
+    entry.operator.clone(),
+    SubjectSelector {
+      subject: value.clone(),
+      ..entry.value.subject.clone()
+    },
+  ));
+  value
+}
+
+fn paste_collection(
+  app: &mut App,
+  collection: &SubjectFactCollection,
+  remapped: &mut HashMap<Subject, Subject>,
+) -> Subject {
+  let original = &collection.subject.subject;
+  if let Some(copy) = remapped.get(original) {
+    return copy.clone();
+  }
+  if !matches!(original, Subject::Static { .. }) || collection.facts.is_empty() {
+    return original.clone();
+  }
+
+  let name = format!("{} (copy)", app.get_name(original));
+  let copy = System::new_named_static(app.get_raw_database_mut(), &name);
+  remapped.insert(original.clone(), copy.clone());
+
+  for fact in &collection.facts {
+    let (Some(property), Some(value)) = (&fact.property, &fact.value) else {
+      continue;
+    };
+    let value_subject = paste_collection(app, value, remapped);
+    app.add_fact(user_fact(
+      copy.clone(),
+      property.subject.clone(),
+      fact.operator.clone().unwrap_or(System::CORE_OPERATOR_IS),
+      SubjectSelector {
+        subject: value_subject,
+        ..value.subject.clone()
+      },
+    ));
+  }
+
+  copy
+}
+
+#[cfg(test)]
+mod tests {
+  use sapling_data_model::Query;
+
+  use super::*;
+  use crate::components::structure_editor::data::{
+    DEFAULT_COLLECTION_MAX_DEPTH, SelectionPathElement,
+    test_fixtures::{add_fact, selector},
+  };
+
+  fn value_of(app: &App, subject: &Subject, property: &Subject) -> Vec<Subject> {
+    app
+      .query_all(&Query {
+        evaluated: false,
+        meta: None,
+        property: Some(property.clone()),
+        subject: subject.clone(),
+      })
+      .into_iter()
+      .map(|fact| fact.value.subject)
+      .collect()
+  }
+
+  #[test]
+  fn test_copy_and_paste_subtree() {
+    let mut app = App::new(128);
+    let [person1, person2, address, home, street, city] = app
+      .create_named_subjects(&["Person 1", "Person 2", "Address", "Home", "Street", "City"])
+      .try_into()
+      .unwrap();
+    add_fact(&mut app, &person1, &address, home.clone());
+    add_fact(
+      &mut app,
+      &home,
+      &street,
+      Subject::String {
+        value: "Main Street".to_string(),
+      },
+    );
+    add_fact(
+      &mut app,
+      &home,
+      &city,
+      Subject::String {
+        value: "Berlin".to_string(),
+      },
+    );
+
+    let collection =
+      SubjectFactCollection::new(selector(&person1), &app, DEFAULT_COLLECTION_MAX_DEPTH);
+    let address_value = SelectionPath::empty()
+      .with(SelectionPathElement::Fact {
+        property: address.clone(),
+      })
+      .with(SelectionPathElement::Value);
+    let entry = copy_value(&address_value, &collection).unwrap();
+    assert_eq!(entry.value.facts.len(), 2);
+
+    let collection =
+      SubjectFactCollection::new(selector(&person2), &app, DEFAULT_COLLECTION_MAX_DEPTH);
+    let target = paste_target(&SelectionPath::default(), &collection).unwrap();
+    assert_eq!(target, person2);
+    let copy = paste(&mut app, &entry, &target);
+
+    assert_ne!(copy, home);
+    assert_eq!(value_of(&app, &person2, &address), vec![copy.clone()]);
+    assert_eq!(
+      value_of(&app, &copy, &street),
+      vec![Subject::String {
+        value: "Main Street".to_string()
+      }]
+    );
+    assert_eq!(
+      value_of(&app, &copy, &city),
+      vec![Subject::String {
+        value: "Berlin".to_string()
+      }]
+    );
+    // The original is left alone
+    assert_eq!(value_of(&app, &person1, &address), vec![home.clone()]);
+    assert_eq!(value_of(&app, &home, &street).len(), 1);
+  }
+
+  #[test]
+  fn test_only_static_values_are_copied() {
+    let mut app = App::new(128);
+    let [person, age] = app
+      .create_named_subjects(&["Person", "Age"])
+      .try_into()
+      .unwrap();
+    add_fact(&mut app, &person, &age, Subject::Integer { value: 42 });
+    let collection =
+      SubjectFactCollection::new(selector(&person), &app, DEFAULT_COLLECTION_MAX_DEPTH);
+
+    let age_value = SelectionPath::empty()
+      .with(SelectionPathElement::Fact { property: age })
+      .with(SelectionPathElement::Value);
+    assert!(copy_value(&age_value, &collection).is_none());
+  }
+}
@@ -3,7 +3,9 @@ use sapling_gui::prelude::*;
 
 use crate::components::structure_editor::{
   StructureEditorMode,
-  data::{SelectionPath, SelectionPathElement, SubjectFactCollection},
+  data::{
+    SelectionPath, SelectionPathElement, SubjectFactCollection, SubjectFactCollectionPlaceholder,
+  },
   subject_editor::SubjectEditor,
 };
 
@@ -46,13 +48,25 @@ impl Component for SubjectCollectionView {
           .build(context);
         return;
       }
+      Subject::Boolean { value } => {
+        TextView::new(FontVariant::EditorNumber, value.to_string())
+          .with_line_height(line_height)
+          .with_layout(vec![UserElementConstraints::relative_to_parent(0.0, 0.0)])
+          .build(context);
+        return;
+      }
       Subject::Static { .. } => {}
       _ => {
         return;
       }
     }
 
-    let subject_name = context.app.get_name(&self.collection.subject.subject);
+    let mut subject_name = context.app.get_name(&self.collection.subject.subject);
+    match self.collection.placeholder {
+      Some(SubjectFactCollectionPlaceholder::Cycle) => subject_name.push_str(" (cycle)"),
+      Some(SubjectFactCollectionPlaceholder::MaxDepth) => subject_name.push_str(" ..."),
+      None => {}
+    }
 
     let mut selected_element = None;
 
@@ -79,6 +93,8 @@ impl Component for SubjectCollectionView {
         let fact_path = self.path.with(SelectionPathElement::Fact {
           property: property.subject.clone(),
         });
+        // Values are keyed by their property, so edit state stays with its fact on reorders
+        let value_key = format!("{:?}", property.subject);
 
         let property_name = context.app.get_name(&property.subject);
         let property_text_view = TextView::new(FontVariant::EditorProperty, property_name)
@@ -125,8 +141,8 @@ impl Component for SubjectCollectionView {
 
           if let Some(value) = &fact.value {
             let value_path = fact_path.with(SelectionPathElement::Value);
-            let value_view = if self.mode.is_editing(&value_path) {
-              SubjectEditor::new(value.subject.subject.clone())
+            let value_view = if let Some(buffer) = self.mode.edit_buffer(&value_path) {
+              SubjectEditor::new(value.subject.subject.clone(), buffer.to_string())
                 .with_layout(vec![
                   UserElementConstraints::anchor_to_right_of(
                     operator_view,
@@ -134,7 +150,7 @@ impl Component for SubjectCollectionView {
                   ),
                   top_constraint.clone(),
                 ])
-                .build(context)
+                .build_keyed(context, &value_key)
             } else {
               SubjectCollectionView::new(
                 (**value).clone(),
@@ -148,7 +164,7 @@ impl Component for SubjectCollectionView {
                 ),
                 top_constraint.clone(),
               ])
-              .build(context)
+              .build_keyed(context, &value_key)
             };
 
             if self.mode.is_selected(&value_path) {
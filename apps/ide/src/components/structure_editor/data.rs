@@ -1,22 +1,47 @@
 use sapling_app::App;
 use sapling_data_model::{Query, Subject, SubjectSelector};
-use sapling_query_engine::FoundFact;
+use sapling_query_engine::{FoundFact, FoundFactIteratorExt};
+
+/// Nesting depth after which the structure editor stops expanding fact values.
+pub const DEFAULT_COLLECTION_MAX_DEPTH: usize = 16;
 
 #[derive(Debug, Clone)]
 pub struct SubjectFactCollection {
   pub subject: SubjectSelector,
   pub facts: Vec<SubjectFactCollectionFact>,
+  /// Set if the facts of the subject were not collected to keep the collection finite
+  pub placeholder: Option<SubjectFactCollectionPlaceholder>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubjectFactCollectionPlaceholder {
+  /// The subject is already being collected further up, e.g. a person being their own best friend
+  Cycle,
+  /// The maximum nesting depth was reached
+  MaxDepth,
 }
 
 #[derive(Debug, Clone)]
 pub struct SubjectFactCollectionFact {
+  /// Index of the fact in the database
+  pub fact_index: Option<usize>,
   pub property: Option<SubjectSelector>,
   pub operator: Option<Subject>,
   pub value: Option<Box<SubjectFactCollection>>,
 }
 
 impl SubjectFactCollection {
-  pub fn new(subject: SubjectSelector, app: &App) -> Self {
+  /// Collects the facts of the subject and, recursively, of their values up to `max_depth` levels.
+  pub fn new(subject: SubjectSelector, app: &App, max_depth: usize) -> Self {
+    Self::collect(subject, app, max_depth, &mut Vec::new())
+  }
+
+  fn collect(
+    subject: SubjectSelector,
+    app: &App,
+    remaining_depth: usize,
+    ancestors: &mut Vec<Subject>,
+  ) -> Self {
     let facts = app
       .query_once(&Query {
         evaluated: false,
@@ -24,27 +49,57 @@ impl SubjectFactCollection {
         property: None,
         subject: subject.subject.clone(),
       })
+      .unique_by_index()
+      .collect::<Vec<_>>();
+
+    let placeholder = if facts.is_empty() {
+      None
+    } else if ancestors.contains(&subject.subject) {
+      Some(SubjectFactCollectionPlaceholder::Cycle)
+    } else if remaining_depth == 0 {
+      Some(SubjectFactCollectionPlaceholder::MaxDepth)
+    } else {
+      None
+    };
+
+    if placeholder.is_some() {
+      return Self {
+        subject,
+        facts: Vec::new(),
+        placeholder,
+      };
+    }
+
+    ancestors.push(subject.subject.clone());
+    let facts = facts
+      .into_iter()
+      .map(|fact| SubjectFactCollectionFact::collect(&fact, app, remaining_depth - 1, ancestors))
       .collect::<Vec<_>>();
+    ancestors.pop();
 
     Self {
       subject,
-      facts: facts
-        .into_iter()
-        .map(|fact| SubjectFactCollectionFact::new(&fact, app))
-        .collect::<Vec<_>>(),
+      facts,
+      placeholder: None,
     }
   }
 }
 
 impl SubjectFactCollectionFact {
-  pub fn new(fact: &FoundFact, app: &App) -> Self {
+  fn collect(
+    fact: &FoundFact,
+    app: &App,
+    remaining_depth: usize,
+    ancestors: &mut Vec<Subject>,
+  ) -> Self {
     let property = fact.fact.property.clone();
     let operator = fact.fact.operator.clone();
     let value_raw = fact.fact.value.clone();
 
-    let value = SubjectFactCollection::new(value_raw, app);
+    let value = SubjectFactCollection::collect(value_raw, app, remaining_depth, ancestors);
 
     SubjectFactCollectionFact {
+      fact_index: Some(fact.fact_index),
       property: Some(property),
       operator: Some(operator),
       value: Some(Box::new(value)),
@@ -92,6 +147,10 @@ impl SelectionPath {
     Self { path }
   }
 
+  pub fn elements(&self) -> &[SelectionPathElement] {
+    &self.path
+  }
+
   pub fn popped(&self) -> Self {
     let mut path = self.path.clone();
     path.pop();
@@ -180,6 +239,38 @@ impl SelectionPath {
     None
   }
 
+  /// Collection the selected element belongs to together with the collection's path. For a
+  /// selected property, operator or value this is the collection owning the fact.
+  pub fn containing_collection<'a>(
+    &self,
+    collection: &'a SubjectFactCollection,
+  ) -> Option<(SelectionPath, &'a SubjectFactCollection)> {
+    let collection_path = match self.path.last()? {
+      SelectionPathElement::Subject | SelectionPathElement::Fact { .. } => self.popped(),
+      SelectionPathElement::Property
+      | SelectionPathElement::Operator
+      | SelectionPathElement::Value => self.popped().popped(),
+    };
+    match collection_path.traverse(collection) {
+      Some(Selection::Collection(Some(containing))) => Some((collection_path, containing)),
+      _ => None,
+    }
+  }
+
+  /// Fact whose value is selected, if the path ends in a value.
+  pub fn selected_value_fact<'a>(
+    &self,
+    collection: &'a SubjectFactCollection,
+  ) -> Option<&'a SubjectFactCollectionFact> {
+    if !matches!(self.path.last(), Some(SelectionPathElement::Value)) {
+      return None;
+    }
+    match self.popped().traverse(collection) {
+      Some(Selection::Fact(fact)) => fact,
+      _ => None,
+    }
+  }
+
   fn advance_fact(&self, collection: &SubjectFactCollection, negative: bool) -> Self {
     let Some(Selection::Fact(Some(property_fact))) = self.traverse(collection) else {
       return self.clone();
@@ -205,7 +296,7 @@ impl SelectionPath {
 
     if negative && current_fact_index == 0 {
       return self.clone();
-    } else if !negative && current_fact_index == parent_collection.facts.len() - 1 {
+    } else if !negative && current_fact_index + 1 >= parent_collection.facts.len() {
       return self.clone();
     }
 
@@ -232,7 +323,9 @@ impl SelectionPath {
 
   pub fn move_to(&self, direction: Direction, collection: &SubjectFactCollection) -> Self {
     let mut path_clone = self.path.clone();
-    let last_item = self.path.last().unwrap();
+    let Some(last_item) = self.path.last() else {
+      return self.clone();
+    };
 
     match (last_item, &direction) {
       (SelectionPathElement::Subject, Direction::Left) => {
@@ -343,3 +436,126 @@ pub enum Direction {
   Right,
   Down,
 }
+
+/// Fixtures shared by the structure editor tests.
+#[cfg(test)]
+pub(crate) mod test_fixtures {
+  use sapling_app::App;
+  use sapling_data_model::{Fact, Subject, SubjectSelector};
+  use sapling_query_engine::System;
+
+  pub fn selector(subject: &Subject) -> SubjectSelector {
+    SubjectSelector {
+      subject: subject.clone(),
+      evaluated: false,
+      property: None,
+    }
+  }
+
+  /// Adds `subject/property = value` with the default meta, returns the index of the new fact.
+  pub fn add_fact(app: &mut App, subject: &Subject, property: &Subject, value: Subject) -> usize {
+    app.add_fact(Fact {
+      subject: selector(subject),
+      property: selector(property),
+      value: selector(&value),
+      operator: System::CORE_OPERATOR_IS,
+      meta: Subject::String {
+        value: "default".to_string(),
+      },
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::components::structure_editor::data::test_fixtures::{add_fact, selector};
+
+  #[test]
+  fn test_self_referential_subject_terminates() {
+    let mut app = App::new(128);
+    let best_friend = app.create_named_subject("Best Friend");
+    let person = app.create_named_subject("Person");
+    add_fact(&mut app, &person, &best_friend, person.clone());
+
+    let collection =
+      SubjectFactCollection::new(selector(&person), &app, DEFAULT_COLLECTION_MAX_DEPTH);
+
+    let best_friend_fact = collection
+      .facts
+      .iter()
+      .find(|fact| {
+        fact
+          .property
+          .as_ref()
+          .is_some_and(|property| property.subject == best_friend)
+      })
+      .unwrap();
+    let value = best_friend_fact.value.as_deref().unwrap();
+    assert_eq!(value.subject.subject, person);
+    assert_eq!(
+      value.placeholder,
+      Some(SubjectFactCollectionPlaceholder::Cycle)
+    );
+    assert!(value.facts.is_empty());
+
+    let collection = SubjectFactCollection::new(selector(&person), &app, 0);
+    assert_eq!(
+      collection.placeholder,
+      Some(SubjectFactCollectionPlaceholder::MaxDepth)
+    );
+    assert!(collection.facts.is_empty());
+  }
+
+  #[test]
+  fn test_move_to_with_empty_nested_collection() {
+    let mut app = App::new(128);
+    let best_friend = app.create_named_subject("Best Friend");
+    let person = app.create_named_subject("Person");
+    let friend = app.create_named_subject("Friend");
+    add_fact(&mut app, &person, &best_friend, friend.clone());
+    let collection =
+      SubjectFactCollection::new(selector(&person), &app, DEFAULT_COLLECTION_MAX_DEPTH);
+
+    let fact_path = SelectionPath::empty().with(SelectionPathElement::Fact {
+      property: best_friend.clone(),
+    });
+    let value_path = fact_path.with(SelectionPathElement::Value);
+    let nested_subject_path = value_path.with(SelectionPathElement::Subject);
+    let property_path = fact_path.with(SelectionPathElement::Property);
+
+    // The friend has no facts, so there is nothing to the right of or below its subject
+    for direction in [Direction::Right, Direction::Up, Direction::Down] {
+      assert!(
+        nested_subject_path
+          .move_to(direction, &collection)
+          .matches(&nested_subject_path)
+      );
+    }
+    assert!(
+      nested_subject_path
+        .move_to(Direction::Left, &collection)
+        .matches(&value_path)
+    );
+    assert!(
+      value_path
+        .move_to(Direction::Right, &collection)
+        .matches(&value_path)
+    );
+
+    // A single fact has no neighbours
+    for direction in [Direction::Up, Direction::Down] {
+      assert!(
+        property_path
+          .move_to(direction, &collection)
+          .matches(&property_path)
+      );
+    }
+
+    assert!(
+      SelectionPath::empty()
+        .move_to(Direction::Right, &collection)
+        .matches(&SelectionPath::empty())
+    );
+  }
+}
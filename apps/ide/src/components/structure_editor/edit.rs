@@ -0,0 +1,323 @@
+use sapling_app::App;
+use sapling_data_model::{Fact, Subject, SubjectSelector};
+use sapling_query_engine::System;
+
+use crate::components::structure_editor::data::{
+  SelectionPath, SelectionPathElement, SubjectFactCollection,
+};
+
+/// Name of the property created by [`add_property`].
+pub const PLACEHOLDER_PROPERTY_NAME: &str = "New Property";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditError {
+  /// The edited fact was removed in the meantime
+  MissingFact,
+  /// The typed text isn't a valid value of the edited value's type
+  InvalidValue {
+    type_name: &'static str,
+    input: String,
+  },
+}
+
+/// Index of the fact whose value is selected and the text to start editing it with, if the
+/// selected value is a primitive.
+pub fn editable_value(
+  selection_path: &SelectionPath,
+  collection: &SubjectFactCollection,
+) -> Option<(usize, String)> {
+  let fact = selection_path.selected_value_fact(collection)?;
+  let value = &fact.value.as_ref()?.subject;
+  if value.evaluated || value.property.is_some() {
+    return None;
+  }
+  Some((fact.fact_index?, initial_buffer(&value.subject)?))
+}
+
+fn initial_buffer(value: &Subject) -> Option<String> {
+  match value {
+    Subject::String { value } => Some(value.clone()),
+    Subject::Integer { value } => Some(value.to_string()),
+    Subject::Float { value } => Some(value.to_string()),
+    Subject::Boolean { value } => Some(value.to_string()),
+    Subject::Static { .. } => None,
+  }
+}
+
+/// Parses the buffer as a value of the same type as `original`.
+pub fn parse_buffer(original: &Subject, buffer: &str) -> Result<Subject, EditError> {
+  let invalid = || EditError::InvalidValue {
+    type_name: original.type_name(),
+    input: buffer.to_string(),
+  };
+  match original {
+    Subject::String { .. } => Ok(Subject::String {
+      value: buffer.to_string(),
+    }),
+    Subject::Integer { .. } => buffer
+      .trim()
+      .parse()
+      .map(|value| Subject::Integer { value })
+      .map_err(|_| invalid()),
+    Subject::Float { .. } => buffer
+      .trim()
+      .parse()
+      .map(|value| Subject::Float { value })
+      .map_err(|_| invalid()),
+    Subject::Boolean { .. } => buffer
+      .trim()
+      .parse()
+      .map(|value| Subject::Boolean { value })
+      .map_err(|_| invalid()),
+    Subject::Static { .. } => Err(invalid()),
+  }
+}
+
+/// Buffer after applying this frame's typed text and backspace presses.
+pub fn apply_input(buffer: &str, typed_text: &str, backspace: bool) -> String {
+  let mut buffer = buffer.to_string();
+  if backspace {
+    buffer.pop();
+  }
+  buffer.push_str(typed_text);
+  buffer
+}
+
+/// Replaces the value of the fact at `fact_index` with the parsed buffer and returns the new index
+/// of the fact. Facts can't be changed in place, so the fact is removed and added again, which
+/// also notifies the watchers of both changes.
+pub fn commit_edit(app: &mut App, fact_index: usize, buffer: &str) -> Result<usize, EditError> {
+  let mut fact = app
+    .get_raw_database()
+    .get_fact(fact_index)
+    .cloned()
+    .ok_or(EditError::MissingFact)?;
+  fact.value.subject = parse_buffer(&fact.value.subject, buffer)?;
+
+  app.remove_fact(fact_index);
+  Ok(app.add_fact(fact))
+}
+
+/// Static subject of the collection containing the selection together with the collection's
+/// path, primitives can't have facts of their own.
+pub fn selected_static_subject(
+  selection_path: &SelectionPath,
+  collection: &SubjectFactCollection,
+) -> Option<(SelectionPath, Subject)> {
+  let (collection_path, containing) = selection_path.containing_collection(collection)?;
+  let subject = containing.subject.subject.clone();
+  matches!(subject, Subject::Static { .. }).then_some((collection_path, subject))
+}
+
+/// Fact with the meta used for everything entered through the editor.
+pub fn user_fact(
+  subject: Subject,
+  property: Subject,
+  operator: Subject,
+  value: SubjectSelector,
+) -> Fact {
+  let selector = |subject| SubjectSelector {
+    subject,
+    evaluated: false,
+    property: None,
+  };
+  Fact {
+    subject: selector(subject),
+    property: selector(property),
+    operator,
+    value,
+    meta: Subject::String {
+      value: "default".to_string(),
+    },
+  }
+}
+
+/// Adds a fact with a new placeholder property and an empty string value to the subject of the
+/// collection containing `selection_path`. Returns the path selecting the new fact's value, so it
+/// can be edited right away, `None` if the selection isn't inside a static subject's collection.
+pub fn add_property(
+  app: &mut App,
+  selection_path: &SelectionPath,
+  collection: &SubjectFactCollection,
+) -> Option<SelectionPath> {
+  let (collection_path, subject) = selected_static_subject(selection_path, collection)?;
+
+  let property = app.create_named_subject(PLACEHOLDER_PROPERTY_NAME);
+  app.add_fact(user_fact(
+    subject,
+    property.clone(),
+    System::CORE_OPERATOR_IS,
+    SubjectSelector {
+      subject: Subject::String {
+        value: String::new(),
+      },
+      evaluated: false,
+      property: None,
+    },
+  ));
+
+  Some(
+    collection_path
+      .with(SelectionPathElement::Fact { property })
+      .with(SelectionPathElement::Value),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use sapling_data_model::Query;
+
+  use super::*;
+  use crate::components::structure_editor::data::{
+    DEFAULT_COLLECTION_MAX_DEPTH, SelectionPathElement,
+    test_fixtures::{add_fact, selector},
+  };
+
+  fn value_path(property: &Subject) -> SelectionPath {
+    SelectionPath::empty()
+      .with(SelectionPathElement::Fact {
+        property: property.clone(),
+      })
+      .with(SelectionPathElement::Value)
+  }
+
+  #[test]
+  fn test_enter_edit_mode_for_primitive_value() {
+    let mut app = App::new(128);
+    let [person, city, employer, acme] = app
+      .create_named_subjects(&["Person", "City", "Employer", "Acme"])
+      .try_into()
+      .unwrap();
+    let city_fact = add_fact(
+      &mut app,
+      &person,
+      &city,
+      Subject::String {
+        value: "Berlin".to_string(),
+      },
+    );
+    add_fact(&mut app, &person, &employer, acme);
+
+    let collection =
+      SubjectFactCollection::new(selector(&person), &app, DEFAULT_COLLECTION_MAX_DEPTH);
+
+    assert_eq!(
+      editable_value(&value_path(&city), &collection),
+      Some((city_fact, "Berlin".to_string()))
+    );
+    // Static subjects and anything but values can't be edited
+    assert_eq!(editable_value(&value_path(&employer), &collection), None);
+    assert_eq!(
+      editable_value(
+        &value_path(&city)
+          .popped()
+          .with(SelectionPathElement::Property),
+        &collection
+      ),
+      None
+    );
+  }
+
+  #[test]
+  fn test_commit_string_change() {
+    let mut app = App::new(128);
+    let [person, city] = app
+      .create_named_subjects(&["Person", "City"])
+      .try_into()
+      .unwrap();
+    let fact_index = add_fact(
+      &mut app,
+      &person,
+      &city,
+      Subject::String {
+        value: "Berlin".to_string(),
+      },
+    );
+
+    let buffer = apply_input("Berlin", "er", false);
+    commit_edit(&mut app, fact_index, &buffer).unwrap();
+
+    let values = app
+      .query_all(&Query {
+        evaluated: false,
+        meta: None,
+        property: Some(city),
+        subject: person,
+      })
+      .into_iter()
+      .map(|fact| fact.value.subject)
+      .collect::<Vec<_>>();
+    assert_eq!(
+      values,
+      vec![Subject::String {
+        value: "Berliner".to_string()
+      }]
+    );
+  }
+
+  #[test]
+  fn test_invalid_integer_is_rejected() {
+    let mut app = App::new(128);
+    let [person, age] = app
+      .create_named_subjects(&["Person", "Age"])
+      .try_into()
+      .unwrap();
+    let fact_index = add_fact(&mut app, &person, &age, Subject::Integer { value: 42 });
+    let fact_count = app.get_raw_database().fact_count();
+
+    assert_eq!(
+      commit_edit(&mut app, fact_index, "4x"),
+      Err(EditError::InvalidValue {
+        type_name: "integer",
+        input: "4x".to_string()
+      })
+    );
+    // The fact is left untouched
+    assert_eq!(app.get_raw_database().fact_count(), fact_count);
+    assert_eq!(
+      app
+        .get_raw_database()
+        .get_fact(fact_index)
+        .unwrap()
+        .value
+        .subject,
+      Subject::Integer { value: 42 }
+    );
+
+    assert_eq!(
+      parse_buffer(&Subject::Integer { value: 42 }, " 7 "),
+      Ok(Subject::Integer { value: 7 })
+    );
+  }
+
+  #[test]
+  fn test_add_property_grows_collection() {
+    let mut app = App::new(128);
+    let [person, age] = app
+      .create_named_subjects(&["Person", "Age"])
+      .try_into()
+      .unwrap();
+    add_fact(&mut app, &person, &age, Subject::Integer { value: 42 });
+
+    let collection =
+      SubjectFactCollection::new(selector(&person), &app, DEFAULT_COLLECTION_MAX_DEPTH);
+    let fact_count = collection.facts.len();
+
+    let new_path = add_property(&mut app, &SelectionPath::default(), &collection).unwrap();
+
+    let collection =
+      SubjectFactCollection::new(selector(&person), &app, DEFAULT_COLLECTION_MAX_DEPTH);
+    assert_eq!(collection.facts.len(), fact_count + 1);
+    let property = app.get_global_by_name(PLACEHOLDER_PROPERTY_NAME).unwrap();
+    assert!(new_path.matches(&value_path(&property)));
+    // The empty value of the new fact can be edited right away
+    assert_eq!(
+      editable_value(&new_path, &collection).map(|(_, buffer)| buffer),
+      Some(String::new())
+    );
+
+    // Primitive values can't have properties
+    let inside_value = value_path(&age).with(SelectionPathElement::Subject);
+    assert!(add_property(&mut app, &inside_value, &collection).is_none());
+  }
+}
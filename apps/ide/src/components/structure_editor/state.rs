@@ -3,8 +3,19 @@ use crate::components::structure_editor::data::SelectionPath;
 #[derive(Clone, Debug)]
 pub(crate) enum StructureEditorMode {
   None,
-  Select { selection_path: SelectionPath },
-  Edit { selection_path: SelectionPath },
+  Select {
+    selection_path: SelectionPath,
+  },
+  /// Adds a placeholder property to the subject of the collection containing `selection_path`
+  /// during the next construction, which then starts editing the new property's value
+  AddProperty {
+    selection_path: SelectionPath,
+  },
+  /// Editing the primitive value at `selection_path`, `buffer` holds the text typed so far
+  Edit {
+    selection_path: SelectionPath,
+    buffer: String,
+  },
 }
 
 impl StructureEditorMode {
@@ -15,10 +26,23 @@ impl StructureEditorMode {
     }
   }
 
-  pub fn is_editing(&self, comparison_path: &SelectionPath) -> bool {
+  pub fn selection_path(&self) -> Option<&SelectionPath> {
     match self {
-      StructureEditorMode::Edit { selection_path } => selection_path.matches(comparison_path),
-      _ => false,
+      StructureEditorMode::None => None,
+      StructureEditorMode::Select { selection_path }
+      | StructureEditorMode::AddProperty { selection_path }
+      | StructureEditorMode::Edit { selection_path, .. } => Some(selection_path),
+    }
+  }
+
+  /// Text typed so far if the value at `comparison_path` is being edited.
+  pub fn edit_buffer(&self, comparison_path: &SelectionPath) -> Option<&str> {
+    match self {
+      StructureEditorMode::Edit {
+        selection_path,
+        buffer,
+      } if selection_path.matches(comparison_path) => Some(buffer),
+      _ => None,
     }
   }
 }
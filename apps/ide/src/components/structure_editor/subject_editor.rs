@@ -1,43 +1,51 @@
 use sapling_data_model::Subject;
 use sapling_gui::prelude::*;
 
+/// Shows the text typed for a value being edited next to the type of the original value.
 #[derive(Debug, Clone)]
 pub struct SubjectEditor {
-  initial_value: Subject,
+  original_value: Subject,
+  buffer: String,
 }
 
 impl SubjectEditor {
-  pub fn new(initial_value: Subject) -> Self {
-    Self { initial_value }
+  pub fn new(original_value: Subject, buffer: String) -> Self {
+    Self {
+      original_value,
+      buffer,
+    }
   }
 }
 
 impl Component for SubjectEditor {
   fn construct(&mut self, context: &mut ElementContext) {
-    let (subject, subject_state) =
-      MutableState::<Subject>::new(context, || self.initial_value.clone(), "subject");
+    let (type_name, variant) = match self.original_value {
+      Subject::String { .. } => ("String", FontVariant::EditorString),
+      Subject::Integer { .. } => ("Integer", FontVariant::EditorNumber),
+      Subject::Float { .. } => ("Float", FontVariant::EditorNumber),
+      Subject::Boolean { .. } => ("Boolean", FontVariant::EditorNumber),
+      Subject::Static { .. } => return,
+    };
+    let buffer = self.buffer.clone();
 
-    let container = StyledView::new()
+    StyledView::new()
       .with_border(1.0, context.theme.color_background_secondary)
       .with_border_radius_even(context.theme.radius_default)
-      .with_children(move |context| match subject {
-        Subject::String { value } => {
-          let type_view = StyledView::new()
-            .with_background_color(context.theme.color_tertiary)
-            .with_border_radius_even(context.theme.radius_large)
-            .with_children(move |context| {
-              TextView::new(FontVariant::EditorEditType, "String".into()).build(context);
-            })
-            .build(context);
+      .with_children(move |context| {
+        let type_view = StyledView::new()
+          .with_background_color(context.theme.color_tertiary)
+          .with_border_radius_even(context.theme.radius_large)
+          .with_children(move |context| {
+            TextView::new(FontVariant::EditorEditType, type_name.into()).build(context);
+          })
+          .build(context);
 
-          TextView::new(FontVariant::EditorString, value.clone())
-            .with_layout(vec![UserElementConstraints::anchor_to_right_of(
-              type_view,
-              context.theme.spacing_default,
-            )])
-            .build(context);
-        }
-        _ => {}
+        TextView::new(variant, buffer)
+          .with_layout(vec![UserElementConstraints::anchor_to_right_of(
+            type_view,
+            context.theme.spacing_default,
+          )])
+          .build(context);
       })
       .build(context);
   }
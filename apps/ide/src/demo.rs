@@ -11,143 +11,140 @@ pub fn insert_demo_data(app: &mut App) {
   let person1 = app.create_named_subject("Person 1");
   let person2 = app.create_named_subject("Person 2");
 
-  app.add_fact(Fact {
-    subject: SubjectSelector {
-      subject: person1.clone(),
-      evaluated: false,
-      property: None,
-    },
-    property: SubjectSelector {
-      evaluated: false,
-      subject: first_name.clone(),
-      property: None,
-    },
-    value: SubjectSelector {
-      subject: Subject::String {
-        value: "Rene".into(),
+  app.add_facts([
+    Fact {
+      subject: SubjectSelector {
+        subject: person1.clone(),
+        evaluated: false,
+        property: None,
       },
-      evaluated: false,
-      property: None,
-    },
-    operator: System::CORE_OPERATOR_IS.clone(),
-    meta: Subject::String {
-      value: "default".to_string(),
-    },
-  });
-
-  app.add_fact(Fact {
-    subject: SubjectSelector {
-      subject: person1.clone(),
-      evaluated: false,
-      property: None,
-    },
-    property: SubjectSelector {
-      evaluated: false,
-      subject: last_name.clone(),
-      property: None,
-    },
-    value: SubjectSelector {
-      subject: Subject::String {
-        value: "Eichhorn".into(),
+      property: SubjectSelector {
+        evaluated: false,
+        subject: first_name.clone(),
+        property: None,
       },
-      evaluated: false,
-      property: None,
-    },
-    operator: System::CORE_OPERATOR_IS.clone(),
-    meta: Subject::String {
-      value: "default".to_string(),
-    },
-  });
-
-  app.add_fact(Fact {
-    subject: SubjectSelector {
-      subject: person1.clone(),
-      evaluated: false,
-      property: None,
-    },
-    property: SubjectSelector {
-      evaluated: false,
-      subject: best_friend.clone(),
-      property: None,
-    },
-    value: SubjectSelector {
-      subject: person2.clone(),
-      evaluated: false,
-      property: None,
-    },
-    operator: System::CORE_OPERATOR_IS.clone(),
-    meta: Subject::String {
-      value: "default".to_string(),
-    },
-  });
-
-  app.add_fact(Fact {
-    subject: SubjectSelector {
-      subject: person1.clone(),
-      evaluated: false,
-      property: None,
-    },
-    property: SubjectSelector {
-      evaluated: false,
-      subject: age.clone(),
-      property: None,
-    },
-    value: SubjectSelector {
-      subject: Subject::Integer { value: 31 },
-      evaluated: false,
-      property: None,
-    },
-    operator: System::CORE_OPERATOR_IS.clone(),
-    meta: Subject::String {
-      value: "default".to_string(),
-    },
-  });
-
-  app.add_fact(Fact {
-    subject: SubjectSelector {
-      subject: person2.clone(),
-      evaluated: false,
-      property: None,
-    },
-    property: SubjectSelector {
-      evaluated: false,
-      subject: first_name.clone(),
-      property: None,
-    },
-    value: SubjectSelector {
-      subject: Subject::String {
-        value: "John".into(),
+      value: SubjectSelector {
+        subject: Subject::String {
+          value: "Rene".into(),
+        },
+        evaluated: false,
+        property: None,
+      },
+      operator: System::CORE_OPERATOR_IS.clone(),
+      meta: Subject::String {
+        value: "default".to_string(),
       },
-      evaluated: false,
-      property: None,
     },
-    operator: System::CORE_OPERATOR_IS.clone(),
-    meta: Subject::String {
-      value: "default".to_string(),
+    Fact {
+      subject: SubjectSelector {
+        subject: person1.clone(),
+        evaluated: false,
+        property: None,
+      },
+      property: SubjectSelector {
+        evaluated: false,
+        subject: last_name.clone(),
+        property: None,
+      },
+      value: SubjectSelector {
+        subject: Subject::String {
+          value: "Eichhorn".into(),
+        },
+        evaluated: false,
+        property: None,
+      },
+      operator: System::CORE_OPERATOR_IS.clone(),
+      meta: Subject::String {
+        value: "default".to_string(),
+      },
     },
-  });
-
-  app.add_fact(Fact {
-    subject: SubjectSelector {
-      subject: person2.clone(),
-      evaluated: false,
-      property: None,
+    Fact {
+      subject: SubjectSelector {
+        subject: person1.clone(),
+        evaluated: false,
+        property: None,
+      },
+      property: SubjectSelector {
+        evaluated: false,
+        subject: best_friend.clone(),
+        property: None,
+      },
+      value: SubjectSelector {
+        subject: person2.clone(),
+        evaluated: false,
+        property: None,
+      },
+      operator: System::CORE_OPERATOR_IS.clone(),
+      meta: Subject::String {
+        value: "default".to_string(),
+      },
     },
-    property: SubjectSelector {
-      evaluated: false,
-      subject: last_name.clone(),
-      property: None,
+    Fact {
+      subject: SubjectSelector {
+        subject: person1.clone(),
+        evaluated: false,
+        property: None,
+      },
+      property: SubjectSelector {
+        evaluated: false,
+        subject: age.clone(),
+        property: None,
+      },
+      value: SubjectSelector {
+        subject: Subject::Integer { value: 31 },
+        evaluated: false,
+        property: None,
+      },
+      operator: System::CORE_OPERATOR_IS.clone(),
+      meta: Subject::String {
+        value: "default".to_string(),
+      },
     },
-    value: SubjectSelector {
-      subject: Subject::String {
-        value: "Doe".into(),
+    Fact {
+      subject: SubjectSelector {
+        subject: person2.clone(),
+        evaluated: false,
+        property: None,
+      },
+      property: SubjectSelector {
+        evaluated: false,
+        subject: first_name.clone(),
+        property: None,
+      },
+      value: SubjectSelector {
+        subject: Subject::String {
+          value: "John".into(),
+        },
+        evaluated: false,
+        property: None,
+      },
+      operator: System::CORE_OPERATOR_IS.clone(),
+      meta: Subject::String {
+        value: "default".to_string(),
       },
-      evaluated: false,
-      property: None,
     },
-    operator: System::CORE_OPERATOR_IS.clone(),
-    meta: Subject::String {
-      value: "default".to_string(),
+    Fact {
+      subject: SubjectSelector {
+        subject: person2.clone(),
+        evaluated: false,
+        property: None,
+      },
+      property: SubjectSelector {
+        evaluated: false,
+        subject: last_name.clone(),
+        property: None,
+      },
+      value: SubjectSelector {
+        subject: Subject::String {
+          value: "Doe".into(),
+        },
+        evaluated: false,
+        property: None,
+      },
+      operator: System::CORE_OPERATOR_IS.clone(),
+      meta: Subject::String {
+        value: "default".to_string(),
+      },
     },
-  });
+  ]);
 }
@@ -5,4 +5,10 @@ pub enum Action {
   EditorSelectModeUp,
   EditorSelectModeDown,
   EditorSelectModeEdit,
+  EditorSelectModeAddProperty,
+  EditorSelectModeCopy,
+  EditorSelectModePaste,
+  EditorEditModeCommit,
+  EditorEditModeCancel,
+  EditorEditModeBackspace,
 }
@@ -38,11 +38,21 @@ fn main() {
   action_map.add_action(Action::EditorSelectModeUp, KeyboardKey::KEY_K);
   action_map.add_action(Action::EditorSelectModeDown, KeyboardKey::KEY_J);
   action_map.add_action(Action::EditorSelectModeEdit, KeyboardKey::KEY_ENTER);
+  action_map.add_action(Action::EditorSelectModeAddProperty, KeyboardKey::KEY_A);
+  action_map.add_action(Action::EditorSelectModeCopy, KeyboardKey::KEY_C);
+  action_map.add_action(Action::EditorSelectModePaste, KeyboardKey::KEY_V);
+  action_map.add_action(Action::EditorEditModeCommit, KeyboardKey::KEY_ENTER);
+  action_map.add_action(Action::EditorEditModeCancel, KeyboardKey::KEY_ESCAPE);
+  action_map.add_action(Action::EditorEditModeBackspace, KeyboardKey::KEY_BACKSPACE);
 
   while !rl.window_should_close() {
     let width = rl.get_render_width();
     let height = rl.get_render_height();
 
+    if rl.is_key_pressed(KeyboardKey::KEY_F2) {
+      theme = Theme::new_with_variant(&mut rl, &thread, theme.variant.toggled());
+    }
+
     let input_state = InputState::from_raylib(&mut rl, action_map.clone());
 
     let mut d = rl.begin_drawing(&thread);
@@ -67,7 +77,7 @@ fn main() {
       &format!(
         "FPS: {}\nFacts: {}\nElements: {} ({})\nConstruct: {:.2}ms\nLayout {:.2}ms\nRender {:.2}ms\n",
         fps,
-        app.get_raw_database_mut().facts_mut().len(),
+        app.get_raw_database().fact_count(),
         ui_stats.element_count,
         ui_stats.constrain_count,
         ui_stats.construction_duration.as_millis() as f32,
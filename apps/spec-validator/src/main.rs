@@ -3,13 +3,24 @@ use clap::Parser as ClapParser;
 use colored::*;
 use sapling_app::App;
 use sapling_data_model::{Fact, Query, Subject};
-use sapling_query_engine::{EvaluationType, ExplainConstraintEvaluationOutcome, FoundFact, System};
+use sapling_query_engine::{
+  EvaluationType, ExplainConstraintEvaluationOutcome, ExplainConstraintEvaluationOutcomeReason,
+  FoundFact, System,
+};
 use similar::{ChangeTag, TextDiff};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
+#[macro_use]
+mod report;
+mod markdown;
+mod naming;
 mod parser;
+use markdown::format_explain_result_markdown;
+use naming::SubjectNamer;
 use parser::{SubjectRegistry, TestLine};
+use report::{FileReport, OutputFormat, QueryFailure, SuiteReport};
 
 #[derive(ClapParser, Debug)]
 #[command(name = "spec-validator")]
@@ -18,6 +29,36 @@ struct Args {
   /// Update test files with actual output when differences are found
   #[arg(short = 'u', long = "update")]
   update: bool,
+
+  /// Export every explain result as a markdown document into the given directory
+  #[arg(long = "explain-markdown", value_name = "DIR")]
+  explain_markdown: Option<PathBuf>,
+
+  /// Number of variables available to a single query, raise it for deeply nested specs
+  #[arg(long = "bank-size", default_value_t = MEMORY_BANK_SIZE)]
+  bank_size: usize,
+
+  /// Only run spec files whose file name contains the given substring (case-insensitive)
+  #[arg(short = 'f', long = "filter")]
+  filter: Option<String>,
+
+  /// Print how long every query took to collect its facts
+  #[arg(long = "timings")]
+  timings: bool,
+
+  /// Output format, `json` prints a single machine-readable report
+  #[arg(long = "format", value_enum, default_value = "text")]
+  format: OutputFormat,
+}
+
+/// Options shared by all test runners
+struct RunOptions {
+  update_mode: bool,
+  explain_markdown_dir: Option<PathBuf>,
+  bank_size: usize,
+  filter: Option<String>,
+  timings: bool,
+  format: OutputFormat,
 }
 
 const MEMORY_BANK_SIZE: usize = 128;
@@ -70,6 +111,9 @@ fn resolve_fact_references(
     // Resolve references in meta
     fact.meta = resolve_subject(&fact.meta);
   }
+
+  // Subjects may have changed, so the subject index has to be rebuilt
+  database.reindex();
 }
 
 fn print_diff(expected: &[String], actual: &[String]) {
@@ -78,11 +122,11 @@ fn print_diff(expected: &[String], actual: &[String]) {
 
   let diff = TextDiff::from_lines(&expected_text, &actual_text);
 
-  println!("  {}", "Diff:".magenta().bold());
+  report!("  {}", "Diff:".magenta().bold());
 
   for (idx, group) in diff.grouped_ops(3).iter().enumerate() {
     if idx > 0 {
-      println!("    {}", "---".dimmed());
+      report!("    {}", "---".dimmed());
     }
 
     for op in group {
@@ -93,14 +137,49 @@ fn print_diff(expected: &[String], actual: &[String]) {
           ChangeTag::Equal => (' ', change.value().trim_end().normal()),
         };
 
-        println!("    {} {}", sign, line);
+        report!("    {} {}", sign, line);
       }
     }
   }
 }
-const FACT_INDEX_OFFSET: usize = 8;
+/// Reasons the explain trace gives for rejecting facts, engine failures are included with their
+/// message.
+fn rejection_reasons(result: &sapling_query_engine::ExplainResult) -> Vec<String> {
+  use sapling_query_engine::ExplainFactEvent;
+  let mut reasons = Vec::new();
+  for event in &result.fact_events {
+    let outcome = match event {
+      ExplainFactEvent::EvaluatingConstraint { outcome, .. }
+      | ExplainFactEvent::EvaluatingSubQuery { outcome, .. } => outcome,
+      _ => continue,
+    };
+    let ExplainConstraintEvaluationOutcome::Rejected(reason) = outcome else {
+      continue;
+    };
+    let reason = match reason {
+      ExplainConstraintEvaluationOutcomeReason::NotFound => "not found",
+      ExplainConstraintEvaluationOutcomeReason::MaxDepthExceeded => "max depth exceeded",
+    };
+    if !reasons.iter().any(|existing| existing == reason) {
+      reasons.push(reason.to_string());
+    }
+  }
+  reasons.extend(
+    result
+      .failures
+      .iter()
+      .map(|failure| failure.message.clone()),
+  );
+  reasons
+}
 
-fn format_explain_result(app: &App, result: &sapling_query_engine::ExplainResult) -> Vec<String> {
+/// Facts are numbered from `fact_offset` on, see [`SubjectRegistry::explain_fact_offset`].
+fn format_explain_result(
+  app: &App,
+  namer: &impl SubjectNamer,
+  result: &sapling_query_engine::ExplainResult,
+  fact_offset: usize,
+) -> Vec<String> {
   let mut lines = Vec::new();
   let database = app.get_raw_database();
 
@@ -109,7 +188,7 @@ fn format_explain_result(app: &App, result: &sapling_query_engine::ExplainResult
     // Get the constraint fact from the database
     let fact = database.get_fact(*fact_id);
     if let Some(fact) = fact {
-      let fact_str = format_fact(app, fact);
+      let fact_str = format_fact(namer, fact);
       lines.push(format!(
         "Constraint{}: {} [{}]",
         idx, constraint_id, fact_str
@@ -121,7 +200,15 @@ fn format_explain_result(app: &App, result: &sapling_query_engine::ExplainResult
 
   // Format subject
   if let Some(subject) = &result.subject {
-    lines.push(format!("Subject: {}", format_subject(app, subject)));
+    lines.push(format!("Subject: {}", format_subject(namer, subject)));
+  }
+
+  for failure in &result.failures {
+    lines.push(format!(
+      "Engine failure: {} - {}",
+      format_subject(namer, &failure.subject),
+      failure.message
+    ));
   }
 
   // Format fact events
@@ -134,18 +221,18 @@ fn format_explain_result(app: &App, result: &sapling_query_engine::ExplainResult
       } => {
         let fact = database.get_fact(*fact_id);
         if let Some(fact) = fact {
-          let fact_str = format_fact(app, fact);
+          let fact_str = format_fact(namer, fact);
           lines.push(format!(
             "Fact{}: {} [{}]",
             constraint_id,
-            fact_id - FACT_INDEX_OFFSET,
+            fact_id - fact_offset,
             fact_str
           ));
         } else {
           lines.push(format!(
             "Fact{}: {} [unknown]",
             constraint_id,
-            fact_id - FACT_INDEX_OFFSET
+            fact_id - fact_offset
           ));
         }
       }
@@ -156,14 +243,14 @@ fn format_explain_result(app: &App, result: &sapling_query_engine::ExplainResult
       } => {
         let fact = database.get_fact(*fact_id);
         if let Some(fact) = fact {
-          let fact_str = format_fact(app, fact);
+          let fact_str = format_fact(namer, fact);
           lines.push(format!(
             "Yielded for Fact{}: {} [{}]{}",
             constraint_id,
-            fact_id - FACT_INDEX_OFFSET,
+            fact_id - fact_offset,
             fact_str,
             if let Some(subject_variable) = subject_variable {
-              format!(" (subject: {})", format_subject(app, subject_variable))
+              format!(" (subject: {})", format_subject(namer, subject_variable))
             } else {
               String::new()
             }
@@ -172,7 +259,7 @@ fn format_explain_result(app: &App, result: &sapling_query_engine::ExplainResult
           lines.push(format!(
             "Yielded for Fact{}: {} [unknown]",
             constraint_id,
-            fact_id - FACT_INDEX_OFFSET
+            fact_id - fact_offset
           ));
         }
       }
@@ -184,13 +271,16 @@ fn format_explain_result(app: &App, result: &sapling_query_engine::ExplainResult
       } => {
         let outcome = match outcome {
           ExplainConstraintEvaluationOutcome::Passed => "PASS",
+          ExplainConstraintEvaluationOutcome::Rejected(
+            ExplainConstraintEvaluationOutcomeReason::MaxDepthExceeded,
+          ) => "REJECTED (max depth exceeded)",
           ExplainConstraintEvaluationOutcome::Rejected(..) => "REJECTED",
         };
         lines.push(format!(
           "Fact{}: Evaluating SubQuery ?{} yields {} => {}",
           constraint_id,
-          format_subject(app, target_query),
-          format_subject(app, target),
+          format_subject(namer, target_query),
+          format_subject(namer, target),
           outcome
         ));
       }
@@ -214,10 +304,10 @@ fn format_explain_result(app: &App, result: &sapling_query_engine::ExplainResult
             lines.push(format!(
               "Fact{}: Subject {} {} {} => {}{}",
               constraint_id,
-              format_subject(app, actual),
-              format_subject(app, operator),
+              format_subject(namer, actual),
+              format_subject(namer, operator),
               if let Some(target) = target {
-                format_subject(app, target)
+                format_subject(namer, target)
               } else {
                 "~unset~".to_string()
               },
@@ -241,10 +331,10 @@ fn format_explain_result(app: &App, result: &sapling_query_engine::ExplainResult
             lines.push(format!(
               "Fact{}: Property {} {} {} => {}",
               constraint_id,
-              format_subject(app, actual),
-              format_subject(app, operator),
+              format_subject(namer, actual),
+              format_subject(namer, operator),
               if let Some(target) = target {
-                format_subject(app, target)
+                format_subject(namer, target)
               } else {
                 "~unset~".to_string()
               },
@@ -263,10 +353,10 @@ fn format_explain_result(app: &App, result: &sapling_query_engine::ExplainResult
             lines.push(format!(
               "Fact{}: Operator {} {} {} => {}{}",
               constraint_id,
-              format_subject(app, actual),
-              format_subject(app, operator),
+              format_subject(namer, actual),
+              format_subject(namer, operator),
               if let Some(target) = target {
-                format_subject(app, target)
+                format_subject(namer, target)
               } else {
                 "~unset~".to_string()
               },
@@ -290,10 +380,10 @@ fn format_explain_result(app: &App, result: &sapling_query_engine::ExplainResult
             lines.push(format!(
               "Fact{}: Value {} {} {} => {}{}",
               constraint_id,
-              format_subject(app, actual),
-              format_subject(app, operator),
+              format_subject(namer, actual),
+              format_subject(namer, operator),
               if let Some(target) = target {
-                format_subject(app, target)
+                format_subject(namer, target)
               } else {
                 "~unset~".to_string()
               },
@@ -316,72 +406,66 @@ fn format_explain_result(app: &App, result: &sapling_query_engine::ExplainResult
 
   for (variable, value) in variables {
     lines.push(format!(
-      "Unification Variable {} = {}",
+      "Unification Variable {} = {}{}",
       variable,
-      format_subject(app, value)
+      format_subject(namer, value),
+      match result.variable_sources.get(variable) {
+        Some(source) => format!(" (bound by Fact {})", source.fact_index - fact_offset),
+        None => "".to_string(),
+      }
     ));
   }
 
   lines
 }
 
-fn format_subject(app: &App, subject: &Subject) -> String {
-  match subject {
-    Subject::Static { uuid } => {
-      let name = app
-        .query_once(&Query {
-          subject: subject.clone(),
-          property: Some(System::CORE_PROPERTY_SUBJECT_NAME),
-          meta: Some(System::CORE_META_INCLUDE),
-          evaluated: false,
-        })
-        .next();
-
-      if let Some(Subject::String { value }) = name.map(|fact| &fact.fact.value.subject) {
-        return value.clone();
-      }
+fn format_subject(namer: &impl SubjectNamer, subject: &Subject) -> String {
+  System::format_subject(subject, |subject| namer.name_of(subject))
+}
 
-      format!("static_{}", uuid)
-    }
-    Subject::Integer { value } => value.to_string(),
-    Subject::Float { value } => value.to_string(),
-    Subject::String { value } => format!("\"{}\"", value),
-  }
+/// Formats a duration in milliseconds with two decimals, e.g. `1.25ms`.
+fn format_duration(duration: Duration) -> String {
+  format!("{:.2}ms", duration.as_secs_f64() * 1000.0)
 }
 
-fn format_fact(app: &App, fact: &Fact) -> String {
+fn format_fact(namer: &impl SubjectNamer, fact: &Fact) -> String {
   let subject_str = if fact.subject.evaluated {
-    format!("?{}", format_subject(app, &fact.subject.subject))
+    format!("?{}", format_subject(namer, &fact.subject.subject))
   } else {
-    format_subject(app, &fact.subject.subject)
+    format_subject(namer, &fact.subject.subject)
   };
 
   let property_str = if fact.property.evaluated {
-    format!("?{}", format_subject(app, &fact.property.subject))
+    format!("?{}", format_subject(namer, &fact.property.subject))
   } else {
-    format_subject(app, &fact.property.subject)
+    format_subject(namer, &fact.property.subject)
   };
 
   let mut value_str = if fact.value.evaluated {
-    format!("?{}", format_subject(app, &fact.value.subject))
+    format!("?{}", format_subject(namer, &fact.value.subject))
   } else {
-    format_subject(app, &fact.value.subject)
+    format_subject(namer, &fact.value.subject)
   };
 
   if let Some(value_property) = &fact.value.property {
-    value_str += &format!("/{}", format_subject(app, value_property));
+    value_str += &format!("/{}", format_subject(namer, value_property));
   }
 
   format!(
     "{}/{} {} {}",
     subject_str,
     property_str,
-    format_subject(app, &fact.operator),
+    format_subject(namer, &fact.operator),
     value_str
   )
 }
 
-fn update_test_file(file_path: &Path, old_lines: &[String], new_lines: &[String]) -> Result<()> {
+fn update_test_file(
+  file_path: &Path,
+  query_line: usize,
+  old_lines: &[String],
+  new_lines: &[String],
+) -> Result<isize> {
   let content = fs::read_to_string(file_path)
     .with_context(|| format!("Failed to read file: {:?}", file_path))?;
 
@@ -394,6 +478,7 @@ fn update_test_file(file_path: &Path, old_lines: &[String], new_lines: &[String]
 
   // Find and replace the old expected output with the new actual output
   let mut updated_content = content.clone();
+  let mut line_delta = new_lines.len() as isize - old_lines.len() as isize;
 
   // Build the old expected section (>> prefixed lines)
   let old_section = old_lines
@@ -409,15 +494,34 @@ fn update_test_file(file_path: &Path, old_lines: &[String], new_lines: &[String]
     .collect::<Vec<_>>()
     .join(line_ending);
 
-  // Replace the old section with the new one
-  if old_section != new_section {
+  if old_section == new_section {
+    return Ok(0);
+  }
+
+  if old_lines.is_empty() {
+    // Replacing an empty section would match everywhere, instead insert the new lines right
+    // after the query line, replacing an explicit ">> (no results)" marker if present.
+    let mut lines = content.split(line_ending).collect::<Vec<_>>();
+    let insert_at = query_line.min(lines.len());
+    if lines
+      .get(insert_at)
+      .is_some_and(|line| line.trim().starts_with(">>"))
+    {
+      lines.remove(insert_at);
+      line_delta -= 1;
+    }
+    lines.insert(insert_at, &new_section);
+    updated_content = lines.join(line_ending);
+  } else {
+    // Replace the old section with the new one
     updated_content = updated_content.replace(&old_section, &new_section);
-    fs::write(file_path, updated_content)
-      .with_context(|| format!("Failed to write updated file: {:?}", file_path))?;
-    println!("  {}", "Updated test file".yellow().bold());
   }
 
-  Ok(())
+  fs::write(file_path, updated_content)
+    .with_context(|| format!("Failed to write updated file: {:?}", file_path))?;
+  report!("  {}", "Updated test file".yellow().bold());
+
+  Ok(line_delta)
 }
 
 fn update_explain_test_file(
@@ -457,27 +561,33 @@ fn update_explain_test_file(
     updated_content = updated_content.replace(&old_section, &new_section);
     fs::write(file_path, updated_content)
       .with_context(|| format!("Failed to write updated file: {:?}", file_path))?;
-    println!("  {}", "Updated test file".yellow().bold());
+    report!("  {}", "Updated test file".yellow().bold());
   }
 
   Ok(())
 }
 
-fn run_test(file_path: &Path, update_mode: bool) -> Result<bool> {
+fn run_test(file_path: &Path, options: &RunOptions) -> Result<FileReport> {
+  let update_mode = options.update_mode;
   let content = fs::read_to_string(file_path)
     .with_context(|| format!("Failed to read file: {:?}", file_path))?;
 
-  let mut registry = SubjectRegistry::new();
+  let mut registry = SubjectRegistry::new(options.bank_size);
   let test_case = registry
     .parse_test_case(&content)
     .with_context(|| format!("Failed to parse test case: {:?}", file_path))?;
 
+  let names = registry.subject_names();
   let (mut app, mut fact_identifiers) = registry.into_database();
 
   let mut success = true;
+  let mut failures = Vec::new();
   let mut query_count = 0;
+  let mut total_query_time = Duration::ZERO;
+  // Updating the file shifts the lines of all following queries
+  let mut line_offset: isize = 0;
 
-  println!("{}", format!("Running test: {:?}", file_path).blue().bold());
+  report!("{}", format!("Running test: {:?}", file_path).blue().bold());
 
   for line in test_case.lines {
     match line {
@@ -494,101 +604,139 @@ fn run_test(file_path: &Path, update_mode: bool) -> Result<bool> {
 
         query_count += 1;
 
-        println!(
-          "  {} {} {}{}{}",
+        let data_query = Query {
+          evaluated: query.subject_evaluated,
+          meta: None,
+          property: query.property.clone(),
+          subject: query.subject.clone(),
+        };
+        let query_label = System::get_human_readable_query(app.get_raw_database(), &data_query);
+        report!(
+          "  {} {} {}",
           "Query".green().bold(),
           query_count,
-          if query.subject_evaluated { "?" } else { "" },
-          format_subject(&app, &query.subject),
-          match &query.property {
-            Some(subject) => format!("/{}", format_subject(&app, subject)),
-            None => "".to_string(),
-          }
+          query_label
         );
 
-        let actual_facts: Vec<FoundFact> = app
-          .query_once(&Query {
-            evaluated: query.subject_evaluated,
-            meta: None,
-            property: query.property.clone(),
-            subject: query.subject.clone(),
+        let started = Instant::now();
+        let actual_facts: Vec<FoundFact> = app.query_once(&data_query).collect();
+        let elapsed = started.elapsed();
+        total_query_time += elapsed;
+
+        let expected_lines: Vec<String> = query
+          .expected_facts
+          .iter()
+          .map(|expected| {
+            let fact_str = format_fact(&names, &expected.fact);
+            if let Some(subject_mapping) = &expected.subject_mapping {
+              format!(
+                "{} ;; subject={}",
+                fact_str,
+                format_subject(&names, subject_mapping)
+              )
+            } else {
+              fact_str
+            }
+          })
+          .collect();
+        let actual_lines: Vec<String> = actual_facts
+          .iter()
+          .map(|found_fact| {
+            let fact_str = format_fact(&names, found_fact.fact);
+            if let Some(subject_binding) = &found_fact.subject_binding {
+              format!(
+                "{} ;; subject={}",
+                fact_str,
+                format_subject(&names, subject_binding)
+              )
+            } else {
+              fact_str
+            }
           })
           .collect();
 
-        println!(
+        report!(
           "  {} ({} facts)",
           "Expected:".yellow(),
           query.expected_facts.len()
         );
-        for expected in &query.expected_facts {
-          let fact_str = format_fact(&app, &expected.fact);
-          if let Some(subject_mapping) = &expected.subject_mapping {
-            println!(
-              "    {} ;; subject={}",
-              fact_str,
-              format_subject(&app, subject_mapping),
-            );
-          } else {
-            println!("    {}", fact_str);
-          }
+        for line in &expected_lines {
+          report!("    {}", line);
         }
 
-        println!("  {} ({} facts)", "Actual:".cyan(), actual_facts.len());
-        for found_fact in &actual_facts {
-          let fact_str = format_fact(&app, found_fact.fact);
-          if let Some(subject_binding) = &found_fact.subject_binding {
-            println!(
-              "    {} ;; subject={}",
-              fact_str,
-              format_subject(&app, subject_binding)
-            );
-          } else {
-            println!("    {}", fact_str);
-          }
+        if options.timings {
+          report!(
+            "  {} ({} facts) {}",
+            "Actual:".cyan(),
+            actual_facts.len(),
+            format!(
+              "(collected {} facts in {})",
+              actual_facts.len(),
+              format_duration(elapsed)
+            )
+            .dimmed()
+          );
+        } else {
+          report!("  {} ({} facts)", "Actual:".cyan(), actual_facts.len());
+        }
+        for line in &actual_lines {
+          report!("    {}", line);
         }
 
         // Compare expected vs actual
-        if actual_facts.len() != query.expected_facts.len() {
-          println!("  {}", "FAIL: Different number of facts".red().bold());
+        if let Some(reason) = &query.expect_error {
+          // Without a reason only the empty result is checked
+          let reasons = rejection_reasons(&app.explain_rule_once(&data_query));
+          let failure = if !actual_facts.is_empty() {
+            Some(format!(
+              "FAIL: Expected error '{}' but the query yielded facts",
+              reason
+            ))
+          } else if !reason.is_empty() && !reasons.iter().any(|actual| actual.starts_with(reason)) {
+            Some(format!(
+              "FAIL: Expected error '{}' but explain rejected with: {}",
+              reason,
+              if reasons.is_empty() {
+                "-".to_string()
+              } else {
+                reasons.join(", ")
+              }
+            ))
+          } else {
+            None
+          };
+
+          if let Some(failure) = failure {
+            report!("  {}", failure.red().bold());
+            success = false;
+            failures.push(QueryFailure {
+              query: query_label.clone(),
+              expected: expected_lines.clone(),
+              actual: actual_lines.clone(),
+            });
+          } else {
+            report!(
+              "  {} {}",
+              "PASS".green().bold(),
+              format!("(expected error: {})", reason).dimmed()
+            );
+          }
+        } else if actual_facts.len() != query.expected_facts.len() {
+          report!("  {}", "FAIL: Different number of facts".red().bold());
           success = false;
+          failures.push(QueryFailure {
+            query: query_label.clone(),
+            expected: expected_lines.clone(),
+            actual: actual_lines.clone(),
+          });
 
           if update_mode {
-            // Build expected output lines from query
-            let old_lines: Vec<String> = query
-              .expected_facts
-              .iter()
-              .map(|expected| {
-                let fact_str = format_fact(&app, &expected.fact);
-                if let Some(subject_mapping) = &expected.subject_mapping {
-                  format!(
-                    "{} ;; subject={}",
-                    fact_str,
-                    format_subject(&app, subject_mapping)
-                  )
-                } else {
-                  fact_str
-                }
-              })
-              .collect();
-
-            // Build actual output lines
-            let new_lines: Vec<String> = actual_facts
-              .iter()
-              .map(|found_fact| {
-                let fact_str = format_fact(&app, found_fact.fact);
-                if let Some(subject_binding) = &found_fact.subject_binding {
-                  format!(
-                    "{} ;; subject={}",
-                    fact_str,
-                    format_subject(&app, subject_binding)
-                  )
-                } else {
-                  fact_str
-                }
-              })
-              .collect();
-
-            update_test_file(file_path, &old_lines, &new_lines)?;
+            line_offset += update_test_file(
+              file_path,
+              query.line.saturating_add_signed(line_offset),
+              &expected_lines,
+              &actual_lines,
+            )?;
           }
         } else {
           let mut matches = true;
@@ -607,7 +755,8 @@ fn run_test(file_path: &Path, update_mode: bool) -> Result<bool> {
               }
 
               // Check if the facts match
-              let facts_match = format_fact(&app, actual.fact) == format_fact(&app, &expected.fact);
+              let facts_match =
+                format_fact(&names, actual.fact) == format_fact(&names, &expected.fact);
 
               if !facts_match {
                 continue;
@@ -617,21 +766,21 @@ fn run_test(file_path: &Path, update_mode: bool) -> Result<bool> {
               let mapping_matches = match (&expected.subject_mapping, &actual.subject_binding) {
                 (None, None) => true,
                 (Some(expected_subj), Some(actual_subj)) => {
-                  format_subject(&app, expected_subj) == format_subject(&app, actual_subj)
+                  format_subject(&names, expected_subj) == format_subject(&names, actual_subj)
                 }
                 (None, Some(actual_subj)) => {
                   failure_reasons.push(format!(
                     "Fact '{}': Subject mapping was not expected but got: {}",
-                    format_fact(&app, &expected.fact),
-                    format_subject(&app, actual_subj)
+                    format_fact(&names, &expected.fact),
+                    format_subject(&names, actual_subj)
                   ));
                   false
                 }
                 (Some(expected_subj), None) => {
                   failure_reasons.push(format!(
                     "Fact '{}': Expected subject mapping '{}' but got None",
-                    format_fact(&app, &expected.fact),
-                    format_subject(&app, expected_subj)
+                    format_fact(&names, &expected.fact),
+                    format_subject(&names, expected_subj)
                   ));
                   false
                 }
@@ -649,9 +798,9 @@ fn run_test(file_path: &Path, update_mode: bool) -> Result<bool> {
               if failure_reasons.is_empty() {
                 failure_reasons.push(format!(
                   "No matching fact found for: {}{}",
-                  format_fact(&app, &expected.fact),
+                  format_fact(&names, &expected.fact),
                   if let Some(subj) = &expected.subject_mapping {
-                    format!(" ;; subject={}", format_subject(&app, subj))
+                    format!(" ;; subject={}", format_subject(&names, subj))
                   } else {
                     String::new()
                   }
@@ -662,58 +811,33 @@ fn run_test(file_path: &Path, update_mode: bool) -> Result<bool> {
           }
 
           if matches {
-            println!("  {}", "PASS".green().bold());
+            report!("  {}", "PASS".green().bold());
           } else {
             for reason in &failure_reasons {
-              println!("  {}", format!("FAIL: {}", reason).red().bold());
+              report!("  {}", format!("FAIL: {}", reason).red().bold());
             }
             if failure_reasons.is_empty() {
-              println!("  {}", "FAIL: Facts don't match".red().bold());
+              report!("  {}", "FAIL: Facts don't match".red().bold());
             }
             success = false;
+            failures.push(QueryFailure {
+              query: query_label.clone(),
+              expected: expected_lines.clone(),
+              actual: actual_lines.clone(),
+            });
 
             if update_mode {
-              // Build expected output lines from query
-              let old_lines: Vec<String> = query
-                .expected_facts
-                .iter()
-                .map(|expected| {
-                  let fact_str = format_fact(&app, &expected.fact);
-                  if let Some(subject_mapping) = &expected.subject_mapping {
-                    format!(
-                      "{} ;; subject={}",
-                      fact_str,
-                      format_subject(&app, subject_mapping)
-                    )
-                  } else {
-                    fact_str
-                  }
-                })
-                .collect();
-
-              // Build actual output lines
-              let new_lines: Vec<String> = actual_facts
-                .iter()
-                .map(|found_fact| {
-                  let fact_str = format_fact(&app, found_fact.fact);
-                  if let Some(subject_binding) = &found_fact.subject_binding {
-                    format!(
-                      "{} ;; subject={}",
-                      fact_str,
-                      format_subject(&app, subject_binding)
-                    )
-                  } else {
-                    fact_str
-                  }
-                })
-                .collect();
-
-              update_test_file(file_path, &old_lines, &new_lines)?;
+              line_offset += update_test_file(
+                file_path,
+                query.line.saturating_add_signed(line_offset),
+                &expected_lines,
+                &actual_lines,
+              )?;
             }
           }
         }
 
-        println!();
+        report!();
       }
       TestLine::ExplainQuery(_explain_query) => {
         // Explain queries are ignored in regular tests
@@ -721,24 +845,46 @@ fn run_test(file_path: &Path, update_mode: bool) -> Result<bool> {
     }
   }
 
-  Ok(success)
+  if options.timings {
+    report!(
+      "  {}",
+      format!(
+        "{} queries took {} in total",
+        query_count,
+        format_duration(total_query_time)
+      )
+      .dimmed()
+    );
+  }
+
+  Ok(FileReport {
+    path: file_path.display().to_string(),
+    passed: success,
+    queries: query_count,
+    failures,
+    error: None,
+  })
 }
 
-fn run_explain_test(file_path: &Path, update_mode: bool) -> Result<bool> {
+fn run_explain_test(file_path: &Path, options: &RunOptions) -> Result<FileReport> {
+  let update_mode = options.update_mode;
   let content = fs::read_to_string(file_path)
     .with_context(|| format!("Failed to read file: {:?}", file_path))?;
 
-  let mut registry = SubjectRegistry::new();
+  let mut registry = SubjectRegistry::new(options.bank_size);
   let test_case = registry
     .parse_test_case(&content)
     .with_context(|| format!("Failed to parse test case: {:?}", file_path))?;
 
+  let names = registry.subject_names();
+  let fact_offset = registry.explain_fact_offset();
   let (mut app, mut fact_identifiers) = registry.into_database();
 
   let mut success = true;
+  let mut failures = Vec::new();
   let mut explain_count = 0;
 
-  println!(
+  report!(
     "{}",
     format!("Running explain test: {:?}", file_path)
       .blue()
@@ -762,53 +908,91 @@ fn run_explain_test(file_path: &Path, update_mode: bool) -> Result<bool> {
 
         explain_count += 1;
 
-        println!(
+        report!(
           "  {} {} {}",
           "Explain".green().bold(),
           explain_count,
-          format_subject(&app, &explain_query.subject),
+          format_subject(&names, &explain_query.subject),
         );
 
         // Call the explain function
-        let explain_result = app.explain_once(&explain_query.subject);
+        let explain_result = match &explain_query.property {
+          Some(property) => app.explain_query_once(&Query {
+            subject: explain_query.subject.clone(),
+            property: Some(property.clone()),
+            meta: None,
+            evaluated: false,
+          }),
+          None => app.explain_once(&explain_query.subject),
+        };
 
         // Format the result into lines
-        let actual_lines = format_explain_result(&app, &explain_result);
+        let actual_lines = format_explain_result(&app, &names, &explain_result, fact_offset);
+
+        if let Some(markdown_dir) = &options.explain_markdown_dir {
+          let file_stem = file_path
+            .file_stem()
+            .and_then(|n| n.to_str())
+            .unwrap_or("explain");
+          let markdown_path = markdown_dir.join(format!("{}_{}.md", file_stem, explain_count));
+          let markdown = format_explain_result_markdown(
+            &app,
+            &names,
+            &format_subject(&names, &explain_query.subject),
+            &explain_result,
+            fact_offset,
+          );
+          fs::create_dir_all(markdown_dir)
+            .with_context(|| format!("Failed to create directory: {:?}", markdown_dir))?;
+          fs::write(&markdown_path, markdown)
+            .with_context(|| format!("Failed to write markdown file: {:?}", markdown_path))?;
+        }
 
-        println!(
+        report!(
           "  {} ({} lines)",
           "Expected:".yellow(),
           explain_query.expected_lines.len()
         );
         for line in &explain_query.expected_lines {
-          println!("    {}", line);
+          report!("    {}", line);
         }
 
-        println!("  {} ({} lines)", "Actual:".cyan(), actual_lines.len());
+        report!("  {} ({} lines)", "Actual:".cyan(), actual_lines.len());
         for line in &actual_lines {
-          println!("    {}", line);
+          report!("    {}", line);
         }
 
         // Compare expected vs actual
         if explain_query.expected_lines == actual_lines {
-          println!("  {}", "PASS".green().bold());
+          report!("  {}", "PASS".green().bold());
         } else {
-          println!("  {}", "FAIL: Output doesn't match".red().bold());
-          println!();
+          report!("  {}", "FAIL: Output doesn't match".red().bold());
+          report!();
           print_diff(&explain_query.expected_lines, &actual_lines);
           success = false;
+          failures.push(QueryFailure {
+            query: format_subject(&names, &explain_query.subject),
+            expected: explain_query.expected_lines.clone(),
+            actual: actual_lines.clone(),
+          });
 
           if update_mode {
             update_explain_test_file(file_path, &explain_query.expected_lines, &actual_lines)?;
           }
         }
 
-        println!();
+        report!();
       }
     }
   }
 
-  Ok(success)
+  Ok(FileReport {
+    path: file_path.display().to_string(),
+    passed: success,
+    queries: explain_count,
+    failures,
+    error: None,
+  })
 }
 
 fn collect_spec_files(
@@ -841,17 +1025,26 @@ fn collect_spec_files(
   Ok((all_spec_files, only_files))
 }
 
+/// Whether the file name of `path` contains `filter`, ignoring case.
+fn matches_filter(path: &Path, filter: &str) -> bool {
+  let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+  file_name.to_lowercase().contains(&filter.to_lowercase())
+}
+
+fn count_passed(reports: &[FileReport]) -> usize {
+  reports.iter().filter(|report| report.passed).count()
+}
+
 fn run_validation_suite(
   dir_path: &Path,
-  test_runner: fn(&Path, bool) -> Result<bool>,
+  test_runner: fn(&Path, &RunOptions) -> Result<FileReport>,
   global_only_files: &[std::path::PathBuf],
-  update_mode: bool,
-) -> Result<(usize, usize)> {
-  let mut total_tests = 0;
-  let mut passed_tests = 0;
+  options: &RunOptions,
+) -> Result<Vec<FileReport>> {
+  let mut reports = Vec::new();
 
   if !dir_path.exists() {
-    return Ok((0, 0));
+    return Ok(reports);
   }
 
   // Collect all .txt files from this directory
@@ -877,42 +1070,78 @@ fn run_validation_suite(
       .collect()
   };
 
+  let files_to_run = match &options.filter {
+    Some(filter) => {
+      let matching: Vec<_> = files_to_run
+        .into_iter()
+        .filter(|path| matches_filter(path, filter))
+        .collect();
+      report!(
+        "{}",
+        format!(
+          "{} file(s) in {:?} matched filter '{}'",
+          matching.len(),
+          dir_path,
+          filter
+        )
+        .dimmed()
+      );
+      matching
+    }
+    None => files_to_run,
+  };
+
   // Run the selected test files
   for path in files_to_run {
     let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
 
     // Skip .skip.txt files (in case they were included via .only.txt logic)
     if file_name.ends_with(".skip.txt") {
-      println!("{}", format!("Skipping: {:?}", path).yellow());
+      report!("{}", format!("Skipping: {:?}", path).yellow());
       continue;
     }
 
-    total_tests += 1;
-    match test_runner(&path, update_mode) {
-      Ok(true) => {
-        passed_tests += 1;
-        println!("{}", "✓ PASSED".green().bold());
-      }
-      Ok(false) => {
-        println!("{}", "✗ FAILED".red().bold());
+    let file_report = match test_runner(&path, options) {
+      Ok(file_report) => {
+        if file_report.passed {
+          report!("{}", "✓ PASSED".green().bold());
+        } else {
+          report!("{}", "✗ FAILED".red().bold());
+        }
+        file_report
       }
       Err(e) => {
-        println!("{}: {:?}", "✗ ERROR".red().bold(), e);
+        report!("{}: {:?}", "✗ ERROR".red().bold(), e);
+        FileReport {
+          path: path.display().to_string(),
+          error: Some(format!("{:?}", e)),
+          ..Default::default()
+        }
       }
-    }
-    println!("{}", "─".repeat(60));
+    };
+    reports.push(file_report);
+    report!("{}", "─".repeat(60));
   }
 
-  Ok((total_tests, passed_tests))
+  Ok(reports)
 }
 
 fn main() -> Result<()> {
   let args = Args::parse();
   let update_mode = args.update;
+  let options = RunOptions {
+    update_mode,
+    explain_markdown_dir: args.explain_markdown,
+    bank_size: args.bank_size,
+    filter: args.filter,
+    timings: args.timings,
+    format: args.format,
+  };
+  report::set_output_format(options.format);
 
   if update_mode {
-    println!("{}", "\n=== UPDATE MODE ENABLED ===".yellow().bold());
-    println!(
+    report!("{}", "\n=== UPDATE MODE ENABLED ===".yellow().bold());
+    report!(
       "{}",
       "Test files will be updated with actual output when differences are found.\n".yellow()
     );
@@ -931,7 +1160,7 @@ fn main() -> Result<()> {
 
   // Print message if .only.txt files were found
   if !global_only_files.is_empty() {
-    println!(
+    report!(
       "{}",
       format!(
         "\nFound {} .only.txt file(s), running only those and skipping all other tests:\n",
@@ -941,20 +1170,21 @@ fn main() -> Result<()> {
       .bold()
     );
     for file in &global_only_files {
-      println!("  - {:?}", file);
+      report!("  - {:?}", file);
     }
-    println!();
+    report!();
   }
 
   // Run normal spec validation
-  println!("\n{}\n", "=== Running Spec Validation ===".blue().bold());
-  let (total_spec_tests, passed_spec_tests) =
-    run_validation_suite(spec_dir, run_test, &global_only_files, update_mode)?;
+  report!("\n{}\n", "=== Running Spec Validation ===".blue().bold());
+  let spec_reports = run_validation_suite(spec_dir, run_test, &global_only_files, &options)?;
+  let total_spec_tests = spec_reports.len();
+  let passed_spec_tests = count_passed(&spec_reports);
 
   if total_spec_tests == 0 {
-    println!("No spec tests found in {:?}", spec_dir);
+    report!("No spec tests found in {:?}", spec_dir);
   } else {
-    println!(
+    report!(
       "\n{}",
       format!(
         "Spec Results: {}/{} tests passed",
@@ -966,21 +1196,23 @@ fn main() -> Result<()> {
   }
 
   // Run explain spec validation
-  println!(
+  report!(
     "\n{}\n",
     "=== Running Explain Spec Validation ===".blue().bold()
   );
-  let (total_explain_tests, passed_explain_tests) = run_validation_suite(
+  let explain_reports = run_validation_suite(
     spec_explain_dir,
     run_explain_test,
     &global_only_files,
-    update_mode,
+    &options,
   )?;
+  let total_explain_tests = explain_reports.len();
+  let passed_explain_tests = count_passed(&explain_reports);
 
   if total_explain_tests == 0 {
-    println!("No explain spec tests found in {:?}", spec_explain_dir);
+    report!("No explain spec tests found in {:?}", spec_explain_dir);
   } else {
-    println!(
+    report!(
       "\n{}",
       format!(
         "Explain Spec Results: {}/{} tests passed",
@@ -995,8 +1227,18 @@ fn main() -> Result<()> {
   let total_tests = total_spec_tests + total_explain_tests;
   let passed_tests = passed_spec_tests + passed_explain_tests;
 
-  println!("\n{}", "=== Overall Summary ===".blue().bold());
-  println!(
+  if options.format == OutputFormat::Json {
+    let suite_report = SuiteReport {
+      total: total_tests,
+      passed: passed_tests,
+      files: spec_reports.into_iter().chain(explain_reports).collect(),
+    };
+    println!("{}", serde_json::to_string_pretty(&suite_report)?);
+    return Ok(());
+  }
+
+  report!("\n{}", "=== Overall Summary ===".blue().bold());
+  report!(
     "{}",
     format!("Total: {}/{} tests passed", passed_tests, total_tests)
       .blue()
@@ -1005,9 +1247,9 @@ fn main() -> Result<()> {
 
   if total_tests > 0 {
     if passed_tests == total_tests {
-      println!("{}", "All tests passed!".green().bold());
+      report!("{}", "All tests passed!".green().bold());
     } else {
-      println!(
+      report!(
         "{}",
         format!("{} tests failed", total_tests - passed_tests)
           .red()
@@ -1018,3 +1260,206 @@ fn main() -> Result<()> {
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_format_duration() {
+    assert_eq!(format_duration(Duration::ZERO), "0.00ms");
+    assert_eq!(format_duration(Duration::from_micros(1250)), "1.25ms");
+    assert_eq!(format_duration(Duration::from_secs(2)), "2000.00ms");
+  }
+
+  #[test]
+  fn test_matches_filter() {
+    let path = Path::new("spec/04_Interop.only.txt");
+    assert!(matches_filter(path, "interop"));
+    assert!(matches_filter(path, "04_INTEROP"));
+    assert!(!matches_filter(path, "unification"));
+    // Only the file name is matched, not the directory
+    assert!(!matches_filter(path, "spec/"));
+  }
+
+  /// Unique per test and process, so parallel tests and concurrent runs don't share files.
+  fn temp_spec_path(test_name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+      "spec_validator_{}_{}.txt",
+      std::process::id(),
+      test_name
+    ))
+  }
+
+  #[test]
+  fn test_expect_error_fails_on_results() {
+    let file_path = temp_spec_path("expect_error");
+    fs::write(&file_path, "data1/value = 1\n\n> data1 ;; expect-error\n").unwrap();
+
+    let options = RunOptions {
+      update_mode: false,
+      explain_markdown_dir: None,
+      bank_size: MEMORY_BANK_SIZE,
+      filter: None,
+      timings: false,
+      format: OutputFormat::Text,
+    };
+    assert!(!run_test(&file_path, &options).unwrap().passed);
+
+    fs::write(
+      &file_path,
+      "data1/value = 1\n\nrule/value == 2\n\n> ?rule ;; expect-error max depth exceeded\n",
+    )
+    .unwrap();
+    assert!(!run_test(&file_path, &options).unwrap().passed);
+
+    fs::write(
+      &file_path,
+      "data1/value = 1\n\nrule/value == 2\n\n> ?rule ;; expect-error not found\n",
+    )
+    .unwrap();
+    assert!(run_test(&file_path, &options).unwrap().passed);
+
+    fs::write(
+      &file_path,
+      "data1/value = 1\n\n> ?data1 ;; expect-error\n>> data1/value = 1\n",
+    )
+    .unwrap();
+    assert!(run_test(&file_path, &options).is_err());
+  }
+
+  #[test]
+  fn test_json_report() {
+    let file_path = temp_spec_path("json_report");
+    fs::write(
+      &file_path,
+      "data1/value = 1\n\n> data1\n>> data1/value = 2\n\n> data1\n>> data1/value = 1\n",
+    )
+    .unwrap();
+
+    let options = RunOptions {
+      update_mode: false,
+      explain_markdown_dir: None,
+      bank_size: MEMORY_BANK_SIZE,
+      filter: None,
+      timings: false,
+      format: OutputFormat::Json,
+    };
+    let file_report = run_test(&file_path, &options).unwrap();
+    let suite_report = SuiteReport {
+      total: 1,
+      passed: count_passed(std::slice::from_ref(&file_report)),
+      files: vec![file_report],
+    };
+
+    let json: serde_json::Value =
+      serde_json::from_str(&serde_json::to_string(&suite_report).unwrap()).unwrap();
+    assert_eq!(json["total"], 1);
+    assert_eq!(json["passed"], 0);
+    let file = &json["files"][0];
+    assert_eq!(file["path"], file_path.display().to_string());
+    assert_eq!(file["passed"], false);
+    assert_eq!(file["queries"], 2);
+    assert!(file["error"].is_null());
+    assert_eq!(file["failures"].as_array().unwrap().len(), 1);
+    assert_eq!(file["failures"][0]["query"], "data1");
+    assert_eq!(file["failures"][0]["expected"][0], "data1/value = 2");
+    assert_eq!(file["failures"][0]["actual"][0], "data1/value = 1");
+  }
+
+  #[test]
+  fn test_boolean_subjects() {
+    let file_path = temp_spec_path("boolean_subjects");
+    fs::write(
+      &file_path,
+      "data1/enabled = true\ndata2/enabled = false\nquery/enabled == true\n\n> ?query\n>> data1/enabled = true\n",
+    )
+    .unwrap();
+
+    let options = RunOptions {
+      update_mode: false,
+      explain_markdown_dir: None,
+      bank_size: MEMORY_BANK_SIZE,
+      filter: None,
+      timings: false,
+      format: OutputFormat::Text,
+    };
+    assert!(run_test(&file_path, &options).unwrap().passed);
+
+    fs::remove_file(&file_path).unwrap();
+  }
+
+  #[test]
+  fn test_update_inserts_missing_expected_facts() {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/update_empty_expected.txt");
+    let file_path = temp_spec_path("update_empty_expected");
+    fs::copy(&fixture, &file_path).unwrap();
+
+    let update_options = RunOptions {
+      update_mode: true,
+      explain_markdown_dir: None,
+      bank_size: MEMORY_BANK_SIZE,
+      filter: None,
+      timings: false,
+      format: OutputFormat::Text,
+    };
+    let check_options = RunOptions {
+      update_mode: false,
+      explain_markdown_dir: None,
+      bank_size: MEMORY_BANK_SIZE,
+      filter: None,
+      timings: false,
+      format: OutputFormat::Text,
+    };
+    assert!(!run_test(&file_path, &update_options).unwrap().passed);
+    assert!(run_test(&file_path, &check_options).unwrap().passed);
+
+    let content = fs::read_to_string(&file_path).unwrap();
+    assert_eq!(
+      content,
+      "foo/name = 'foo'\nfoo/age = 42\n\n> foo\n>> foo/name = \"foo\"\n>> foo/age = 42\n\n> foo/age\n>> foo/age = 42\n"
+    );
+
+    fs::remove_file(&file_path).unwrap();
+  }
+
+  #[test]
+  fn test_explain_markdown_golden() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+    let content = fs::read_to_string(fixtures.join("explain_markdown.txt")).unwrap();
+    let golden = fs::read_to_string(fixtures.join("explain_markdown.md")).unwrap();
+
+    let mut registry = SubjectRegistry::new(MEMORY_BANK_SIZE);
+    let test_case = registry.parse_test_case(&content).unwrap();
+    let names = registry.subject_names();
+    let fact_offset = registry.explain_fact_offset();
+    let (mut app, mut fact_identifiers) = registry.into_database();
+
+    let mut markdown = None;
+    for line in test_case.lines {
+      match line {
+        TestLine::Fact(fact, fact_identifier) => {
+          let fact_id = app.add_fact(fact);
+          if let Some(identifier) = fact_identifier {
+            fact_identifiers.insert(identifier, fact_id);
+          }
+        }
+        // Without expected `#>` lines the explain query is parsed as a regular query
+        TestLine::Query(query) => {
+          resolve_fact_references(&mut app, &fact_identifiers);
+          let explain_result = app.explain_once(&query.subject);
+          markdown = Some(format_explain_result_markdown(
+            &app,
+            &names,
+            &format_subject(&names, &query.subject),
+            &explain_result,
+            fact_offset,
+          ));
+        }
+        TestLine::ExplainQuery(_) => {}
+      }
+    }
+
+    assert_eq!(markdown.unwrap(), golden);
+  }
+}
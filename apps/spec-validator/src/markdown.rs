@@ -0,0 +1,227 @@
+use sapling_app::App;
+use sapling_query_engine::{
+  EvaluationType, ExplainConstraintEvaluation, ExplainConstraintEvaluationOutcome,
+  ExplainFactEvent, ExplainResult,
+};
+
+use crate::{format_fact, format_subject, naming::SubjectNamer};
+
+/// Renders an explain result as a markdown document, meant for sharing debugging sessions
+/// rather than for diffing like `format_explain_result`.
+pub fn format_explain_result_markdown(
+  app: &App,
+  namer: &impl SubjectNamer,
+  title: &str,
+  result: &ExplainResult,
+  fact_offset: usize,
+) -> String {
+  let database = app.get_raw_database();
+  let mut lines = Vec::new();
+
+  lines.push(format!("# Explain `{}`", title));
+  lines.push(String::new());
+
+  // Constraints
+  lines.push("## Constraints".to_string());
+  lines.push(String::new());
+  if result.constraints.is_empty() {
+    lines.push("_No constraints_".to_string());
+  } else {
+    lines.push("| # | Constraint | Fact |".to_string());
+    lines.push("|---|---|---|".to_string());
+    for (idx, (constraint_id, fact_id)) in result.constraints.iter().enumerate() {
+      let fact_str = database
+        .get_fact(*fact_id)
+        .map(|fact| format_fact(namer, fact))
+        .unwrap_or_else(|| "unknown".to_string());
+      lines.push(format!(
+        "| {} | {} | {} |",
+        idx,
+        constraint_id,
+        code_cell(&fact_str)
+      ));
+    }
+  }
+  lines.push(String::new());
+
+  // Subject
+  lines.push("## Subject".to_string());
+  lines.push(String::new());
+  match &result.subject {
+    Some(subject) => lines.push(code_cell(&format_subject(namer, subject))),
+    None => lines.push("_No subject_".to_string()),
+  }
+  lines.push(String::new());
+
+  // Failures are rare, the section is left out without any
+  if !result.failures.is_empty() {
+    lines.push("## Engine failures".to_string());
+    lines.push(String::new());
+    lines.push("| Subject | Failure |".to_string());
+    lines.push("|---|---|".to_string());
+    for failure in &result.failures {
+      lines.push(format!(
+        "| {} | {} |",
+        code_cell(&format_subject(namer, &failure.subject)),
+        failure.message
+      ));
+    }
+    lines.push(String::new());
+  }
+
+  // Fact events
+  lines.push("## Fact events".to_string());
+  lines.push(String::new());
+  if result.fact_events.is_empty() {
+    lines.push("_No fact events_".to_string());
+  } else {
+    lines.push("| Fact | Event | Details | Outcome |".to_string());
+    lines.push("|---|---|---|---|".to_string());
+    for event in &result.fact_events {
+      lines.push(format_fact_event_row(app, namer, event, fact_offset));
+    }
+  }
+  lines.push(String::new());
+
+  // Variables
+  lines.push("## Unification variables".to_string());
+  lines.push(String::new());
+  if result.variables.is_empty() {
+    lines.push("_No unification variables_".to_string());
+  } else {
+    let mut variables = result.variables.iter().collect::<Vec<_>>();
+    variables.sort_by_key(|var| var.0);
+
+    lines.push("| Variable | Value |".to_string());
+    lines.push("|---|---|".to_string());
+    for (variable, value) in variables {
+      lines.push(format!(
+        "| {} | {} |",
+        code_cell(variable),
+        code_cell(&format_subject(namer, value))
+      ));
+    }
+  }
+
+  lines.push(String::new());
+  lines.join("\n")
+}
+
+fn format_fact_event_row(
+  app: &App,
+  namer: &impl SubjectNamer,
+  event: &ExplainFactEvent,
+  fact_offset: usize,
+) -> String {
+  let database = app.get_raw_database();
+  let format_fact_id = |fact_id: usize| {
+    let fact_str = database
+      .get_fact(fact_id)
+      .map(|fact| format_fact(namer, fact))
+      .unwrap_or_else(|| "unknown".to_string());
+    format!("{} {}", fact_id - fact_offset, code_cell(&fact_str))
+  };
+
+  match event {
+    ExplainFactEvent::EvaluatingExpectedFact {
+      constraint_id,
+      fact_id,
+    } => format!(
+      "| Fact{} | Evaluating | {} | |",
+      constraint_id,
+      format_fact_id(*fact_id)
+    ),
+    ExplainFactEvent::YieldingFact {
+      constraint_id,
+      fact_id,
+      subject_variable,
+    } => format!(
+      "| Fact{} | Yielded | {}{} | |",
+      constraint_id,
+      format_fact_id(*fact_id),
+      if let Some(subject_variable) = subject_variable {
+        format!(
+          " (subject: {})",
+          code_cell(&format_subject(namer, subject_variable))
+        )
+      } else {
+        String::new()
+      }
+    ),
+    ExplainFactEvent::EvaluatingSubQuery {
+      constraint_id,
+      target,
+      target_query,
+      outcome,
+    } => format!(
+      "| Fact{} | SubQuery | {} yields {} | {} |",
+      constraint_id,
+      code_cell(&format!("?{}", format_subject(namer, target_query))),
+      code_cell(&format_subject(namer, target)),
+      outcome_badge(outcome)
+    ),
+    ExplainFactEvent::EvaluatingConstraint {
+      constraint_id,
+      evaluation,
+      ty,
+      outcome,
+    } => {
+      let (kind, target, actual, operator) = match evaluation {
+        ExplainConstraintEvaluation::Subject {
+          target,
+          actual,
+          operator,
+        } => ("Subject", target, actual, operator),
+        ExplainConstraintEvaluation::Property {
+          target,
+          actual,
+          operator,
+        } => ("Property", target, actual, operator),
+        ExplainConstraintEvaluation::Operator {
+          target,
+          actual,
+          operator,
+        } => ("Operator", target, actual, operator),
+        ExplainConstraintEvaluation::Value {
+          target,
+          actual,
+          operator,
+        } => ("Value", target, actual, operator),
+      };
+
+      format!(
+        "| Fact{} | {}{} | {} | {} |",
+        constraint_id,
+        kind,
+        if ty == &EvaluationType::Unification {
+          " (unification)"
+        } else {
+          ""
+        },
+        code_cell(&format!(
+          "{} {} {}",
+          format_subject(namer, actual),
+          format_subject(namer, operator),
+          if let Some(target) = target {
+            format_subject(namer, target)
+          } else {
+            "~unset~".to_string()
+          }
+        )),
+        outcome_badge(outcome)
+      )
+    }
+  }
+}
+
+fn outcome_badge(outcome: &ExplainConstraintEvaluationOutcome) -> &'static str {
+  match outcome {
+    ExplainConstraintEvaluationOutcome::Passed => "✅ PASS",
+    ExplainConstraintEvaluationOutcome::Rejected(..) => "❌ REJECTED",
+  }
+}
+
+/// Wraps the text in backticks, escaping pipes so it can't break the surrounding table.
+fn code_cell(text: &str) -> String {
+  format!("`{}`", text.replace('|', "\\|"))
+}
@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+
+use sapling_app::App;
+use sapling_data_model::{Query, Subject};
+use sapling_query_engine::{Database, System};
+
+/// Resolves the human readable name of a subject for formatting.
+pub trait SubjectNamer {
+  fn name_of(&self, subject: &Subject) -> Option<String>;
+}
+
+/// Resolves names by querying the app for the subject name property.
+impl SubjectNamer for App {
+  fn name_of(&self, subject: &Subject) -> Option<String> {
+    let name = self
+      .query_once(&Query {
+        subject: subject.clone(),
+        property: Some(System::CORE_PROPERTY_SUBJECT_NAME),
+        meta: Some(System::CORE_META_INCLUDE),
+        evaluated: false,
+      })
+      .next();
+
+    match name.map(|fact| &fact.fact.value.subject) {
+      Some(Subject::String { value }) => Some(value.clone()),
+      _ => None,
+    }
+  }
+}
+
+/// Snapshot of all subject names known to a database, avoids running a query per lookup.
+#[derive(Debug, Default)]
+pub struct SubjectNames {
+  names: BTreeMap<Subject, String>,
+}
+
+impl SubjectNames {
+  pub fn from_database(database: &Database) -> Self {
+    let mut names = BTreeMap::new();
+    for (_, fact) in database.iter_naive_facts() {
+      if !fact
+        .property
+        .subject
+        .is_same(&System::CORE_PROPERTY_SUBJECT_NAME)
+      {
+        continue;
+      }
+
+      // The first name wins, same as for the query based lookup
+      if let Subject::String { value } = &fact.value.subject {
+        names
+          .entry(fact.subject.subject.clone())
+          .or_insert_with(|| value.clone());
+      }
+    }
+
+    Self { names }
+  }
+}
+
+impl SubjectNamer for SubjectNames {
+  fn name_of(&self, subject: &Subject) -> Option<String> {
+    self.names.get(subject).cloned()
+  }
+}
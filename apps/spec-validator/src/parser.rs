@@ -7,6 +7,13 @@ use sapling_query_engine::{Database, System};
 use sapling_std::StandardLibrary;
 use std::collections::HashMap;
 
+use crate::naming::SubjectNames;
+
+/// Built-in facts of the app and the standard library when explain fixtures were first written,
+/// back then facts were numbered from a fixed offset.
+const ORIGINAL_BUILTIN_FACT_COUNT: usize = 14;
+const ORIGINAL_EXPLAIN_FACT_OFFSET: usize = 10;
+
 #[derive(Parser)]
 #[grammar = "grammar.pest"]
 pub struct SpecParser;
@@ -23,11 +30,18 @@ pub struct Query {
   pub subject_evaluated: bool,
   pub expected_facts: Vec<ExpectedFact>,
   pub property: Option<Subject>,
+  /// 1-based line number of the `>` query line in the spec file
+  pub line: usize,
+  /// Set by a `;; expect-error <reason>` marker, the query must not yield any facts and explain
+  /// has to reject it with the reason
+  pub expect_error: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct ExplainQuery {
   pub subject: Subject,
+  /// Set when the explain line is property-qualified, e.g. `> explainAlice/city`
+  pub property: Option<Subject>,
   pub expected_lines: Vec<String>,
 }
 
@@ -47,20 +61,30 @@ pub struct SubjectRegistry {
   static_subjects: HashMap<String, Subject>,
   fact_identifiers: HashMap<String, usize>,
   app: App,
+  builtin_fact_count: usize,
 }
 
 impl SubjectRegistry {
-  pub fn new() -> Self {
-    let mut app = App::new(128);
+  /// `bank_size` is the number of variables available to a single query.
+  pub fn new(bank_size: usize) -> Self {
+    let mut app = App::new(bank_size);
     app.add_plugin(StandardLibrary::default());
+    let builtin_fact_count = app.get_raw_database().fact_count();
 
     Self {
       static_subjects: HashMap::new(),
       fact_identifiers: HashMap::new(),
       app,
+      builtin_fact_count,
     }
   }
 
+  /// Offset subtracted from fact indices in explain output. Explain fixtures number facts as if
+  /// there were still the original built-in facts, so new built-in facts don't renumber them.
+  pub fn explain_fact_offset(&self) -> usize {
+    self.builtin_fact_count - (ORIGINAL_BUILTIN_FACT_COUNT - ORIGINAL_EXPLAIN_FACT_OFFSET)
+  }
+
   fn get_or_create_static_subject(&mut self, name: &str) -> Subject {
     let system_subject = self.app.get_global_by_name(name);
     if let Some(system_subject) = system_subject {
@@ -121,6 +145,9 @@ impl SubjectRegistry {
           value: name.to_string(),
         })
       }
+      Rule::boolean => Ok(Subject::Boolean {
+        value: pair.as_str() == "true",
+      }),
       Rule::integer => {
         let value = pair.as_str().parse::<i64>()?;
         Ok(Subject::Integer { value })
@@ -220,6 +247,21 @@ impl SubjectRegistry {
         Rule::equals_operator => {
           operator = System::CORE_OPERATOR_EQ.clone();
         }
+        Rule::not_equals_operator => {
+          operator = System::CORE_OPERATOR_NOT.clone();
+        }
+        Rule::less_operator => {
+          operator = System::CORE_OPERATOR_LT.clone();
+        }
+        Rule::greater_operator => {
+          operator = System::CORE_OPERATOR_GT.clone();
+        }
+        Rule::less_equal_operator => {
+          operator = System::CORE_OPERATOR_LE.clone();
+        }
+        Rule::greater_equal_operator => {
+          operator = System::CORE_OPERATOR_GE.clone();
+        }
         Rule::meta_list => {
           for meta_pair in inner_pair.into_inner() {
             if let Rule::meta_subject = meta_pair.as_rule() {
@@ -299,8 +341,10 @@ impl SubjectRegistry {
     let mut lines = Vec::new();
     let mut current_query_subject: Option<(Subject, bool)> = None;
     let mut current_query_property: Option<Subject> = None;
+    let mut current_query_line = 0;
+    let mut current_expect_error: Option<String> = None;
     let mut current_expected_facts = Vec::new();
-    let mut current_explain_subject: Option<Subject> = None;
+    let mut current_explain_subject: Option<(Subject, Option<Subject>)> = None;
     let mut current_expected_explain_lines = Vec::new();
 
     for pair in pairs {
@@ -319,12 +363,15 @@ impl SubjectRegistry {
                           subject_evaluated: evaluated,
                           expected_facts: current_expected_facts,
                           property: current_query_property.clone(),
+                          line: current_query_line,
+                          expect_error: current_expect_error.take(),
                         }));
                         current_expected_facts = Vec::new();
                       }
-                      if let Some(explain_subject) = current_explain_subject.take() {
+                      if let Some((explain_subject, property)) = current_explain_subject.take() {
                         lines.push(TestLine::ExplainQuery(ExplainQuery {
                           subject: explain_subject,
+                          property,
                           expected_lines: current_expected_explain_lines,
                         }));
                         current_expected_explain_lines = Vec::new();
@@ -342,26 +389,46 @@ impl SubjectRegistry {
                           subject_evaluated: evaluated,
                           expected_facts: current_expected_facts,
                           property: current_query_property.clone(),
+                          line: current_query_line,
+                          expect_error: current_expect_error.take(),
                         }));
                         current_expected_facts = Vec::new();
                       }
-                      if let Some(explain_subject) = current_explain_subject.take() {
+                      if let Some((explain_subject, property)) = current_explain_subject.take() {
                         lines.push(TestLine::ExplainQuery(ExplainQuery {
                           subject: explain_subject,
+                          property,
                           expected_lines: current_expected_explain_lines,
                         }));
                         current_expected_explain_lines = Vec::new();
                       }
 
+                      current_query_line = line_content.line_col().0;
                       for query_pair in line_content.into_inner() {
-                        if let Rule::subject_selector = query_pair.as_rule() {
-                          let (selector, _) = self.parse_subject_selector(query_pair)?;
-                          current_query_subject = Some((selector.subject, selector.evaluated));
-                          current_query_property = selector.property;
+                        match query_pair.as_rule() {
+                          Rule::subject_selector => {
+                            let (selector, _) = self.parse_subject_selector(query_pair)?;
+                            current_query_subject = Some((selector.subject, selector.evaluated));
+                            current_query_property = selector.property;
+                          }
+                          Rule::expect_error => {
+                            let reason = query_pair
+                              .into_inner()
+                              .find(|pair| pair.as_rule() == Rule::expect_error_reason)
+                              .map(|pair| pair.as_str().trim().to_string());
+                            current_expect_error = Some(reason.unwrap_or_default());
+                          }
+                          _ => {}
                         }
                       }
                     }
                     Rule::expected_line => {
+                      if current_query_subject.is_some() && current_expect_error.is_some() {
+                        return Err(anyhow::anyhow!(
+                          "Line {}: a query with expect-error can't have expected facts",
+                          line_content.line_col().0
+                        ));
+                      }
                       for expected_pair in line_content.into_inner() {
                         if let Rule::fact = expected_pair.as_rule() {
                           let (fact, subject_mapping, _fact_identifier) =
@@ -377,8 +444,8 @@ impl SubjectRegistry {
                       // If we have a regular query pending, this is the start of an explain query
                       // Convert it to an explain query
                       if let Some((subject, _evaluated)) = current_query_subject.take() {
-                        current_explain_subject = Some(subject);
-                        current_query_property = None;
+                        current_explain_subject = Some((subject, current_query_property.take()));
+                        current_expect_error = None;
                         current_expected_facts.clear();
                       }
 
@@ -406,11 +473,14 @@ impl SubjectRegistry {
         subject_evaluated: evaluated,
         expected_facts: current_expected_facts,
         property: current_query_property.clone(),
+        line: current_query_line,
+        expect_error: current_expect_error.take(),
       }));
     }
-    if let Some(explain_subject) = current_explain_subject {
+    if let Some((explain_subject, property)) = current_explain_subject {
       lines.push(TestLine::ExplainQuery(ExplainQuery {
         subject: explain_subject,
+        property,
         expected_lines: current_expected_explain_lines,
       }));
     }
@@ -418,6 +488,11 @@ impl SubjectRegistry {
     Ok(TestCase { lines })
   }
 
+  /// Names of all subjects known after parsing, including system and plugin subjects.
+  pub fn subject_names(&self) -> SubjectNames {
+    SubjectNames::from_database(self.app.get_raw_database())
+  }
+
   pub fn into_database(self) -> (App, HashMap<String, usize>) {
     (self.app, self.fact_identifiers)
   }
@@ -0,0 +1,55 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+  /// Colored output for humans
+  Text,
+  /// A single JSON report on stdout once all specs ran
+  Json,
+}
+
+static TEXT_OUTPUT: AtomicBool = AtomicBool::new(true);
+
+pub fn set_output_format(format: OutputFormat) {
+  TEXT_OUTPUT.store(format == OutputFormat::Text, Ordering::Relaxed);
+}
+
+pub fn is_text_output() -> bool {
+  TEXT_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// `println!` which is silenced in JSON mode, so stdout only contains the report.
+macro_rules! report {
+  ($($arg:tt)*) => {
+    if $crate::report::is_text_output() {
+      println!($($arg)*);
+    }
+  };
+}
+
+/// Outcome of a single spec file.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FileReport {
+  pub path: String,
+  pub passed: bool,
+  pub queries: usize,
+  pub failures: Vec<QueryFailure>,
+  /// Set if the file couldn't be run at all, e.g. because it failed to parse
+  pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QueryFailure {
+  pub query: String,
+  pub expected: Vec<String>,
+  pub actual: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SuiteReport {
+  pub total: usize,
+  pub passed: usize,
+  pub files: Vec<FileReport>,
+}
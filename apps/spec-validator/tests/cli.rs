@@ -0,0 +1,18 @@
+use std::{path::Path, process::Command};
+
+#[test]
+fn test_json_report_is_the_only_output() {
+  // The core spec evaluates fact values through sub-queries, none of which may log to stdout
+  let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+  let output = Command::new(env!("CARGO_BIN_EXE_spec-validator"))
+    .args(["--format", "json", "--filter", "01_core"])
+    .current_dir(workspace)
+    .output()
+    .unwrap();
+
+  let report: serde_json::Value =
+    serde_json::from_slice(&output.stdout).expect("stdout should only contain the JSON report");
+  // The filter matches the spec and the explain spec
+  assert_eq!(report["total"], 2);
+  assert_eq!(report["passed"], 2);
+}
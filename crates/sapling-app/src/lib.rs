@@ -1,7 +1,7 @@
-use sapling_data_model::{Fact, Query, Subject};
+use sapling_data_model::{Fact, Query, QueryBuilder, Subject};
 use sapling_query_engine::{
-  Database, DatabaseWatcher, FoundFact, QueryEngine, SharedVariableAllocator, SharedVariableBank,
-  System,
+  AbstractMachine, Database, DatabaseWatcher, FoundFact, QueryEngine, SharedVariableAllocator,
+  SharedVariableBank, System, WatcherId,
 };
 
 pub use crate::plugin::{AppPlugin, AppPluginInstallContext};
@@ -62,10 +62,30 @@ impl App {
     self.registry.get_global_by_name(name)
   }
 
+  /// Number of operands the interop function `name` expects. `None` if there is no such function
+  /// or it accepts any number of operands.
+  pub fn get_interop_arity(&self, name: &str) -> Option<usize> {
+    self.registry.get_interop_arity(name)
+  }
+
   pub fn create_named_subject(&mut self, name: &str) -> Subject {
     self.registry.create_global(&mut self.database, name.into())
   }
 
+  /// Returns a subject for every name in order, reusing already registered subjects (including
+  /// system subjects) instead of creating new ones. Unlike [`App::create_named_subject`], which
+  /// always creates a new subject and shadows an existing registration, this keeps names unique.
+  pub fn create_named_subjects(&mut self, names: &[&str]) -> Vec<Subject> {
+    names
+      .iter()
+      .map(|name| {
+        self
+          .get_global_by_name(name)
+          .unwrap_or_else(|| self.create_named_subject(name))
+      })
+      .collect()
+  }
+
   pub fn get_name(&self, subject: &Subject) -> String {
     System::get_subject_name(&self.database, subject).unwrap_or_default()
   }
@@ -81,6 +101,92 @@ impl App {
     )
   }
 
+  /// Queries the facts of `subject`, optionally only those of `property`, which carry exactly the
+  /// given meta. [`FoundFact::meta`] returns the meta of a matched fact.
+  pub fn query_with_meta<'a>(
+    &'a self,
+    subject: &Subject,
+    property: Option<&Subject>,
+    meta: &Subject,
+  ) -> AbstractMachine<'a> {
+    let mut query = QueryBuilder::subject(subject.clone()).meta(meta.clone());
+    if let Some(property) = property {
+      query = query.property(property.clone());
+    }
+
+    self.variable_allocator.reset();
+    self.variable_bank.reset();
+    self.query_engine.query(
+      &self.database,
+      &query.build(),
+      self.variable_bank.clone(),
+      self.variable_allocator.clone(),
+    )
+  }
+
+  /// Like [`App::query_once`] but stops after `max_yields` facts or `max_steps` executed
+  /// instructions, whichever comes first. [`AbstractMachine::limit_reached`] tells whether the
+  /// result is partial.
+  pub fn query_once_limited(
+    &self,
+    query: &Query,
+    max_yields: usize,
+    max_steps: usize,
+  ) -> AbstractMachine<'_> {
+    self.variable_allocator.reset();
+    self.variable_bank.reset();
+    self
+      .query_engine
+      .query_with_limit(
+        &self.database,
+        query,
+        self.variable_bank.clone(),
+        self.variable_allocator.clone(),
+        max_yields,
+      )
+      .with_step_budget(max_steps)
+  }
+
+  /// Like [`App::query_once`] but clones the matched facts, so the app can be mutated right away.
+  pub fn query_all(&self, query: &Query) -> Vec<Fact> {
+    self
+      .query_once(query)
+      .map(|found| found.fact.clone())
+      .collect()
+  }
+
+  /// Follows the given properties starting from `root` and returns the value of the last one,
+  /// e.g. `Person 1 / Best Friend / First Name`. Values which refer to a property of another
+  /// subject (`subject/property`) are resolved before continuing with the next hop.
+  ///
+  /// Returns `None` if any of the properties along the path has no value.
+  pub fn query_path(&self, root: &Subject, path: &[Subject]) -> Option<Subject> {
+    let mut current = root.clone();
+    for property in path {
+      current = self.query_value(&current, property, false)?;
+    }
+    Some(current)
+  }
+
+  fn query_value(&self, subject: &Subject, property: &Subject, evaluated: bool) -> Option<Subject> {
+    let value = self
+      .query_once(&Query {
+        subject: subject.clone(),
+        property: Some(property.clone()),
+        meta: None,
+        evaluated,
+      })
+      .next()?
+      .fact
+      .value
+      .clone();
+
+    match &value.property {
+      Some(value_property) => self.query_value(&value.subject, value_property, value.evaluated),
+      None => Some(value.subject),
+    }
+  }
+
   pub fn explain_once(&self, subject: &Subject) -> sapling_query_engine::ExplainResult {
     self.variable_allocator.reset();
     self.variable_bank.reset();
@@ -92,6 +198,65 @@ impl App {
     )
   }
 
+  /// Explains a property-qualified query, the query's subject is the explain subject.
+  pub fn explain_query_once(&self, query: &Query) -> sapling_query_engine::ExplainResult {
+    self.variable_allocator.reset();
+    self.variable_bank.reset();
+    self.query_engine.explain_query(
+      &self.database,
+      query,
+      self.variable_bank.clone(),
+      self.variable_allocator.clone(),
+    )
+  }
+
+  /// Explains an evaluated query without an explain subject, see [`QueryEngine::explain_rule`].
+  pub fn explain_rule_once(&self, query: &Query) -> sapling_query_engine::ExplainResult {
+    self.variable_allocator.reset();
+    self.variable_bank.reset();
+    self.query_engine.explain_rule(
+      &self.database,
+      query,
+      self.variable_bank.clone(),
+      self.variable_allocator.clone(),
+    )
+  }
+
+  /// Facts added or modified since the last [`App::mark_clean`] together with their index.
+  /// Loading these on top of the snapshot they were taken from replaces the facts at existing
+  /// indices and appends the others, which reproduces the current database.
+  pub fn dirty_facts(&self) -> impl Iterator<Item = (usize, &Fact)> {
+    self
+      .database
+      .dirty_fact_indices()
+      .filter_map(|index| Some((index, self.database.get_fact(index)?)))
+  }
+
+  /// Marks all facts as persisted, see [`Database::mark_clean`].
+  pub fn mark_clean(&mut self) -> u64 {
+    self.database.mark_clean()
+  }
+
+  /// Reports `id` from [`App::changed_watchers`] once the result of `query` changed, see
+  /// [`DatabaseWatcher::watch_polled`].
+  pub fn watch(&mut self, query: &Query, id: WatcherId) {
+    self.variable_allocator.reset();
+    self.variable_bank.reset();
+    self.watcher.watch_polled(
+      &self.database,
+      &self.query_engine,
+      self.variable_bank.clone(),
+      self.variable_allocator.clone(),
+      query,
+      id,
+    );
+  }
+
+  /// Ids of the watched queries whose result changed since the last call.
+  pub fn changed_watchers(&mut self) -> Vec<WatcherId> {
+    self.watcher.changed_watchers()
+  }
+
   pub fn add_fact(&mut self, fact: Fact) -> usize {
     let index = self.database.add_fact(fact);
     self.watcher.handle_new_fact(
@@ -103,4 +268,43 @@ impl App {
     );
     index
   }
+
+  /// Adds all facts before notifying watchers once, which is considerably cheaper than calling
+  /// [`App::add_fact`] for each fact. Returns the indices of the facts in order.
+  pub fn add_facts(&mut self, facts: impl IntoIterator<Item = Fact>) -> Vec<usize> {
+    let indices = facts
+      .into_iter()
+      .map(|fact| self.database.add_fact(fact))
+      .collect::<Vec<_>>();
+    let (Some(&first), Some(&last)) = (indices.first(), indices.last()) else {
+      return indices;
+    };
+
+    self.variable_allocator.reset();
+    self.variable_bank.reset();
+    self.watcher.handle_new_facts(
+      &mut self.database,
+      &self.query_engine,
+      self.variable_bank.clone(),
+      self.variable_allocator.clone(),
+      first..last + 1,
+    );
+    indices
+  }
+
+  /// Removes the fact at `index` and notifies watchers. Later facts move down by one index, see
+  /// [`Database::remove_fact`].
+  pub fn remove_fact(&mut self, index: usize) -> Option<Fact> {
+    let fact = self.database.remove_fact(index)?;
+    self.variable_allocator.reset();
+    self.variable_bank.reset();
+    self.watcher.handle_removed_fact(
+      &mut self.database,
+      &self.query_engine,
+      self.variable_bank.clone(),
+      self.variable_allocator.clone(),
+      index,
+    );
+    Some(fact)
+  }
 }
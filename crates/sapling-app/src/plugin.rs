@@ -39,16 +39,50 @@ impl<'a> AppPluginInstallContext<'a> {
     }
   }
 
-  pub fn add_interop_fn<F, TArg, TOut>(&mut self, name: &str, result_name: &str, func: F)
-  where
+  /// Registers `func` to compute `name/result_name` from the operands `name/0`, `name/1`, ...
+  /// `arity` is the number of operands the function expects, `None` if it accepts any number.
+  pub fn add_interop_fn<F, TArg, TOut>(
+    &mut self,
+    name: &str,
+    result_name: &str,
+    arity: Option<usize>,
+    func: F,
+  ) where
     F: Fn(&TArg) -> TOut + Clone + 'static,
     for<'b> TArg: SaplingDeserializable<AppPluginSerializerContext<'b>>,
     for<'b> TOut: SaplingSerializable<AppPluginSerializerContext<'b>>,
+  {
+    self.add_interop_relation(name, result_name, arity, move |argument: &TArg| {
+      std::iter::once(func(argument))
+    });
+  }
+
+  /// Like [`AppPluginInstallContext::add_interop_fn`], but for relational functions yielding any
+  /// number of results, e.g. the factors of a number. Every result is added as its own
+  /// `name/result_name` fact.
+  pub fn add_interop_relation<F, TArg, TOut, TResults>(
+    &mut self,
+    name: &str,
+    result_name: &str,
+    arity: Option<usize>,
+    func: F,
+  ) where
+    F: Fn(&TArg) -> TResults + Clone + 'static,
+    TResults: IntoIterator<Item = TOut>,
+    for<'b> TArg: SaplingDeserializable<AppPluginSerializerContext<'b>>,
+    for<'b> TOut: SaplingSerializable<AppPluginSerializerContext<'b>>,
   {
     let subject = self.registry.create_global(self.database, name.into());
+    self.registry.set_interop_arity(name.into(), arity);
+    // Functions share their result property, e.g. every math function yields `Result`
     let result_subject = self
       .registry
-      .create_global(self.database, result_name.into());
+      .get_global_by_name(result_name)
+      .unwrap_or_else(|| {
+        self
+          .registry
+          .create_global(self.database, result_name.into())
+      });
 
     let queries_for_input = {
       let mut context = AppPluginSerializerContext::new(
@@ -68,7 +102,7 @@ impl<'a> AppPluginInstallContext<'a> {
             query_engine: &QueryEngine,
             variable_bank: SharedVariableBank,
             variable_allocator: SharedVariableAllocator| {
-        let result = {
+        let results = {
           let mut context = AppPluginSerializerContext::new(
             database,
             query_engine,
@@ -76,46 +110,59 @@ impl<'a> AppPluginInstallContext<'a> {
             variable_allocator.clone(),
             None,
           );
-          let argument = TArg::deserialize_subject(subject, &mut context);
-          if let Err(err) = argument {
-            match err {
-              DeserializeError::MissingFact { .. } => {
-                // expected error when subject does not have any properties yet
-              }
-              _ => {
-                eprintln!(
-                  "Failed to deserialize argument for interop function '{}' - {:?}",
-                  name, err
-                );
-              }
-            }
+          TArg::deserialize_subject(subject, &mut context).map(|argument| {
+            func(&argument)
+              .into_iter()
+              .map(|result| result.serialize_to_facts(&mut context, name))
+              .collect::<Vec<_>>()
+          })
+        };
+
+        let results = match results {
+          Ok(results) => {
+            database.clear_failure(subject);
+            results
+          }
+          Err(DeserializeError::MissingFact { .. }) => {
+            // expected error when subject does not have any properties yet
+            return;
+          }
+          Err(err) => {
+            // Surfaces in explain, the results computed from the last valid operands remain
+            database.report_failure(
+              subject.clone(),
+              format!(
+                "Failed to deserialize the operands of the interop function - {}",
+                err
+              ),
+            );
             return;
           }
-          let result = func(&argument.unwrap());
-          result.serialize_to_facts(&mut context, &name)
         };
 
-        database.add_fact(Fact {
-          subject: SubjectSelector {
-            evaluated: false,
-            property: None,
-            subject: subject.clone(),
-          },
-          property: SubjectSelector {
-            evaluated: false,
-            property: None,
-            subject: result_subject.clone(),
-          },
-          meta: Subject::String {
-            value: "default".into(),
-          },
-          operator: System::CORE_OPERATOR_IS,
-          value: SubjectSelector {
-            subject: result,
-            evaluated: false,
-            property: None,
-          },
-        });
+        for result in results {
+          database.add_fact(Fact {
+            subject: SubjectSelector {
+              evaluated: false,
+              property: None,
+              subject: subject.clone(),
+            },
+            property: SubjectSelector {
+              evaluated: false,
+              property: None,
+              subject: result_subject.clone(),
+            },
+            meta: Subject::String {
+              value: "default".into(),
+            },
+            operator: System::CORE_OPERATOR_IS,
+            value: SubjectSelector {
+              subject: result,
+              evaluated: false,
+              property: None,
+            },
+          });
+        }
       },
     );
 
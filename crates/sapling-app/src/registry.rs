@@ -6,6 +6,7 @@ use sapling_query_engine::{Database, System};
 #[derive(Default)]
 pub struct AppRegistry {
   global_subjects: HashMap<String, Subject>,
+  interop_arities: HashMap<String, Option<usize>>,
 }
 
 impl AppRegistry {
@@ -19,4 +20,12 @@ impl AppRegistry {
     let system_subject = System::get_named_subject(name);
     system_subject.or_else(|| self.global_subjects.get(name).cloned())
   }
+
+  pub fn set_interop_arity(&mut self, name: String, arity: Option<usize>) {
+    self.interop_arities.insert(name, arity);
+  }
+
+  pub fn get_interop_arity(&self, name: &str) -> Option<usize> {
+    self.interop_arities.get(name).copied().flatten()
+  }
 }
@@ -32,8 +32,8 @@ impl<'a> AppPluginSerializerContext<'a> {
 }
 
 impl<'a> SerializerContext for AppPluginSerializerContext<'a> {
-  fn add_fact(&mut self, fact: sapling_data_model::Fact) {
-    self.database.add_fact(fact);
+  fn add_fact(&mut self, fact: sapling_data_model::Fact) -> usize {
+    self.database.add_fact(fact)
   }
   fn new_static_subject(&mut self, name: &str) -> sapling_data_model::Subject {
     self
@@ -55,6 +55,13 @@ impl<'a> DeserializerContext for AppPluginSerializerContext<'a> {
   fn get_subject_name(&mut self, subject: &sapling_data_model::Subject) -> String {
     System::get_subject_name(self.database, subject).unwrap()
   }
+  fn find_subjects(
+    &mut self,
+    property: &sapling_data_model::Subject,
+    value: &sapling_data_model::Subject,
+  ) -> Vec<sapling_data_model::Subject> {
+    self.database.get_subjects_with_value(property, value)
+  }
   fn query<'db, 'q>(
     &'db mut self,
     query: &'q sapling_data_model::Query,
@@ -0,0 +1,472 @@
+use sapling_app::{App, AppPlugin, AppPluginInstallContext};
+use sapling_data_model::{Fact, Query, Subject, SubjectSelector};
+use sapling_query_engine::{FoundFactIteratorExt, System, WatcherId};
+use sapling_serialization_macro::SaplingDeserialization;
+
+fn add_fact(app: &mut App, subject: &Subject, property: &Subject, value: Subject) {
+  app.add_fact(fact(subject, property, value));
+}
+
+fn fact(subject: &Subject, property: &Subject, value: Subject) -> Fact {
+  Fact {
+    subject: SubjectSelector {
+      subject: subject.clone(),
+      evaluated: false,
+      property: None,
+    },
+    property: SubjectSelector {
+      subject: property.clone(),
+      evaluated: false,
+      property: None,
+    },
+    value: SubjectSelector {
+      subject: value,
+      evaluated: false,
+      property: None,
+    },
+    operator: System::CORE_OPERATOR_IS,
+    meta: Subject::String {
+      value: "default".to_string(),
+    },
+  }
+}
+
+#[test]
+fn test_query_path() {
+  let mut app = App::new(128);
+  let first_name = app.create_named_subject("First Name");
+  let best_friend = app.create_named_subject("Best Friend");
+  let person1 = app.create_named_subject("Person 1");
+  let person2 = app.create_named_subject("Person 2");
+
+  add_fact(
+    &mut app,
+    &person1,
+    &first_name,
+    Subject::String {
+      value: "Rene".into(),
+    },
+  );
+  add_fact(&mut app, &person1, &best_friend, person2.clone());
+  add_fact(
+    &mut app,
+    &person2,
+    &first_name,
+    Subject::String {
+      value: "John".into(),
+    },
+  );
+
+  let name = app.query_path(&person1, &[best_friend.clone(), first_name.clone()]);
+  assert!(matches!(name, Some(Subject::String { value }) if value == "John"));
+
+  let name = app.query_path(&person1, std::slice::from_ref(&first_name));
+  assert!(matches!(name, Some(Subject::String { value }) if value == "Rene"));
+
+  // Person 2 has no best friend
+  let name = app.query_path(&person2, &[best_friend, first_name]);
+  assert!(name.is_none());
+}
+
+#[test]
+fn test_create_named_subjects() {
+  let mut app = App::new(128);
+  let existing = app.create_named_subject("Existing");
+
+  let subjects = app.create_named_subjects(&["First", "Existing", "Second", "First"]);
+
+  assert_eq!(subjects.len(), 4);
+  assert_eq!(subjects[1], existing);
+  assert_eq!(subjects[0], subjects[3]);
+  assert_ne!(subjects[0], subjects[2]);
+  assert_eq!(app.get_name(&subjects[2]), "Second");
+  assert_eq!(app.get_global_by_name("First"), Some(subjects[0].clone()));
+
+  // System subjects are reused as well
+  let wildcard = app.create_named_subjects(&["*"]);
+  assert_eq!(wildcard[0], System::CORE_WILDCARD_SUBJECT);
+}
+
+#[test]
+fn test_dirty_facts() {
+  let mut app = App::new(128);
+  let name = app.create_named_subject("Name");
+  let person = app.create_named_subject("Person");
+  add_fact(
+    &mut app,
+    &person,
+    &name,
+    Subject::String {
+      value: "Rene".into(),
+    },
+  );
+  assert!(app.dirty_facts().count() > 0);
+
+  let snapshot = app.mark_clean();
+  assert_eq!(snapshot, app.get_raw_database().snapshot_id());
+  assert_eq!(app.dirty_facts().count(), 0);
+
+  let age = app.create_named_subject("Age");
+  add_fact(&mut app, &person, &age, Subject::Integer { value: 30 });
+
+  let dirty = app
+    .dirty_facts()
+    .map(|(_, fact)| fact.clone())
+    .collect::<Vec<_>>();
+  // The name fact of "Age" and the age itself
+  assert_eq!(dirty.len(), 2);
+  assert_eq!(dirty[0].subject.subject, age);
+  assert_eq!(dirty[1].subject.subject, person);
+  assert_eq!(dirty[1].property.subject, age);
+
+  // Mutably accessed facts of the snapshot are dirty as well
+  app.get_raw_database_mut().get_fact_mut(0);
+  assert_eq!(app.dirty_facts().next().map(|(index, _)| index), Some(0));
+  assert_eq!(app.dirty_facts().count(), 3);
+}
+
+#[test]
+fn test_query_all() {
+  let mut app = App::new(128);
+  let name = app.create_named_subject("Name");
+  let age = app.create_named_subject("Age");
+  let person = app.create_named_subject("Person");
+  add_fact(
+    &mut app,
+    &person,
+    &name,
+    Subject::String {
+      value: "Rene".into(),
+    },
+  );
+  add_fact(&mut app, &person, &age, Subject::Integer { value: 30 });
+
+  let facts = app.query_all(&Query {
+    subject: person.clone(),
+    property: None,
+    meta: None,
+    evaluated: false,
+  });
+
+  // The app is not borrowed anymore
+  add_fact(&mut app, &person, &age, Subject::Integer { value: 31 });
+
+  assert_eq!(facts.len(), 2);
+  assert_eq!(facts[0].property.subject, name);
+  assert_eq!(facts[1].property.subject, age);
+  assert_eq!(facts[1].value.subject, Subject::Integer { value: 30 });
+}
+
+#[test]
+fn test_remove_fact() {
+  let mut app = App::new(128);
+  let name = app.create_named_subject("Name");
+  let age = app.create_named_subject("Age");
+  let person = app.create_named_subject("Person");
+  add_fact(&mut app, &person, &age, Subject::Integer { value: 30 });
+  add_fact(
+    &mut app,
+    &person,
+    &name,
+    Subject::String {
+      value: "Rene".into(),
+    },
+  );
+  let query = Query {
+    subject: person.clone(),
+    property: None,
+    meta: None,
+    evaluated: false,
+  };
+
+  let age_index = app.query_once(&query).next().unwrap().fact_index;
+  let removed = app.remove_fact(age_index).unwrap();
+  assert_eq!(removed.property.subject, age);
+
+  let facts = app.query_all(&query);
+  assert_eq!(facts.len(), 1);
+  assert_eq!(facts[0].property.subject, name);
+  assert_eq!(app.query_path(&person, &[age]), None);
+
+  assert!(app.remove_fact(usize::MAX).is_none());
+}
+
+#[test]
+fn test_add_facts_matches_sequential_add_fact() {
+  fn setup() -> (App, Subject, Vec<Subject>) {
+    let mut app = App::new(128);
+    let index = app.create_named_subject("Index");
+    let name = app.create_named_subject("Name");
+    let people = (0..50)
+      .map(|i| app.create_named_subject(&format!("Person {i}")))
+      .collect::<Vec<_>>();
+
+    // Two watchers matching some of the added facts and one matching none of them
+    let query = |person: &Subject, property: &Subject| Query {
+      subject: person.clone(),
+      property: Some(property.clone()),
+      meta: None,
+      evaluated: false,
+    };
+    app.watch(&query(&people[0], &index), WatcherId(1));
+    app.watch(&query(&people[49], &index), WatcherId(2));
+    app.watch(&query(&people[0], &name), WatcherId(3));
+    (app, index, people)
+  }
+
+  let (mut sequential, index, people) = setup();
+  let sequential_indices = people
+    .iter()
+    .enumerate()
+    .map(|(i, person)| {
+      sequential.add_fact(fact(person, &index, Subject::Integer { value: i as i64 }))
+    })
+    .collect::<Vec<_>>();
+
+  let (mut batched, index, people) = setup();
+  let batched_indices = batched.add_facts(
+    people
+      .iter()
+      .enumerate()
+      .map(|(i, person)| fact(person, &index, Subject::Integer { value: i as i64 })),
+  );
+  assert_eq!(batched_indices, sequential_indices);
+  assert!(batched.add_facts([]).is_empty());
+
+  let sequential_changed = sequential.changed_watchers();
+  assert_eq!(sequential_changed, vec![WatcherId(1), WatcherId(2)]);
+  assert_eq!(batched.changed_watchers(), sequential_changed);
+
+  for person in &people {
+    let query = Query {
+      subject: person.clone(),
+      property: Some(index.clone()),
+      meta: None,
+      evaluated: false,
+    };
+    let sequential_values = sequential
+      .query_all(&query)
+      .into_iter()
+      .map(|fact| fact.value.subject)
+      .collect::<Vec<_>>();
+    let batched_values = batched
+      .query_all(&query)
+      .into_iter()
+      .map(|fact| fact.value.subject)
+      .collect::<Vec<_>>();
+    assert_eq!(batched_values.len(), 1);
+    assert_eq!(batched_values, sequential_values);
+  }
+}
+
+#[test]
+fn test_interop_arity() {
+  #[derive(SaplingDeserialization)]
+  struct Operands {
+    #[sapling(indexed = true)]
+    indexed: Vec<i64>,
+  }
+
+  struct DifferencePlugin;
+  impl AppPlugin for DifferencePlugin {
+    fn install_plugin(&mut self, context: &mut AppPluginInstallContext) {
+      context.add_interop_fn("Difference", "Result", Some(2), |operands: &Operands| {
+        operands
+          .indexed
+          .iter()
+          .skip(1)
+          .fold(operands.indexed[0], |a, b| a - b)
+      });
+      context.add_interop_fn("Sum", "Result", None, |operands: &Operands| {
+        operands.indexed.iter().sum::<i64>()
+      });
+    }
+  }
+
+  let mut app = App::new(128);
+  app.add_plugin(DifferencePlugin);
+
+  assert_eq!(app.get_interop_arity("Difference"), Some(2));
+  assert_eq!(app.get_interop_arity("Sum"), None);
+  assert_eq!(app.get_interop_arity("Unknown"), None);
+}
+
+#[test]
+fn test_query_once_limited_stops_after_max_yields() {
+  let mut app = App::new(128);
+  let index = app.create_named_subject("Index");
+  let person = app.create_named_subject("Person");
+  for i in 0..10 {
+    add_fact(&mut app, &person, &index, Subject::Integer { value: i });
+  }
+  let query = Query {
+    subject: person.clone(),
+    property: None,
+    meta: None,
+    evaluated: false,
+  };
+
+  let mut machine = app.query_once_limited(&query, 3, 10_000);
+  assert_eq!(machine.by_ref().count(), 3);
+  assert!(machine.limit_reached());
+
+  let mut machine = app.query_once_limited(&query, 20, 10_000);
+  assert_eq!(machine.by_ref().count(), 10);
+  assert!(!machine.limit_reached());
+
+  // Yielding exactly the limit isn't a partial result
+  let mut machine = app.query_once_limited(&query, 10, 10_000);
+  assert_eq!(machine.by_ref().count(), 10);
+  assert!(!machine.limit_reached());
+}
+
+#[test]
+fn test_query_once_limited_terminates_cyclic_search() {
+  let mut app = App::new(128);
+  let value = app.create_named_subject("Value");
+  let a = app.create_named_subject("A");
+  let b = app.create_named_subject("B");
+  let query = app.create_named_subject("Query");
+
+  // a/value = b/value and b/value = a/value never resolve to an actual value
+  let mut a_value = fact(&a, &value, b.clone());
+  a_value.value.property = Some(value.clone());
+  app.add_fact(a_value);
+  let mut b_value = fact(&b, &value, a.clone());
+  b_value.value.property = Some(value.clone());
+  app.add_fact(b_value);
+  let mut constraint = fact(&query, &value, Subject::Integer { value: 1 });
+  constraint.operator = System::CORE_OPERATOR_EQ;
+  app.add_fact(constraint);
+
+  let mut machine = app.query_once_limited(
+    &Query {
+      subject: query,
+      property: None,
+      meta: None,
+      evaluated: true,
+    },
+    10,
+    1_000,
+  );
+  assert_eq!(machine.by_ref().count(), 0);
+  assert!(machine.limit_reached());
+}
+
+#[test]
+fn test_query_with_meta() {
+  let mut app = App::new(128);
+  let name = app.create_named_subject("Name");
+  let age = app.create_named_subject("Age");
+  let person = app.create_named_subject("Person");
+  let imported = Subject::String {
+    value: "imported".into(),
+  };
+  let default = Subject::String {
+    value: "default".into(),
+  };
+
+  add_fact(&mut app, &person, &age, Subject::Integer { value: 30 });
+  let mut imported_name = fact(
+    &person,
+    &name,
+    Subject::String {
+      value: "Rene".into(),
+    },
+  );
+  imported_name.meta = imported.clone();
+  app.add_fact(imported_name);
+  let mut imported_age = fact(&person, &age, Subject::Integer { value: 31 });
+  imported_age.meta = imported.clone();
+  app.add_fact(imported_age);
+
+  let default_facts = app
+    .query_with_meta(&person, None, &default)
+    .collect::<Vec<_>>();
+  assert_eq!(default_facts.len(), 1);
+  assert_eq!(default_facts[0].meta(), &default);
+  assert_eq!(
+    default_facts[0].fact.value.subject,
+    Subject::Integer { value: 30 }
+  );
+
+  let imported_facts = app
+    .query_with_meta(&person, None, &imported)
+    .collect::<Vec<_>>();
+  assert_eq!(imported_facts.len(), 2);
+  assert!(imported_facts.iter().all(|found| found.meta() == &imported));
+
+  let imported_ages = app
+    .query_with_meta(&person, Some(&age), &imported)
+    .map(|found| found.fact.value.subject.clone())
+    .collect::<Vec<_>>();
+  assert_eq!(imported_ages, vec![Subject::Integer { value: 31 }]);
+}
+
+#[test]
+fn test_unique_by_index() {
+  let mut app = App::new(128);
+  let color = app.create_named_subject("Color");
+  let green = app.create_named_subject("Green");
+  let query = app.create_named_subject("Query");
+  let string = |value: &str| Subject::String {
+    value: value.into(),
+  };
+
+  add_fact(&mut app, &green, &color, string("green"));
+  // Both constraints match the same fact, so it is yielded once for each of them
+  for excluded in ["red", "blue"] {
+    let mut constraint = fact(&query, &color, string(excluded));
+    constraint.operator = System::CORE_OPERATOR_NOT;
+    app.add_fact(constraint);
+  }
+
+  let query = Query {
+    subject: query,
+    property: None,
+    meta: None,
+    evaluated: true,
+  };
+  let all = app.query_once(&query).collect::<Vec<_>>();
+  assert_eq!(all.len(), 2);
+
+  let unique = app.query_once(&query).unique_by_index().collect::<Vec<_>>();
+  assert_eq!(unique.len(), 1);
+  assert_eq!(unique[0].fact_index, all[0].fact_index);
+  assert_eq!(unique[0].subject_binding, all[0].subject_binding);
+}
+
+#[test]
+fn test_changed_watchers() {
+  let mut app = App::new(128);
+  let age = app.create_named_subject("Age");
+  let name = app.create_named_subject("Name");
+  let person = app.create_named_subject("Person");
+  add_fact(
+    &mut app,
+    &person,
+    &name,
+    Subject::String {
+      value: "Rene".into(),
+    },
+  );
+
+  let query = |property: &Subject| Query {
+    subject: person.clone(),
+    property: Some(property.clone()),
+    meta: None,
+    evaluated: false,
+  };
+  app.watch(&query(&age), WatcherId(1));
+  app.watch(&query(&name), WatcherId(2));
+  // Facts which existed before watching aren't a change
+  assert!(app.changed_watchers().is_empty());
+
+  add_fact(&mut app, &person, &age, Subject::Integer { value: 30 });
+  assert_eq!(app.changed_watchers(), vec![WatcherId(1)]);
+  assert!(app.changed_watchers().is_empty());
+
+  add_fact(&mut app, &person, &age, Subject::Integer { value: 31 });
+  add_fact(&mut app, &person, &age, Subject::Integer { value: 32 });
+  assert_eq!(app.changed_watchers(), vec![WatcherId(1)]);
+}
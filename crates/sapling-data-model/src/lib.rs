@@ -1,3 +1,3 @@
 mod model;
 
-pub use model::{Fact, Query, Subject, SubjectSelector};
+pub use model::{Fact, Query, QueryBuilder, Subject, SubjectSelector};
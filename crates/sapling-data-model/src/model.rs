@@ -1,6 +1,9 @@
+use std::hash::{Hash, Hasher};
+
 #[derive(Clone, Debug)]
 pub enum Subject {
   Static { uuid: u128 },
+  Boolean { value: bool },
   Integer { value: i64 },
   Float { value: f64 },
   String { value: String },
@@ -10,6 +13,7 @@ impl Subject {
   pub fn type_name(&self) -> &'static str {
     match self {
       Subject::Static { .. } => "static",
+      Subject::Boolean { .. } => "boolean",
       Subject::Integer { .. } => "integer",
       Subject::Float { .. } => "float",
       Subject::String { .. } => "string",
@@ -19,12 +23,109 @@ impl Subject {
   pub fn is_same(&self, other: &Subject) -> bool {
     match (self, other) {
       (Subject::Static { uuid: uuid1 }, Subject::Static { uuid: uuid2 }) => uuid1 == uuid2,
+      (Subject::Boolean { value: value1 }, Subject::Boolean { value: value2 }) => value1 == value2,
       (Subject::Integer { value: value1 }, Subject::Integer { value: value2 }) => value1 == value2,
       (Subject::Float { value: value1 }, Subject::Float { value: value2 }) => value1 == value2,
       (Subject::String { value: value1 }, Subject::String { value: value2 }) => value1 == value2,
       _ => false,
     }
   }
+
+  pub fn as_boolean(&self) -> Option<bool> {
+    match self {
+      Subject::Boolean { value } => Some(*value),
+      _ => None,
+    }
+  }
+
+  pub fn as_integer(&self) -> Option<i64> {
+    match self {
+      Subject::Integer { value } => Some(*value),
+      _ => None,
+    }
+  }
+
+  pub fn as_float(&self) -> Option<f64> {
+    match self {
+      Subject::Float { value } => Some(*value),
+      _ => None,
+    }
+  }
+
+  pub fn as_string(&self) -> Option<&str> {
+    match self {
+      Subject::String { value } => Some(value),
+      _ => None,
+    }
+  }
+
+  /// Whether the subject is a literal value rather than a static identity.
+  pub fn is_primitive(&self) -> bool {
+    !matches!(self, Subject::Static { .. })
+  }
+
+  fn variant_rank(&self) -> u8 {
+    match self {
+      Subject::Static { .. } => 0,
+      Subject::Boolean { .. } => 1,
+      Subject::Integer { .. } => 2,
+      Subject::Float { .. } => 3,
+      Subject::String { .. } => 4,
+    }
+  }
+}
+
+/// Subjects are ordered by variant first (static < boolean < integer < float < string) and by
+/// their inner value second, statics are ordered by their uuid.
+///
+/// Floats are compared using [`f64::total_cmp`] to get a total order, hence the ordering only
+/// agrees with [`Subject::is_same`] for floats which are neither `NaN` nor signed zeros.
+impl Ord for Subject {
+  fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    match (self, other) {
+      (Subject::Static { uuid: uuid1 }, Subject::Static { uuid: uuid2 }) => uuid1.cmp(uuid2),
+      (Subject::Boolean { value: value1 }, Subject::Boolean { value: value2 }) => {
+        value1.cmp(value2)
+      }
+      (Subject::Integer { value: value1 }, Subject::Integer { value: value2 }) => {
+        value1.cmp(value2)
+      }
+      (Subject::Float { value: value1 }, Subject::Float { value: value2 }) => {
+        value1.total_cmp(value2)
+      }
+      (Subject::String { value: value1 }, Subject::String { value: value2 }) => value1.cmp(value2),
+      _ => self.variant_rank().cmp(&other.variant_rank()),
+    }
+  }
+}
+
+impl PartialOrd for Subject {
+  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl PartialEq for Subject {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other).is_eq()
+  }
+}
+
+impl Eq for Subject {}
+
+/// Agrees with [`Subject::eq`], floats are hashed by their bits just like [`f64::total_cmp`]
+/// only considers floats with the same bits equal.
+impl Hash for Subject {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.variant_rank().hash(state);
+    match self {
+      Subject::Static { uuid } => uuid.hash(state),
+      Subject::Boolean { value } => value.hash(state),
+      Subject::Integer { value } => value.hash(state),
+      Subject::Float { value } => value.to_bits().hash(state),
+      Subject::String { value } => value.hash(state),
+    }
+  }
 }
 
 #[derive(Clone, Debug)]
@@ -53,3 +154,254 @@ pub struct Query {
   pub meta: Option<Subject>,
   pub evaluated: bool,
 }
+
+/// Builds a [`Query`], by default without property and meta and not evaluated.
+///
+/// ```
+/// # use sapling_data_model::{QueryBuilder, Subject};
+/// let query = QueryBuilder::subject(Subject::Static { uuid: 1 })
+///   .property(Subject::Static { uuid: 2 })
+///   .build();
+/// assert!(query.meta.is_none());
+/// ```
+#[derive(Clone, Debug)]
+pub struct QueryBuilder {
+  query: Query,
+}
+
+impl QueryBuilder {
+  pub fn subject(subject: Subject) -> Self {
+    Self {
+      query: Query {
+        subject,
+        property: None,
+        meta: None,
+        evaluated: false,
+      },
+    }
+  }
+
+  pub fn property(mut self, property: Subject) -> Self {
+    self.query.property = Some(property);
+    self
+  }
+
+  pub fn meta(mut self, meta: Subject) -> Self {
+    self.query.meta = Some(meta);
+    self
+  }
+
+  pub fn evaluated(mut self, evaluated: bool) -> Self {
+    self.query.evaluated = evaluated;
+    self
+  }
+
+  pub fn build(self) -> Query {
+    self.query
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_subject_cross_variant_ordering() {
+    let mut subjects = vec![
+      Subject::String {
+        value: "a".to_string(),
+      },
+      Subject::Float { value: 1.5 },
+      Subject::Integer { value: 2 },
+      Subject::Static { uuid: 10 },
+      Subject::Integer { value: -1 },
+      Subject::Static { uuid: 3 },
+      Subject::Float { value: -0.5 },
+    ];
+    subjects.sort();
+
+    assert_eq!(
+      subjects,
+      vec![
+        Subject::Static { uuid: 3 },
+        Subject::Static { uuid: 10 },
+        Subject::Integer { value: -1 },
+        Subject::Integer { value: 2 },
+        Subject::Float { value: -0.5 },
+        Subject::Float { value: 1.5 },
+        Subject::String {
+          value: "a".to_string(),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn test_subject_ordering_consistent_with_is_same() {
+    let subjects = [
+      Subject::Static { uuid: 1 },
+      Subject::Boolean { value: true },
+      Subject::Integer { value: 1 },
+      Subject::Float { value: 1.0 },
+      Subject::String {
+        value: "1".to_string(),
+      },
+    ];
+
+    for a in &subjects {
+      for b in &subjects {
+        assert_eq!(a.is_same(b), a.cmp(b).is_eq());
+      }
+    }
+  }
+
+  #[test]
+  fn test_equal_subjects_hash_equally() {
+    use std::collections::HashSet;
+
+    let subjects: HashSet<Subject> = [
+      Subject::Static { uuid: 1 },
+      Subject::Static { uuid: 1 },
+      Subject::Integer { value: 1 },
+      Subject::Float { value: 1.0 },
+      Subject::Float { value: 1.0 },
+      Subject::String {
+        value: "1".to_string(),
+      },
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(subjects.len(), 4);
+    assert!(subjects.contains(&Subject::Float { value: 1.0 }));
+    assert!(!subjects.contains(&Subject::Static { uuid: 2 }));
+  }
+
+  #[test]
+  fn test_subject_sort_is_stable() {
+    // Equal subjects keep their relative order, tracked by the second tuple element
+    let mut subjects = [
+      (Subject::Integer { value: 1 }, 0),
+      (Subject::Static { uuid: 1 }, 1),
+      (Subject::Integer { value: 1 }, 2),
+      (Subject::Static { uuid: 1 }, 3),
+    ];
+    subjects.sort_by(|a, b| a.0.cmp(&b.0));
+
+    assert_eq!(
+      subjects.iter().map(|(_, index)| *index).collect::<Vec<_>>(),
+      vec![1, 3, 0, 2]
+    );
+  }
+
+  #[test]
+  fn test_query_builder_defaults() {
+    let query = QueryBuilder::subject(Subject::Static { uuid: 1 }).build();
+
+    assert_eq!(query.subject, Subject::Static { uuid: 1 });
+    assert_eq!(query.property, None);
+    assert_eq!(query.meta, None);
+    assert!(!query.evaluated);
+  }
+
+  #[test]
+  fn test_query_builder_property() {
+    let query = QueryBuilder::subject(Subject::Static { uuid: 1 })
+      .property(Subject::Static { uuid: 2 })
+      .build();
+
+    assert_eq!(query.property, Some(Subject::Static { uuid: 2 }));
+    assert_eq!(query.meta, None);
+  }
+
+  #[test]
+  fn test_query_builder_meta() {
+    let query = QueryBuilder::subject(Subject::Static { uuid: 1 })
+      .meta(Subject::Static { uuid: 3 })
+      .build();
+
+    assert_eq!(query.property, None);
+    assert_eq!(query.meta, Some(Subject::Static { uuid: 3 }));
+  }
+
+  #[test]
+  fn test_query_builder_evaluated() {
+    let query = QueryBuilder::subject(Subject::Integer { value: 4 })
+      .property(Subject::Static { uuid: 2 })
+      .meta(Subject::Static { uuid: 3 })
+      .evaluated(true)
+      .build();
+
+    assert_eq!(query.subject, Subject::Integer { value: 4 });
+    assert_eq!(query.property, Some(Subject::Static { uuid: 2 }));
+    assert_eq!(query.meta, Some(Subject::Static { uuid: 3 }));
+    assert!(query.evaluated);
+  }
+
+  fn all_variants() -> [Subject; 5] {
+    [
+      Subject::Static { uuid: 7 },
+      Subject::Boolean { value: true },
+      Subject::Integer { value: -3 },
+      Subject::Float { value: 2.5 },
+      Subject::String {
+        value: "text".to_string(),
+      },
+    ]
+  }
+
+  #[test]
+  fn test_as_boolean() {
+    assert_eq!(
+      all_variants().map(|subject| subject.as_boolean()),
+      [None, Some(true), None, None, None]
+    );
+  }
+
+  #[test]
+  fn test_as_integer() {
+    assert_eq!(
+      all_variants().map(|subject| subject.as_integer()),
+      [None, None, Some(-3), None, None]
+    );
+  }
+
+  #[test]
+  fn test_as_float() {
+    assert_eq!(
+      all_variants().map(|subject| subject.as_float()),
+      [None, None, None, Some(2.5), None]
+    );
+  }
+
+  #[test]
+  fn test_as_string() {
+    let subjects = all_variants();
+    assert_eq!(
+      subjects.each_ref().map(|subject| subject.as_string()),
+      [None, None, None, None, Some("text")]
+    );
+  }
+
+  #[test]
+  fn test_is_primitive() {
+    assert_eq!(
+      all_variants().map(|subject| subject.is_primitive()),
+      [false, true, true, true, true]
+    );
+  }
+
+  #[test]
+  fn test_boolean_matching() {
+    let subject = Subject::Boolean { value: true };
+    assert!(subject.is_same(&Subject::Boolean { value: true }));
+    assert!(!subject.is_same(&Subject::Boolean { value: false }));
+    // Booleans are distinct from their integer encoding
+    assert!(!subject.is_same(&Subject::Integer { value: 1 }));
+    assert_eq!(subject.type_name(), "boolean");
+
+    assert!(Subject::Boolean { value: false } < subject);
+    assert!(Subject::Static { uuid: 1 } < subject);
+    assert!(subject < Subject::Integer { value: 0 });
+  }
+}
@@ -0,0 +1,88 @@
+//! Compares frames of trees built from layout only views against the same trees built from views
+//! which take part in rendering.
+//!
+//! Run with `cargo bench -p sapling-gui --bench layout_only`.
+
+use std::{hint::black_box, time::Duration};
+
+use sapling_app::App;
+use sapling_gui::{
+  NoopRenderer,
+  prelude::{
+    Component, ComponentElement, ElementContext, InputState, LayoutView, Orchestrator, Theme,
+  },
+};
+
+const DEPTH: usize = 5;
+const CHILDREN_PER_VIEW: usize = 3;
+const FRAMES: u32 = 20;
+
+/// Counterpart of [`LayoutView`] which is allocated and rendered like any other view.
+#[derive(Debug)]
+struct RenderedView;
+
+impl Component for RenderedView {}
+
+#[derive(Debug)]
+struct TreeView {
+  layout_only: bool,
+}
+
+impl Component for TreeView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    build_tree(context, DEPTH, self.layout_only);
+  }
+}
+
+fn build_tree(context: &mut ElementContext, depth: usize, layout_only: bool) {
+  if depth == 0 {
+    return;
+  }
+
+  for _ in 0..CHILDREN_PER_VIEW {
+    let children = move |context: &mut ElementContext| build_tree(context, depth - 1, layout_only);
+    if layout_only {
+      LayoutView.with_children(children).build(context);
+    } else {
+      RenderedView.with_children(children).build(context);
+    }
+  }
+}
+
+fn measure(name: &str, layout_only: bool) {
+  let mut app = App::new(16);
+  let mut orchestrator = Orchestrator::new(false);
+  let mut construction = Duration::ZERO;
+  let mut layout = Duration::ZERO;
+  let mut render = Duration::ZERO;
+  let mut element_count = 0;
+
+  for _ in 0..FRAMES {
+    let stats = black_box(orchestrator.construct_and_render(
+      TreeView { layout_only },
+      800.0,
+      600.0,
+      &mut NoopRenderer::default(),
+      &mut Theme::no_fonts(),
+      &mut app,
+      &InputState::default(),
+    ));
+    construction += stats.construction_duration;
+    layout += stats.layout_duration;
+    render += stats.render_duration;
+    element_count = stats.element_count;
+  }
+
+  let per_frame = |total: Duration| total.as_secs_f64() * 1000.0 / FRAMES as f64;
+  println!(
+    "{name}: {element_count} elements, construction {:.2}ms, layout {:.2}ms, render {:.2}ms",
+    per_frame(construction),
+    per_frame(layout),
+    per_frame(render)
+  );
+}
+
+fn main() {
+  measure("layout only", true);
+  measure("rendered", false);
+}
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use crate::layout::ResolvedLayout;
+
+/// Easing curve applied to the progress of a layout transition.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+  Linear,
+  EaseIn,
+  EaseOut,
+  EaseInOut,
+}
+
+impl Easing {
+  /// Maps the linear progress `t` in `0..=1` onto the easing curve.
+  pub fn apply(&self, t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    match self {
+      Easing::Linear => t,
+      Easing::EaseIn => t * t * t,
+      Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+      Easing::EaseInOut => {
+        if t < 0.5 {
+          4.0 * t * t * t
+        } else {
+          1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+        }
+      }
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AnimationConfig {
+  pub duration: Duration,
+  pub easing: Easing,
+}
+
+/// Transition of a single element from the layout it was displayed at towards its solved layout.
+#[derive(Debug, Clone)]
+pub(crate) struct LayoutAnimation {
+  from: ResolvedLayout,
+  to: ResolvedLayout,
+  elapsed: Duration,
+}
+
+impl LayoutAnimation {
+  /// Animation which already settled at the given layout, used for newly appearing elements.
+  pub fn settled(layout: ResolvedLayout, config: &AnimationConfig) -> Self {
+    Self {
+      from: layout.clone(),
+      to: layout,
+      elapsed: config.duration,
+    }
+  }
+
+  /// Retargets the animation if the solved layout changed and advances it by `delta`, returning
+  /// the layout to render this frame.
+  pub fn advance(
+    &mut self,
+    target: &ResolvedLayout,
+    delta: Duration,
+    config: &AnimationConfig,
+  ) -> ResolvedLayout {
+    if &self.to != target {
+      self.from = self.current(config);
+      self.to = target.clone();
+      self.elapsed = Duration::ZERO;
+    }
+
+    self.elapsed = (self.elapsed + delta).min(config.duration);
+    self.current(config)
+  }
+
+  fn current(&self, config: &AnimationConfig) -> ResolvedLayout {
+    let progress = if config.duration.is_zero() {
+      1.0
+    } else {
+      self.elapsed.as_secs_f32() / config.duration.as_secs_f32()
+    };
+    let t = config.easing.apply(progress);
+    let lerp = |from: f32, to: f32| from + (to - from) * t;
+
+    ResolvedLayout {
+      x: lerp(self.from.x, self.to.x),
+      y: lerp(self.from.y, self.to.y),
+      width: lerp(self.from.width, self.to.width),
+      height: lerp(self.from.height, self.to.height),
+    }
+  }
+}
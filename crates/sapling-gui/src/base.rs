@@ -1,8 +1,4 @@
-use std::{
-  any::Any,
-  hash::{DefaultHasher, Hash, Hasher},
-  marker::PhantomData,
-};
+use std::{any::Any, hash::Hash, marker::PhantomData};
 
 use raylib::{
   color::Color,
@@ -10,9 +6,10 @@ use raylib::{
 };
 
 use crate::{
-  component::Component,
+  component::{ChildrenProperty, Component},
+  input::{PointerEvent, PointerEventKind, action_hash},
   layout::UserElementConstraints,
-  prelude::{ElementContext, RenderContext, RenderFilter, StatefulContext},
+  prelude::{Element, ElementContext, RenderContext, RenderFilter, StatefulContext},
   theme::FontVariant,
 };
 
@@ -20,7 +17,11 @@ use crate::{
 #[derive(Debug, Default)]
 pub struct LayoutView;
 
-impl Component for LayoutView {}
+impl Component for LayoutView {
+  fn is_layout_only(&self) -> bool {
+    true
+  }
+}
 
 /// General purpose view that renders a stylable element supporting basic shapes.
 #[derive(Debug)]
@@ -36,7 +37,14 @@ impl StyledView {
   }
 
   pub fn with_background_color(mut self, color: Color) -> Self {
-    self.style.background_color = Some(color);
+    self.style.background = Some(BackgroundFill::Solid(color));
+    self
+  }
+
+  /// Fills the background with a linear gradient from `start` to `end`, see
+  /// [`BackgroundFill::LinearGradient`].
+  pub fn with_background_gradient(mut self, start: Color, end: Color, angle: f32) -> Self {
+    self.style.background = Some(BackgroundFill::LinearGradient { start, end, angle });
     self
   }
 
@@ -62,7 +70,20 @@ impl StyledView {
   }
 
   pub fn with_border(mut self, width: f32, color: Color) -> Self {
-    self.style.border_width = width;
+    self.with_border_sides(width, width, width, width, color)
+  }
+
+  /// Border with an individual width per side, a side with zero width is not drawn. Borders
+  /// with different widths are drawn as straight lines ignoring the border radius.
+  pub fn with_border_sides(
+    mut self,
+    top: f32,
+    right: f32,
+    bottom: f32,
+    left: f32,
+    color: Color,
+  ) -> Self {
+    self.style.border_widths = (top, right, bottom, left);
     self.style.border_color = color;
     self
   }
@@ -99,48 +120,113 @@ impl Component for StyledView {
       );
     }
 
-    if self.style.border_width > 0.0 {
+    let (top, right, bottom, left) = self.style.border_widths;
+    let layout = context.layout;
+    let uniform_border = top == right && right == bottom && bottom == left;
+
+    if uniform_border && top > 0.0 {
       context.renderer.draw_rectangle_border(
         Rectangle {
-          x: context.layout.x,
-          y: context.layout.y,
-          height: context.layout.height,
-          width: context.layout.width,
+          x: layout.x,
+          y: layout.y,
+          height: layout.height,
+          width: layout.width,
         },
         radii,
         self.style.border_color,
-        self.style.border_width,
+        top,
       );
+    } else if !uniform_border {
+      let sides = [
+        (top, Rectangle::new(layout.x, layout.y, layout.width, top)),
+        (
+          right,
+          Rectangle::new(
+            layout.x + layout.width - right,
+            layout.y,
+            right,
+            layout.height,
+          ),
+        ),
+        (
+          bottom,
+          Rectangle::new(
+            layout.x,
+            layout.y + layout.height - bottom,
+            layout.width,
+            bottom,
+          ),
+        ),
+        (
+          left,
+          Rectangle::new(layout.x, layout.y, left, layout.height),
+        ),
+      ];
+      for (width, rect) in sides {
+        if width > 0.0 {
+          context.renderer.draw_rectangle(
+            rect,
+            Vector4::new(0.0, 0.0, 0.0, 0.0),
+            self.style.border_color,
+          );
+        }
+      }
     }
 
-    if let Some(background_color) = self.style.background_color {
-      context.renderer.draw_rectangle(
-        Rectangle {
-          x: context.layout.x + self.style.border_width / 2.0,
-          y: context.layout.y + self.style.border_width / 2.0,
-          height: context.layout.height - self.style.border_width,
-          width: context.layout.width - self.style.border_width,
-        },
-        inner_radii(
-          radii,
-          self.style.border_width,
-          self.style.border_width <= 0.0,
-        ),
-        background_color,
-      );
+    if let Some(background) = self.style.background {
+      // A uniform border is centered on the edge of the layout, single sides are drawn inside
+      let (inset, background_radii) = if uniform_border {
+        (
+          (top / 2.0, right / 2.0, bottom / 2.0, left / 2.0),
+          inner_radii(radii, top, top <= 0.0),
+        )
+      } else {
+        ((top, right, bottom, left), radii)
+      };
+      let rect = Rectangle {
+        x: layout.x + inset.3,
+        y: layout.y + inset.0,
+        height: layout.height - inset.0 - inset.2,
+        width: layout.width - inset.1 - inset.3,
+      };
+      match background {
+        BackgroundFill::Solid(color) => {
+          context
+            .renderer
+            .draw_rectangle(rect, background_radii, color);
+        }
+        BackgroundFill::LinearGradient { start, end, angle } => {
+          context
+            .renderer
+            .draw_rectangle_gradient(rect, background_radii, start, end, angle);
+        }
+      }
     }
   }
 }
 
 #[derive(Debug)]
 pub struct ViewStyle {
-  background_color: Option<Color>,
+  background: Option<BackgroundFill>,
   border_radius: (f32, f32, f32, f32),
-  border_width: f32,
+  /// Top, right, bottom, left
+  border_widths: (f32, f32, f32, f32),
   border_color: Color,
   drop_shadow: Option<DropShadowStyle>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BackgroundFill {
+  Solid(Color),
+  /// Blends from `start` to `end` along the direction `angle` in degrees, 0 runs left to right
+  /// and 90 top to bottom.
+  LinearGradient {
+    start: Color,
+    end: Color,
+    angle: f32,
+  },
+}
+
 #[derive(Clone, Debug)]
 pub struct DropShadowStyle {
   pub offset: Vector2,
@@ -151,9 +237,9 @@ pub struct DropShadowStyle {
 impl Default for ViewStyle {
   fn default() -> Self {
     ViewStyle {
-      background_color: None,
+      background: None,
       border_radius: (0.0, 0.0, 0.0, 0.0),
-      border_width: 0.0,
+      border_widths: (0.0, 0.0, 0.0, 0.0),
       border_color: Color::BLACK,
       drop_shadow: None,
     }
@@ -179,6 +265,8 @@ pub struct TextView {
   vertical_alignment: TextVerticalAlignment,
   auto_size: bool,
   line_height: f32,
+  max_width: Option<f32>,
+  lines: Vec<String>,
 }
 
 impl TextView {
@@ -190,6 +278,8 @@ impl TextView {
       vertical_alignment: TextVerticalAlignment::Top,
       auto_size: true,
       line_height: 1.0,
+      max_width: None,
+      lines: Vec::new(),
     }
   }
 
@@ -207,15 +297,41 @@ impl TextView {
     self.vertical_alignment = alignment;
     self
   }
+
+  /// Wraps the text at whitespace into lines of at most `max_width`, a single word wider than
+  /// that still gets a line of its own.
+  pub fn with_max_width(mut self, max_width: f32) -> Self {
+    self.max_width = Some(max_width);
+    self
+  }
 }
 
 impl Component for TextView {
   fn construct(&mut self, context: &mut ElementContext) {
     let font_config = context.theme.text_config(self.variant);
-    let mut expected_size = font_config
-      .font
-      .calculate_text_size(&self.text, font_config.size);
+    self.lines = match self.max_width {
+      Some(max_width) => wrap_text(&self.text, max_width, |line| {
+        font_config
+          .font
+          .calculate_text_size(line, font_config.size)
+          .x
+      }),
+      None => vec![self.text.clone()],
+    };
+
+    let line_sizes = self
+      .lines
+      .iter()
+      .map(|line| font_config.font.calculate_text_size(line, font_config.size))
+      .collect::<Vec<_>>();
+    let mut expected_size = Vector2::new(
+      line_sizes.iter().map(|size| size.x).fold(0.0, f32::max),
+      line_sizes.first().map(|size| size.y).unwrap_or_default() * line_sizes.len() as f32,
+    );
     expected_size.y *= self.line_height;
+    if let Some(max_width) = self.max_width {
+      expected_size.x = expected_size.x.min(max_width);
+    }
 
     let grow_width = self.horizontal_alignment == TextHorizontalAlignment::Left && self.auto_size;
     let grow_height = self.vertical_alignment == TextVerticalAlignment::Top && self.auto_size;
@@ -233,34 +349,63 @@ impl Component for TextView {
 
   fn render(&self, context: &mut RenderContext) {
     let font_config = context.theme.text_config(self.variant);
-    let expected_size = font_config
-      .font
-      .calculate_text_size(&self.text, font_config.size);
-
-    let x = match self.horizontal_alignment {
-      TextHorizontalAlignment::Left => context.layout.x,
-      TextHorizontalAlignment::Center => {
-        (context.layout.width - expected_size.x) / 2.0 + context.layout.x
-      }
-      TextHorizontalAlignment::Right => context.layout.width - expected_size.x + context.layout.x,
-    };
+    let line_sizes = self
+      .lines
+      .iter()
+      .map(|line| font_config.font.calculate_text_size(line, font_config.size))
+      .collect::<Vec<_>>();
+    let single_line_height = line_sizes.first().map(|size| size.y).unwrap_or_default();
+    let line_advance = single_line_height * self.line_height;
+    let text_height = line_advance * line_sizes.len().saturating_sub(1) as f32 + single_line_height;
 
     let y = match self.vertical_alignment {
       TextVerticalAlignment::Top => context.layout.y,
       TextVerticalAlignment::Center => {
-        (context.layout.height - expected_size.y) / 2.0 + context.layout.y
+        (context.layout.height - text_height) / 2.0 + context.layout.y
       }
-      TextVerticalAlignment::Bottom => context.layout.height - expected_size.y + context.layout.y,
+      TextVerticalAlignment::Bottom => context.layout.height - text_height + context.layout.y,
     };
 
-    context.renderer.draw_text(
-      font_config.font,
-      &self.text,
-      Vector2::new(x, y),
-      font_config.size,
-      font_config.color,
-    );
+    for (index, (line, size)) in self.lines.iter().zip(line_sizes).enumerate() {
+      let x = match self.horizontal_alignment {
+        TextHorizontalAlignment::Left => context.layout.x,
+        TextHorizontalAlignment::Center => (context.layout.width - size.x) / 2.0 + context.layout.x,
+        TextHorizontalAlignment::Right => context.layout.width - size.x + context.layout.x,
+      };
+
+      context.renderer.draw_text(
+        font_config.font,
+        line,
+        Vector2::new(x, y + line_advance * index as f32),
+        font_config.size,
+        font_config.color,
+      );
+    }
+  }
+}
+
+/// Greedily breaks `text` into lines at whitespace so each line measures at most `max_width`.
+/// Explicit line breaks in the text are kept.
+fn wrap_text(text: &str, max_width: f32, mut measure: impl FnMut(&str) -> f32) -> Vec<String> {
+  let mut lines = Vec::new();
+  for paragraph in text.split('\n') {
+    let mut line = String::new();
+    for word in paragraph.split_whitespace() {
+      if line.is_empty() {
+        line.push_str(word);
+        continue;
+      }
+
+      let candidate = format!("{} {}", line, word);
+      if measure(&candidate) <= max_width {
+        line = candidate;
+      } else {
+        lines.push(std::mem::replace(&mut line, word.to_string()));
+      }
+    }
+    lines.push(line);
   }
+  lines
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -412,13 +557,27 @@ impl TextFormat {
 }
 
 pub struct Pressable {
-  on_press: Box<dyn Fn(&mut RenderContext) + 'static>,
+  on_press: PressHandler,
+}
+
+enum PressHandler {
+  Pointer(Box<dyn Fn(&mut ElementContext) + 'static>),
+  /// Checked while rendering, see [`Pressable::new`]
+  Render(Box<dyn Fn(&mut RenderContext) + 'static>),
 }
 
 impl Pressable {
+  /// Calls `on_press` when the pointer is released over the element, unless a child handled it.
+  pub fn on_press<F: Fn(&mut ElementContext) + 'static>(on_press: F) -> Self {
+    Self {
+      on_press: PressHandler::Pointer(Box::new(on_press)),
+    }
+  }
+
+  #[deprecated(note = "use `Pressable::on_press`, which receives the press as a pointer event")]
   pub fn new<F: Fn(&mut RenderContext) + 'static>(on_press: F) -> Self {
     Self {
-      on_press: Box::new(on_press),
+      on_press: PressHandler::Render(Box::new(on_press)),
     }
   }
 }
@@ -430,14 +589,26 @@ impl std::fmt::Debug for Pressable {
 }
 
 impl Component for Pressable {
+  fn on_pointer(&mut self, event: PointerEvent, context: &mut ElementContext) -> bool {
+    let PressHandler::Pointer(on_press) = &self.on_press else {
+      return false;
+    };
+    if event.kind != PointerEventKind::Up {
+      return false;
+    }
+
+    (*on_press)(context);
+    true
+  }
+
+  #[allow(deprecated)]
   fn render(&self, context: &mut RenderContext) {
+    let PressHandler::Render(on_press) = &self.on_press else {
+      return;
+    };
     if let Some(pressed_location) = context.input_state.mouse_press {
-      if pressed_location.x >= context.layout.x
-        && pressed_location.y >= context.layout.y
-        && pressed_location.x < context.layout.x + context.layout.width
-        && pressed_location.y < context.layout.y + context.layout.height
-      {
-        (*self.on_press)(context);
+      if context.layout.contains(pressed_location) {
+        (*on_press)(context);
       }
     }
   }
@@ -502,12 +673,9 @@ impl FocusableInteractiveView {
     action: impl Hash,
     handler: F,
   ) -> Self {
-    let hash = {
-      let mut hasher = DefaultHasher::new();
-      action.hash(&mut hasher);
-      hasher.finish()
-    };
-    self.action_handlers.push((hash, Box::new(handler)));
+    self
+      .action_handlers
+      .push((action_hash(action), Box::new(handler)));
     self
   }
 }
@@ -521,3 +689,323 @@ impl Component for FocusableInteractiveView {
     }
   }
 }
+
+/// Container scrolling its content with the mouse wheel, content outside of the view is clipped.
+///
+/// Only the innermost scroll view under the pointer scrolls, it receives the wheel movement as a
+/// [`PointerEventKind::Scroll`] event. Events arrive after the layout of the frame, so the content
+/// moves in the next frame. The scroll offset is clamped to the content size measured in the
+/// previous frame, as the layout of the current frame is only known after construction.
+pub struct ScrollView {
+  content: ChildrenProperty,
+  horizontal: bool,
+  scroll_speed: f32,
+  viewport_size: Option<MutableState<Vector2>>,
+  offset_state: Option<MutableState<Vector2>>,
+  offset: Vector2,
+  max_offset: Vector2,
+}
+
+impl std::fmt::Debug for ScrollView {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("ScrollView")
+      .field("horizontal", &self.horizontal)
+      .field("scroll_speed", &self.scroll_speed)
+      .finish()
+  }
+}
+
+impl ScrollView {
+  pub fn new() -> Self {
+    Self {
+      content: None,
+      horizontal: false,
+      scroll_speed: 20.0,
+      viewport_size: None,
+      offset_state: None,
+      offset: Vector2::zero(),
+      max_offset: Vector2::zero(),
+    }
+  }
+
+  pub fn with_content<F: FnOnce(&mut ElementContext) + 'static>(mut self, factory: F) -> Self {
+    self.content = Some(Box::new(factory));
+    self
+  }
+
+  /// Also scrolls horizontally, by default the content only moves vertically. Holding shift turns
+  /// vertical wheel movement into horizontal movement.
+  pub fn with_horizontal_scrolling(mut self) -> Self {
+    self.horizontal = true;
+    self
+  }
+
+  /// Pixels scrolled per unit of mouse wheel movement.
+  pub fn with_scroll_speed(mut self, scroll_speed: f32) -> Self {
+    self.scroll_speed = scroll_speed;
+    self
+  }
+}
+
+impl Component for ScrollView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    let (viewport_size, viewport_size_state) =
+      MutableState::new(context, Vector2::zero, "scroll_viewport_size");
+    let (content_size, content_size_state) =
+      MutableState::new(context, Vector2::zero, "scroll_content_size");
+    let (offset, offset_state) = MutableState::new(context, Vector2::zero, "scroll_offset");
+    self.viewport_size = Some(viewport_size_state);
+    self.offset_state = Some(offset_state);
+
+    // The content may have shrunk since the offset was set
+    self.max_offset = Vector2::new(
+      if self.horizontal {
+        (content_size.x - viewport_size.x).max(0.0)
+      } else {
+        0.0
+      },
+      (content_size.y - viewport_size.y).max(0.0),
+    );
+    let offset = Vector2::new(
+      offset.x.clamp(0.0, self.max_offset.x),
+      offset.y.clamp(0.0, self.max_offset.y),
+    );
+    self.offset = offset;
+    offset_state.set_direct(context, offset);
+
+    let content = self.content.take();
+    ScrollContent {
+      size: content_size_state,
+    }
+    .with_layout(vec![UserElementConstraints::relative_to_parent(
+      -offset.x, -offset.y,
+    )])
+    .with_children(move |context| {
+      if let Some(content) = content {
+        content(context);
+      }
+    })
+    .build(context);
+  }
+
+  fn render(&self, context: &mut RenderContext) {
+    if let Some(viewport_size) = &self.viewport_size {
+      viewport_size.set_direct(
+        context,
+        Vector2::new(context.layout.width, context.layout.height),
+      );
+    }
+  }
+
+  fn on_pointer(&mut self, event: PointerEvent, context: &mut ElementContext) -> bool {
+    let (PointerEventKind::Scroll { delta }, Some(offset_state)) = (event.kind, self.offset_state)
+    else {
+      return false;
+    };
+    let delta = if self.horizontal {
+      context.input_state.shift_scroll(delta)
+    } else {
+      delta
+    };
+
+    let offset = Vector2::new(
+      (self.offset.x - delta.x * self.scroll_speed).clamp(0.0, self.max_offset.x),
+      (self.offset.y - delta.y * self.scroll_speed).clamp(0.0, self.max_offset.y),
+    );
+    // Already at the edge, an enclosing scroll view can take the movement instead
+    if offset == self.offset {
+      return false;
+    }
+    self.offset = offset;
+    offset_state.set_direct(context, offset);
+    true
+  }
+
+  fn clips_children(&self) -> bool {
+    true
+  }
+}
+
+/// Sized by the content of a [`ScrollView`], records its size for clamping the scroll offset.
+struct ScrollContent {
+  size: MutableState<Vector2>,
+}
+
+impl std::fmt::Debug for ScrollContent {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("ScrollContent").finish()
+  }
+}
+
+impl Component for ScrollContent {
+  fn render(&self, context: &mut RenderContext) {
+    self.size.set_direct(
+      context,
+      Vector2::new(context.layout.width, context.layout.height),
+    );
+  }
+}
+
+/// Places the children built by its content below each other, separated by `gap`.
+///
+/// The children shouldn't constrain their own y position, the stack takes its size from the
+/// children unless it is sized explicitly.
+pub struct VStack {
+  gap: f32,
+  content: ChildrenProperty,
+}
+
+impl std::fmt::Debug for VStack {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("VStack").field("gap", &self.gap).finish()
+  }
+}
+
+impl VStack {
+  pub fn new(gap: f32) -> Self {
+    Self { gap, content: None }
+  }
+
+  pub fn with_content<F: FnOnce(&mut ElementContext) + 'static>(mut self, factory: F) -> Self {
+    self.content = Some(Box::new(factory));
+    self
+  }
+}
+
+impl Component for VStack {
+  fn construct(&mut self, context: &mut ElementContext) {
+    if let Some(content) = self.content.take() {
+      content(context);
+    }
+    constrain_children(context, |children, index| match index.checked_sub(1) {
+      Some(previous) => UserElementConstraints::anchor_to_bottom_of(children[previous], self.gap),
+      None => UserElementConstraints::relative_to_parent_vertical(0.0),
+    });
+  }
+}
+
+/// Horizontal counterpart of [`VStack`], places its children right of each other.
+pub struct HStack {
+  gap: f32,
+  content: ChildrenProperty,
+}
+
+impl std::fmt::Debug for HStack {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("HStack").field("gap", &self.gap).finish()
+  }
+}
+
+impl HStack {
+  pub fn new(gap: f32) -> Self {
+    Self { gap, content: None }
+  }
+
+  pub fn with_content<F: FnOnce(&mut ElementContext) + 'static>(mut self, factory: F) -> Self {
+    self.content = Some(Box::new(factory));
+    self
+  }
+}
+
+impl Component for HStack {
+  fn construct(&mut self, context: &mut ElementContext) {
+    if let Some(content) = self.content.take() {
+      content(context);
+    }
+    constrain_children(context, |children, index| match index.checked_sub(1) {
+      Some(previous) => UserElementConstraints::anchor_to_right_of(children[previous], self.gap),
+      None => UserElementConstraints::relative_to_parent_horizontal(0.0),
+    });
+  }
+}
+
+/// Places the children built by its content in rows of `columns` children, row by row. Each
+/// child is anchored right of its left neighbor and below the child above it, separated by `gap`.
+///
+/// Like the stacks, the grid takes its size from the children unless it is sized explicitly.
+pub struct GridView {
+  columns: usize,
+  gap: f32,
+  content: ChildrenProperty,
+}
+
+impl std::fmt::Debug for GridView {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("GridView")
+      .field("columns", &self.columns)
+      .field("gap", &self.gap)
+      .finish()
+  }
+}
+
+impl GridView {
+  pub fn new(columns: usize, gap: f32) -> Self {
+    Self {
+      columns: columns.max(1),
+      gap,
+      content: None,
+    }
+  }
+
+  pub fn with_content<F: FnOnce(&mut ElementContext) + 'static>(mut self, factory: F) -> Self {
+    self.content = Some(Box::new(factory));
+    self
+  }
+}
+
+impl Component for GridView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    if let Some(content) = self.content.take() {
+      content(context);
+    }
+    constrain_children(context, |children, index| {
+      let horizontal = match index % self.columns {
+        0 => UserElementConstraints::relative_to_parent_horizontal(0.0),
+        _ => UserElementConstraints::anchor_to_right_of(children[index - 1], self.gap),
+      };
+      let vertical = match index.checked_sub(self.columns) {
+        Some(above) => UserElementConstraints::anchor_to_bottom_of(children[above], self.gap),
+        None => UserElementConstraints::relative_to_parent_vertical(0.0),
+      };
+      horizontal.merged(&vertical)
+    });
+  }
+}
+
+/// Applies the constraints returned for each direct child, given all children and its index.
+fn constrain_children(
+  context: &mut ElementContext,
+  constraints_for: impl Fn(&[Element], usize) -> UserElementConstraints,
+) {
+  let children = context.child_elements();
+  for (index, child) in children.iter().enumerate() {
+    context.set_element_constraints(child, constraints_for(&children, index).constraints);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_wrap_text() {
+    // Every character is 1 unit wide
+    let lines = wrap_text(
+      "the quick brown fox jumps over the lazy dog",
+      10.0,
+      |line| line.chars().count() as f32,
+    );
+    assert_eq!(
+      lines,
+      vec!["the quick", "brown fox", "jumps over", "the lazy", "dog"]
+    );
+  }
+
+  #[test]
+  fn test_wrap_text_keeps_long_words_and_line_breaks() {
+    let lines = wrap_text("a extraordinarily\nlong b", 5.0, |line| {
+      line.chars().count() as f32
+    });
+    assert_eq!(lines, vec!["a", "extraordinarily", "long", "b"]);
+  }
+}
@@ -1,6 +1,7 @@
 use std::{any::Any, fmt::Debug};
 
 use crate::{
+  input::PointerEvent,
   layout::UserElementConstraints,
   orchestrator::{Element, ElementContext},
   prelude::RenderContext,
@@ -35,20 +36,52 @@ pub trait ComponentElement: Sized + Debug + 'static {
   }
 
   fn build(self, context: &mut ElementContext) -> Element;
+
+  /// Like [`ComponentElement::build`], but the element is identified by `key` instead of its
+  /// position, see [`ElementContext::allocate_element_keyed`].
+  fn build_keyed(self, context: &mut ElementContext, key: &str) -> Element;
 }
 
 pub trait Component: Debug + Any {
   fn construct(&mut self, _context: &mut ElementContext) {}
   fn render(&self, _context: &mut RenderContext) {}
+
+  /// Called once the first frame an element appears, after the whole tree of that frame was
+  /// constructed. An element is identified by its key path from the root, so an element rebuilt
+  /// at the same position in the following frames counts as the same element.
+  fn on_mount(&mut self, _context: &mut ElementContext) {}
+
+  /// Called on the instance of the last frame an element appeared in, during the first frame
+  /// its key path is missing from the tree.
+  fn on_unmount(&mut self, _context: &mut ElementContext) {}
+
+  /// Called for pointer events hitting this element, or one of its children which didn't handle
+  /// the event. Returning `false` bubbles the event further up to the parent element.
+  fn on_pointer(&mut self, _event: PointerEvent, _context: &mut ElementContext) -> bool {
+    false
+  }
+
+  /// Components which neither construct children nor render anything can return `true` here so
+  /// the orchestrator only allocates and lays out their element, skipping `construct`/`render`.
+  fn is_layout_only(&self) -> bool {
+    false
+  }
+
+  /// Clips everything rendered by descendants of this element to its layout.
+  fn clips_children(&self) -> bool {
+    false
+  }
 }
 
-impl<T: ComponentElement> ComponentElement for LayoutedComponent<T> {
-  fn build(self, context: &mut ElementContext) -> Element {
-    let element = self.component.build(context);
+impl<T: ComponentElement> LayoutedComponent<T> {
+  fn apply_layout(
+    layout_constraints: Vec<UserElementConstraints>,
+    element: Element,
+    context: &mut ElementContext,
+  ) -> Element {
     context.set_element_constraints(
       &element,
-      self
-        .layout_constraints
+      layout_constraints
         .into_iter()
         .flat_map(|c| c.constraints.into_iter())
         .collect(),
@@ -57,6 +90,18 @@ impl<T: ComponentElement> ComponentElement for LayoutedComponent<T> {
   }
 }
 
+impl<T: ComponentElement> ComponentElement for LayoutedComponent<T> {
+  fn build(self, context: &mut ElementContext) -> Element {
+    let element = self.component.build(context);
+    Self::apply_layout(self.layout_constraints, element, context)
+  }
+
+  fn build_keyed(self, context: &mut ElementContext, key: &str) -> Element {
+    let element = self.component.build_keyed(context, key);
+    Self::apply_layout(self.layout_constraints, element, context)
+  }
+}
+
 impl<T: ComponentElement> std::fmt::Debug for LayoutedComponent<T> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.debug_struct("LayoutedComponent")
@@ -72,6 +117,13 @@ impl<T: ComponentElement> ComponentElement for ParentComponent<T> {
     (self.children)(&mut child_context);
     element
   }
+
+  fn build_keyed(self, context: &mut ElementContext, key: &str) -> Element {
+    let element = self.component.build_keyed(context, key);
+    let mut child_context = context.get_context_for_child(&element);
+    (self.children)(&mut child_context);
+    element
+  }
 }
 
 impl<T: ComponentElement> std::fmt::Debug for ParentComponent<T> {
@@ -88,6 +140,12 @@ impl<T: Component + 'static> ComponentElement for T {
     context.construct_element(&element);
     element
   }
+
+  fn build_keyed(self, context: &mut ElementContext, key: &str) -> Element {
+    let element = context.allocate_element_keyed(self, key);
+    context.construct_element(&element);
+    element
+  }
 }
 
 pub type ChildrenProperty = Option<Box<dyn FnOnce(&mut ElementContext)>>;
@@ -9,11 +9,19 @@ use crate::{
 };
 
 #[derive(Debug)]
-pub struct DebuggerView;
+pub struct DebuggerView {
+  root: Option<usize>,
+}
 
 impl DebuggerView {
   pub fn new() -> Self {
-    Self {}
+    Self { root: None }
+  }
+
+  /// Only shows the element with the given id and its descendants in the tree.
+  pub fn with_root(mut self, element_id: usize) -> Self {
+    self.root = Some(element_id);
+    self
   }
 }
 
@@ -21,6 +29,7 @@ impl Component for DebuggerView {
   fn construct(&mut self, context: &mut crate::prelude::ElementContext) {
     let (selected_node, selected_node_state) =
       MutableState::<Option<usize>>::new(context, || None, "selected_node");
+    let root = self.root.unwrap_or(0);
 
     StyledView::new()
       .with_background_color(Color::BLACK.alpha(0.4))
@@ -30,17 +39,34 @@ impl Component for DebuggerView {
         32.0, 32.0, 600.0, 500.0,
       )])
       .with_children(move |context| {
-        if let Some(root_node) = context
-          .prev_debug_nodes
-          .as_ref()
-          .and_then(|nodes| nodes.first())
-        {
-          let mut height = context.theme.spacing_default;
-          render_node_recursive(root_node, 0, &mut height, context, selected_node_state);
+        let prev_debug_nodes = context.prev_debug_nodes;
+        let Some(nodes) = prev_debug_nodes.as_ref() else {
+          return;
+        };
+
+        let mut height = context.theme.spacing_default;
+        for (index, (id, indentation)) in visible_nodes(nodes, root).into_iter().enumerate() {
+          if index > 0 {
+            height += 16.0;
+          }
+          render_node(
+            &nodes[id],
+            indentation,
+            height,
+            context,
+            selected_node_state,
+          );
         }
       })
       .build(context);
 
+    let hovered_node = context.prev_debug_nodes.as_ref().and_then(|nodes| {
+      hovered_node(nodes, root, context.input_state.pointer_position).map(|id| nodes[id].clone())
+    });
+    if let Some(hovered_node) = hovered_node {
+      HoverInfoView::new(hovered_node, context.input_state.pointer_position).build(context);
+    }
+
     if let Some(selected_node) = selected_node.and_then(|node_id| {
       context
         .prev_debug_nodes
@@ -175,26 +201,58 @@ impl Component for DebuggerView {
   }
 }
 
-fn render_node_recursive(
+/// Ids of the nodes shown in the tree together with their indentation, depth first starting at
+/// `root`. The debugger's own subtree is left out and an unknown root shows nothing.
+fn visible_nodes(nodes: &[DebugAllocatedElement], root: usize) -> Vec<(usize, usize)> {
+  let mut visible = Vec::new();
+  let mut pending = vec![(root, 0)];
+  while let Some((id, indentation)) = pending.pop() {
+    let Some(node) = nodes.get(id) else {
+      continue;
+    };
+    if node.component_name.contains("DebuggerView") {
+      continue;
+    }
+
+    visible.push((id, indentation));
+    pending.extend(
+      node
+        .children
+        .iter()
+        .rev()
+        .map(|child| (*child, indentation + 1)),
+    );
+  }
+  visible
+}
+
+/// The innermost shown node under the pointer. Children are allocated after their parents, so
+/// the node with the highest id wins.
+fn hovered_node(nodes: &[DebugAllocatedElement], root: usize, position: Vector2) -> Option<usize> {
+  visible_nodes(nodes, root)
+    .into_iter()
+    .map(|(id, _)| id)
+    .filter(|id| nodes[*id].layout.contains(position))
+    .max()
+}
+
+fn render_node(
   node: &DebugAllocatedElement,
   indentation: usize,
-  height: &mut f32,
+  height: f32,
   context: &mut ElementContext,
   selected_node_state: MutableState<Option<usize>>,
 ) {
   let node = node.clone();
-  if node.component_name.contains("DebuggerView") {
-    return;
-  }
 
-  Pressable::new(move |context| {
+  Pressable::on_press(move |context| {
     selected_node_state.set_direct(context, Some(node.id));
     println!("Element clicked in debugger:\n{}", node.debug_info);
   })
   .with_layout(vec![
     UserElementConstraints::relative_to_parent(
       indentation as f32 * context.theme.spacing_large + context.theme.spacing_default,
-      *height,
+      height,
     ),
     UserElementConstraints::fixed_size(10.0, 10.0),
   ])
@@ -214,22 +272,11 @@ fn render_node_recursive(
     .with_layout(vec![
       UserElementConstraints::relative_to_parent(
         indentation as f32 * context.theme.spacing_large + context.theme.spacing_default,
-        *height,
+        height,
       ),
       UserElementConstraints::fixed_size(10.0, 10.0),
     ])
     .build(context);
-
-  for child in &node.children {
-    *height += 16.0;
-    render_node_recursive(
-      &context.prev_debug_nodes.as_ref().unwrap()[*child],
-      indentation + 1,
-      height,
-      context,
-      selected_node_state,
-    );
-  }
 }
 
 #[derive(Debug)]
@@ -269,6 +316,73 @@ impl Component for HighlightOverlayView {
   }
 }
 
+/// Panel next to the pointer listing the resolved layout and the constraints of a node.
+#[derive(Debug)]
+struct HoverInfoView {
+  node: DebugAllocatedElement,
+  position: Vector2,
+}
+
+impl HoverInfoView {
+  fn new(node: DebugAllocatedElement, position: Vector2) -> Self {
+    Self { node, position }
+  }
+}
+
+impl Component for HoverInfoView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    let layout = self.node.layout.clone();
+    let constraints = self.node.layout_constraints.clone();
+    let line_height = 16.0;
+    let padding = context.theme.spacing_default;
+
+    StyledView::new()
+      .with_background_color(Color::BLACK.alpha(0.8))
+      .with_border(1.0, Color::WHITE.alpha(0.6))
+      .with_border_radius_even(4.0)
+      .with_layout(vec![
+        UserElementConstraints::absolute_position(self.position.x + 12.0, self.position.y + 12.0),
+        UserElementConstraints::fixed_size(
+          320.0,
+          (constraints.len() + 2) as f32 * line_height + 2.0 * padding,
+        ),
+      ])
+      .with_children(move |context| {
+        let mut offset = padding;
+        for line in [
+          format!(
+            "X: {} Y: {} W: {} H: {}",
+            layout.x, layout.y, layout.width, layout.height
+          ),
+          format!("{} constraints", constraints.len()),
+        ] {
+          TextView::new(
+            FontVariant::Custom {
+              color: Color::WHITE,
+              size: 14.0,
+            },
+            line,
+          )
+          .with_layout(vec![UserElementConstraints::relative_to_parent(
+            padding, offset,
+          )])
+          .build(context);
+          offset += line_height;
+        }
+
+        for constraint in &constraints {
+          ConstraintTextView::new(constraint.clone())
+            .with_layout(vec![UserElementConstraints::relative_to_parent(
+              padding, offset,
+            )])
+            .build(context);
+          offset += line_height;
+        }
+      })
+      .build(context);
+  }
+}
+
 #[derive(Debug)]
 struct ConstraintTextView {
   constraint: CompiledConstraint,
@@ -287,8 +401,93 @@ impl Component for ConstraintTextView {
         color: Color::WHITE,
         size: 14.0,
       },
-      self.constraint.get_formular(),
+      self.constraint.to_string(),
     )
     .build(context);
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn node(id: usize, parent_id: Option<usize>, children: Vec<usize>) -> DebugAllocatedElement {
+    DebugAllocatedElement {
+      id,
+      parent_id,
+      key: format!("{}", id),
+      debug_info: String::new(),
+      component_name: "StyledView".to_string(),
+      layout: ResolvedLayout {
+        width: 0.0,
+        height: 0.0,
+        x: 0.0,
+        y: 0.0,
+      },
+      layout_constraints: Vec::new(),
+      children,
+    }
+  }
+
+  fn positioned(
+    mut node: DebugAllocatedElement,
+    x: f32,
+    y: f32,
+    size: f32,
+  ) -> DebugAllocatedElement {
+    node.layout = ResolvedLayout {
+      width: size,
+      height: size,
+      x,
+      y,
+    };
+    node
+  }
+
+  // 0 -> (1 -> 3, 2)
+  fn debug_tree() -> Vec<DebugAllocatedElement> {
+    vec![
+      positioned(node(0, None, vec![1, 2]), 0.0, 0.0, 100.0),
+      positioned(node(1, Some(0), vec![3]), 0.0, 0.0, 50.0),
+      positioned(node(2, Some(0), vec![]), 50.0, 50.0, 50.0),
+      positioned(node(3, Some(1), vec![]), 10.0, 10.0, 10.0),
+    ]
+  }
+
+  #[test]
+  fn test_visible_nodes_of_whole_tree() {
+    assert_eq!(
+      visible_nodes(&debug_tree(), 0),
+      vec![(0, 0), (1, 1), (3, 2), (2, 1)]
+    );
+  }
+
+  #[test]
+  fn test_visible_nodes_of_subtree() {
+    assert_eq!(visible_nodes(&debug_tree(), 1), vec![(1, 0), (3, 1)]);
+    assert_eq!(visible_nodes(&debug_tree(), 2), vec![(2, 0)]);
+  }
+
+  #[test]
+  fn test_visible_nodes_of_invalid_root() {
+    assert_eq!(visible_nodes(&debug_tree(), 42), vec![]);
+  }
+
+  #[test]
+  fn test_hovered_node_is_innermost() {
+    let nodes = debug_tree();
+    assert_eq!(hovered_node(&nodes, 0, Vector2::new(15.0, 15.0)), Some(3));
+    assert_eq!(hovered_node(&nodes, 0, Vector2::new(30.0, 5.0)), Some(1));
+    assert_eq!(hovered_node(&nodes, 0, Vector2::new(75.0, 75.0)), Some(2));
+    assert_eq!(hovered_node(&nodes, 0, Vector2::new(75.0, 25.0)), Some(0));
+    assert_eq!(hovered_node(&nodes, 0, Vector2::new(150.0, 25.0)), None);
+  }
+
+  #[test]
+  fn test_hovered_node_outside_of_root() {
+    assert_eq!(
+      hovered_node(&debug_tree(), 2, Vector2::new(15.0, 15.0)),
+      None
+    );
+  }
+}
@@ -1,4 +1,4 @@
-use std::ffi::CString;
+use std::{collections::HashMap, ffi::CString};
 
 use anyhow::{Result, anyhow};
 use raylib::{
@@ -18,10 +18,12 @@ pub struct Font {
   glyph_info: RSliceGlyphInfo,
   rectangles: Vec<Rectangle>,
   texture: Texture2D,
+  measure_cache: TextMeasureCache,
 }
 
 impl Font {
   const FONT_SIZE: i32 = 32;
+  const MAX_MEASURE_CACHE_ENTRIES: usize = 4096;
   const FONT_GLYPHS: &'static str = "!\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHI\nJKLMNOPQRSTUVWXYZ[]^_`abcdefghijklmn\nopqrstuvwxyz{|}~";
 
   pub fn new(raylib: &mut RaylibHandle, thread: &RaylibThread, font_path: &str) -> Result<Self> {
@@ -64,12 +66,39 @@ impl Font {
       glyph_info,
       rectangles,
       texture,
+      measure_cache: TextMeasureCache::new(Self::MAX_MEASURE_CACHE_ENTRIES),
     })
   }
 
+  /// Measures the text, repeated measurements of the same text and size are served from a cache.
+  /// The atlas never changes after loading, so cached sizes stay valid for the font's lifetime.
   pub fn calculate_text_size(&mut self, text: &str, font_size: f32) -> Vector2 {
+    let Self {
+      glyph_info,
+      rectangles,
+      texture,
+      measure_cache,
+      ..
+    } = self;
+    measure_cache.get_or_measure(text, font_size, |text, font_size| {
+      Self::measure_text(glyph_info, rectangles, texture, text, font_size)
+    })
+  }
+
+  /// Number of texts measured so far, cache hits excluded.
+  pub fn text_measurements(&self) -> usize {
+    self.measure_cache.measured()
+  }
+
+  fn measure_text(
+    glyph_info: &mut RSliceGlyphInfo,
+    rectangles: &mut [Rectangle],
+    texture: &Texture2D,
+    text: &str,
+    font_size: f32,
+  ) -> Vector2 {
     let glyph_info_slice_ffi = unsafe {
-      std::mem::transmute::<_, &mut ffi::GlyphInfo>(&mut self.glyph_info.as_mut().as_mut()[0])
+      std::mem::transmute::<_, &mut ffi::GlyphInfo>(&mut glyph_info.as_mut().as_mut()[0])
     };
 
     let font = RaylibFFIFont {
@@ -77,13 +106,13 @@ impl Font {
       glyphCount: Self::FONT_GLYPHS.len() as i32,
       glyphPadding: 0,
       glyphs: glyph_info_slice_ffi as _,
-      recs: self.rectangles.as_mut_ptr(),
+      recs: rectangles.as_mut_ptr(),
       texture: Texture {
-        format: self.texture.format,
-        height: self.texture.height,
-        width: self.texture.width,
-        mipmaps: self.texture.mipmaps,
-        id: self.texture.id,
+        format: texture.format,
+        height: texture.height,
+        width: texture.width,
+        mipmaps: texture.mipmaps,
+        id: texture.id,
       },
     };
 
@@ -128,6 +157,62 @@ impl Font {
   }
 }
 
+/// Text sizes keyed by font size and text. Once full the cache starts over, measuring is cheap
+/// enough that tracking recency isn't worth it.
+struct TextMeasureCache {
+  entries: HashMap<u32, HashMap<String, Vector2>>,
+  len: usize,
+  max_entries: usize,
+  measured: usize,
+}
+
+impl TextMeasureCache {
+  fn new(max_entries: usize) -> Self {
+    Self {
+      entries: HashMap::new(),
+      len: 0,
+      max_entries,
+      measured: 0,
+    }
+  }
+
+  fn get_or_measure(
+    &mut self,
+    text: &str,
+    font_size: f32,
+    measure: impl FnOnce(&str, f32) -> Vector2,
+  ) -> Vector2 {
+    let size_key = font_size.to_bits();
+    if let Some(size) = self
+      .entries
+      .get(&size_key)
+      .and_then(|texts| texts.get(text))
+    {
+      return *size;
+    }
+
+    if self.len >= self.max_entries {
+      self.entries.clear();
+      self.len = 0;
+    }
+
+    let size = measure(text, font_size);
+    self.measured += 1;
+    self.len += 1;
+    self
+      .entries
+      .entry(size_key)
+      .or_default()
+      .insert(text.to_string(), size);
+    size
+  }
+
+  /// Number of measurements that missed the cache.
+  fn measured(&self) -> usize {
+    self.measured
+  }
+}
+
 struct FontWrapper(RaylibFFIFont);
 
 impl AsRef<RaylibFFIFont> for FontWrapper {
@@ -135,3 +220,48 @@ impl AsRef<RaylibFFIFont> for FontWrapper {
     &self.0
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::cell::Cell;
+
+  use super::*;
+
+  #[test]
+  fn test_same_text_is_measured_once() {
+    let calls = Cell::new(0);
+    let measure = |text: &str, font_size: f32| {
+      calls.set(calls.get() + 1);
+      Vector2::new(text.len() as f32 * font_size, font_size)
+    };
+    let mut cache = TextMeasureCache::new(8);
+
+    let first = cache.get_or_measure("hello", 14.0, measure);
+    let second = cache.get_or_measure("hello", 14.0, measure);
+
+    assert_eq!(first, Vector2::new(70.0, 14.0));
+    assert_eq!(first, second);
+    assert_eq!(calls.get(), 1);
+    assert_eq!(cache.measured(), 1);
+
+    // A different size is a different entry
+    assert_eq!(
+      cache.get_or_measure("hello", 28.0, measure),
+      Vector2::new(140.0, 28.0)
+    );
+    assert_eq!(calls.get(), 2);
+  }
+
+  #[test]
+  fn test_full_cache_starts_over() {
+    let mut cache = TextMeasureCache::new(2);
+    let measure = |_: &str, _: f32| Vector2::new(1.0, 1.0);
+    cache.get_or_measure("a", 14.0, measure);
+    cache.get_or_measure("b", 14.0, measure);
+    cache.get_or_measure("c", 14.0, measure);
+    cache.get_or_measure("a", 14.0, measure);
+
+    assert_eq!(cache.measured(), 4);
+    assert!(cache.len <= 2);
+  }
+}
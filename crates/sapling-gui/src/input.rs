@@ -5,9 +5,85 @@ use std::{
 
 use raylib::{RaylibHandle, ffi::KeyboardKey, math::Vector2};
 
+use crate::layout::ResolvedLayout;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointerEventKind {
+  Down,
+  Up,
+  Move,
+  Scroll { delta: Vector2 },
+}
+
+/// Pointer event as received by [`crate::prelude::Component::on_pointer`]. The position is
+/// relative to the element handling the event, the orchestrator translates it while bubbling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointerEvent {
+  pub kind: PointerEventKind,
+  pub position: Vector2,
+}
+
+impl PointerEvent {
+  pub fn new(kind: PointerEventKind, position: Vector2) -> Self {
+    Self { kind, position }
+  }
+
+  pub fn relative_to(&self, layout: &ResolvedLayout) -> Self {
+    Self {
+      kind: self.kind,
+      position: Vector2::new(self.position.x - layout.x, self.position.y - layout.y),
+    }
+  }
+}
+
+/// Modifier keys held down, either the current state or the state an action requires.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyModifiers {
+  pub shift: bool,
+  pub ctrl: bool,
+  pub alt: bool,
+}
+
+impl KeyModifiers {
+  pub const NONE: Self = Self {
+    shift: false,
+    ctrl: false,
+    alt: false,
+  };
+  pub const SHIFT: Self = Self {
+    shift: true,
+    ctrl: false,
+    alt: false,
+  };
+  pub const CTRL: Self = Self {
+    shift: false,
+    ctrl: true,
+    alt: false,
+  };
+  pub const ALT: Self = Self {
+    shift: false,
+    ctrl: false,
+    alt: true,
+  };
+}
+
 #[derive(Default)]
 pub struct InputState {
+  /// Pointer events of the current frame in window coordinates
+  pub pointer_events: Vec<PointerEvent>,
+  #[deprecated(note = "use `pointer_events`, a release is a `PointerEventKind::Up` event")]
   pub mouse_press: Option<Vector2>,
+  /// Pointer position in window coordinates, also known in frames without pointer events
+  pub pointer_position: Vector2,
+  /// Mouse wheel movement of the current frame, zero if the wheel didn't move. Unlike the delta
+  /// of a [`PointerEventKind::Scroll`] event, it is already passed through
+  /// [`InputState::shift_scroll`].
+  pub scroll_delta: Vector2,
+  pub shift_down: bool,
+  pub ctrl_down: bool,
+  pub alt_down: bool,
+  /// Characters typed this frame in order, after keyboard layout and modifiers were applied
+  pub typed_text: String,
   action_map: ActionMap,
   key_presses: HashMap<u64, bool>,
 }
@@ -16,26 +92,137 @@ impl InputState {
   pub fn from_raylib(handle: &mut RaylibHandle, action_map: ActionMap) -> Self {
     let mut state = Self::default();
 
+    state.shift_down = handle.is_key_down(KeyboardKey::KEY_LEFT_SHIFT)
+      || handle.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT);
+    state.ctrl_down = handle.is_key_down(KeyboardKey::KEY_LEFT_CONTROL)
+      || handle.is_key_down(KeyboardKey::KEY_RIGHT_CONTROL);
+    state.alt_down = handle.is_key_down(KeyboardKey::KEY_LEFT_ALT)
+      || handle.is_key_down(KeyboardKey::KEY_RIGHT_ALT);
+
+    let scroll = handle.get_mouse_wheel_move_v();
+    state.scroll_delta = state.shift_scroll(scroll);
+
+    let position = handle.get_mouse_position();
+    state.pointer_position = position;
+    let mut push_event = |kind| state.pointer_events.push(PointerEvent::new(kind, position));
+
+    let delta = handle.get_mouse_delta();
+    if delta.x != 0.0 || delta.y != 0.0 {
+      push_event(PointerEventKind::Move);
+    }
+    if handle.is_mouse_button_pressed(raylib::ffi::MouseButton::MOUSE_BUTTON_LEFT) {
+      push_event(PointerEventKind::Down);
+    }
     if handle.is_mouse_button_released(raylib::ffi::MouseButton::MOUSE_BUTTON_LEFT) {
-      state.mouse_press = Some(handle.get_mouse_position());
+      push_event(PointerEventKind::Up);
+      #[allow(deprecated)]
+      {
+        state.mouse_press = Some(position);
+      }
+    }
+    if scroll.x != 0.0 || scroll.y != 0.0 {
+      push_event(PointerEventKind::Scroll { delta: scroll });
     }
 
-    for (hash, key) in &action_map.keys {
-      state.key_presses.insert(*hash, handle.is_key_pressed(*key));
+    while let Some(character) = handle.get_char_pressed() {
+      state.typed_text.push(character);
     }
 
-    state.action_map = action_map;
+    state.resolve_actions(action_map, |key| handle.is_key_pressed(key));
     state
   }
 
+  /// Turns vertical wheel movement into horizontal movement while shift is held, as most mouse
+  /// wheels only report vertical movement. Scroll events keep the raw delta, so the receiver
+  /// decides whether it wants this.
+  pub fn shift_scroll(&self, delta: Vector2) -> Vector2 {
+    if self.shift_down && delta.x == 0.0 {
+      Vector2::new(delta.y, 0.0)
+    } else {
+      delta
+    }
+  }
+
+  /// Replaces the pointer events, e.g. to replay synthetic input.
+  pub fn with_pointer_events(mut self, pointer_events: Vec<PointerEvent>) -> Self {
+    self.pointer_events = pointer_events;
+    self
+  }
+
+  /// Replaces the pointer position, e.g. to replay synthetic input.
+  pub fn with_pointer_position(mut self, pointer_position: Vector2) -> Self {
+    self.pointer_position = pointer_position;
+    self
+  }
+
+  /// Replaces the scroll delta, e.g. to replay synthetic input.
+  pub fn with_scroll_delta(mut self, scroll_delta: Vector2) -> Self {
+    self.scroll_delta = scroll_delta;
+    self
+  }
+
+  /// Replaces the typed text, e.g. to replay synthetic input.
+  pub fn with_typed_text(mut self, typed_text: &str) -> Self {
+    self.typed_text = typed_text.to_string();
+    self
+  }
+
+  /// Replaces the held modifiers, has to be called before [`Self::with_pressed_keys`] to affect
+  /// which actions are pressed.
+  pub fn with_modifiers(mut self, modifiers: KeyModifiers) -> Self {
+    self.shift_down = modifiers.shift;
+    self.ctrl_down = modifiers.ctrl;
+    self.alt_down = modifiers.alt;
+    self
+  }
+
+  /// Resolves the actions of `action_map` as if exactly `keys` were pressed this frame.
+  pub fn with_pressed_keys(mut self, action_map: ActionMap, keys: &[KeyboardKey]) -> Self {
+    self.resolve_actions(action_map, |key| keys.contains(&key));
+    self
+  }
+
+  pub fn modifiers(&self) -> KeyModifiers {
+    KeyModifiers {
+      shift: self.shift_down,
+      ctrl: self.ctrl_down,
+      alt: self.alt_down,
+    }
+  }
+
   pub fn is_action_pressed(&self, action: u64) -> bool {
     *self.key_presses.get(&action).unwrap_or(&false)
   }
+
+  /// An action is pressed if its key was pressed while exactly its modifiers were held, so
+  /// Shift+Left doesn't also trigger an action bound to plain Left.
+  fn resolve_actions(
+    &mut self,
+    action_map: ActionMap,
+    mut is_key_pressed: impl FnMut(KeyboardKey) -> bool,
+  ) {
+    let modifiers = self.modifiers();
+    self.key_presses.clear();
+    for (hash, (key, required_modifiers)) in &action_map.keys {
+      self.key_presses.insert(
+        *hash,
+        *required_modifiers == modifiers && is_key_pressed(*key),
+      );
+    }
+    self.action_map = action_map;
+  }
+}
+
+/// Hash identifying an action in [`ActionMap`] and [`InputState::is_action_pressed`].
+pub fn action_hash(action: impl Hash) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  action.hash(&mut hasher);
+  hasher.finish()
 }
 
 #[derive(Default, Clone)]
 pub struct ActionMap {
-  keys: HashMap<u64, KeyboardKey>,
+  keys: HashMap<u64, (KeyboardKey, KeyModifiers)>,
 }
 
 impl ActionMap {
@@ -46,10 +233,16 @@ impl ActionMap {
   }
 
   pub fn add_action(&mut self, action: impl Hash, key: KeyboardKey) {
-    let mut hasher = DefaultHasher::new();
-    action.hash(&mut hasher);
-    let hash = hasher.finish();
+    self.add_action_with_modifiers(action, key, KeyModifiers::NONE);
+  }
 
-    self.keys.insert(hash, key);
+  /// Binds the action to `key` pressed while exactly `modifiers` are held.
+  pub fn add_action_with_modifiers(
+    &mut self,
+    action: impl Hash,
+    key: KeyboardKey,
+    modifiers: KeyModifiers,
+  ) {
+    self.keys.insert(action_hash(action), (key, modifiers));
   }
 }
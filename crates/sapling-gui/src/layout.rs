@@ -8,10 +8,21 @@ pub use optimizer::*;
 pub use preset::*;
 pub use resolver::*;
 
-#[derive(Debug, Clone)]
+use raylib::math::Vector2;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct ResolvedLayout {
   pub width: f32,
   pub height: f32,
   pub x: f32,
   pub y: f32,
 }
+
+impl ResolvedLayout {
+  pub fn contains(&self, point: Vector2) -> bool {
+    point.x >= self.x
+      && point.y >= self.y
+      && point.x < self.x + self.width
+      && point.y < self.y + self.height
+  }
+}
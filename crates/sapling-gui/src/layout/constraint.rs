@@ -1,3 +1,5 @@
+use std::fmt;
+
 #[derive(Debug, Clone)]
 pub enum CompiledConstraint {
   /// Forces an variable to equal a constant value.
@@ -13,6 +15,14 @@ pub enum CompiledConstraint {
     source_variable: ConstraintVariable,
     constant_offset: f32,
   },
+  /// Forces an variable to equal another variable scaled by a multiplier plus a constant offset.
+  /// var = other_var * multiplier + offset
+  ForcedVariableAssignmentScaled {
+    target_variable: ConstraintVariable,
+    source_variable: ConstraintVariable,
+    multiplier: f32,
+    constant_offset: f32,
+  },
   /// Forces an variable to equal the maximum value of a list of variables plus a constant offset.
   /// var = max(source_variables) + offset
   ForcedVariableAssignmentMaxOf {
@@ -27,14 +37,26 @@ pub enum CompiledConstraint {
     source_variables: Vec<(ConstraintVariable, f32)>,
     constant_offset: f32,
   },
+  /// Raises an variable to at least a constant after its value has been assigned.
+  /// var = max(var, constant)
+  ClampMin {
+    variable: ConstraintVariable,
+    constant: f32,
+  },
+  /// Lowers an variable to at most a constant after its value has been assigned.
+  /// var = min(var, constant)
+  ClampMax {
+    variable: ConstraintVariable,
+    constant: f32,
+  },
   /// Tries to assume variable from child variable, this is only resolved if cycles can be prevented
   /// self.dimension = max(children.dimension)
   TryAssumeMaxChildSize {
     dimension: Dimension,
     constant_offset: f32,
   },
-  /// Tries to assume variable from child variable, this is only resolved if cycles can be prevented
-  /// self.dimension = max(parent.dimension)
+  /// Takes the size of the parent, or of the window for the root element, shrunk by the offset.
+  /// self.dimension = parent.dimension - offset
   TryAssumeParentSize {
     dimension: Dimension,
     constant_offset: f32,
@@ -54,6 +76,9 @@ impl CompiledConstraint {
       CompiledConstraint::ForcedVariableAssignment {
         target_variable, ..
       } => Some(*target_variable),
+      CompiledConstraint::ForcedVariableAssignmentScaled {
+        target_variable, ..
+      } => Some(*target_variable),
       CompiledConstraint::ForcedConstAssignment { variable, .. } => Some(*variable),
       CompiledConstraint::ForcedVariableAssignmentMaxOf {
         target_variable, ..
@@ -68,11 +93,18 @@ impl CompiledConstraint {
   pub(crate) fn get_explicit_mentioned_variables(&self) -> Vec<ConstraintVariable> {
     match self {
       CompiledConstraint::ForcedConstAssignment { variable, .. } => vec![*variable],
+      CompiledConstraint::ClampMin { variable, .. } => vec![*variable],
+      CompiledConstraint::ClampMax { variable, .. } => vec![*variable],
       CompiledConstraint::ForcedVariableAssignment {
         source_variable,
         target_variable,
         ..
       } => vec![*source_variable, *target_variable],
+      CompiledConstraint::ForcedVariableAssignmentScaled {
+        source_variable,
+        target_variable,
+        ..
+      } => vec![*source_variable, *target_variable],
       CompiledConstraint::ForcedVariableAssignmentMaxOf {
         target_variable,
         source_variables,
@@ -102,6 +134,12 @@ impl CompiledConstraint {
       CompiledConstraint::ForcedConstAssignment { variable, constant } => {
         formular.push_str(&format!("{} = {}", variable.formular_name(), constant));
       }
+      CompiledConstraint::ClampMin { variable, constant } => {
+        formular.push_str(&format!("{} >= {}", variable.formular_name(), constant));
+      }
+      CompiledConstraint::ClampMax { variable, constant } => {
+        formular.push_str(&format!("{} <= {}", variable.formular_name(), constant));
+      }
       CompiledConstraint::ForcedVariableAssignmentMaxOf {
         target_variable,
         source_variables,
@@ -120,16 +158,11 @@ impl CompiledConstraint {
       CompiledConstraint::ForcedVariableAssignmentTerms {
         target_variable,
         source_variables,
-        ..
+        constant_offset,
       } => {
         formular.push_str(&format!("{} = ", target_variable.formular_name()));
-        formular.push_str(
-          &source_variables
-            .iter()
-            .map(|(v, term)| format!("({} * {})", term, v.formular_name()))
-            .collect::<Vec<String>>()
-            .join(" + "),
-        );
+        // Writing into a string can't fail
+        let _ = write_terms(&mut formular, source_variables, *constant_offset);
       }
       CompiledConstraint::TryAssumeMaxChildSize {
         dimension,
@@ -152,6 +185,25 @@ impl CompiledConstraint {
           },
         ));
       }
+      CompiledConstraint::TryAssumeParentSize {
+        dimension,
+        constant_offset,
+      } => {
+        let dimension = match dimension {
+          Dimension::Width => "width",
+          Dimension::Height => "height",
+        };
+        formular.push_str(&format!(
+          "self_{} = parent_{}{}",
+          dimension,
+          dimension,
+          if *constant_offset != 0.0f32 {
+            format!(" - {}", constant_offset)
+          } else {
+            "".to_string()
+          },
+        ));
+      }
       CompiledConstraint::ForcedVariableAssignment {
         target_variable,
         source_variable,
@@ -168,6 +220,24 @@ impl CompiledConstraint {
           },
         ));
       }
+      CompiledConstraint::ForcedVariableAssignmentScaled {
+        target_variable,
+        source_variable,
+        multiplier,
+        constant_offset,
+      } => {
+        formular.push_str(&format!(
+          "{} = {} * {}{}",
+          target_variable.formular_name(),
+          multiplier,
+          source_variable.formular_name(),
+          if *constant_offset != 0.0f32 {
+            format!(" + {}", constant_offset)
+          } else {
+            "".to_string()
+          },
+        ));
+      }
       _ => {}
     }
 
@@ -175,6 +245,12 @@ impl CompiledConstraint {
   }
 }
 
+impl fmt::Display for CompiledConstraint {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.get_formular())
+  }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ConstraintVariable {
   WindowWidth,
@@ -214,6 +290,36 @@ impl ConstraintVariable {
   }
 }
 
+/// Weighted sum of variables, for the edges and centers of an element that span more than one
+/// variable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElementExpr {
+  pub terms: Vec<(ConstraintVariable, f32)>,
+}
+
+impl ElementExpr {
+  /// Constraint assigning the expression plus `constant_offset` to `target_variable`.
+  pub fn assign_to(
+    self,
+    target_variable: ConstraintVariable,
+    constant_offset: f32,
+  ) -> CompiledConstraint {
+    CompiledConstraint::ForcedVariableAssignmentTerms {
+      target_variable,
+      source_variables: self.terms,
+      constant_offset,
+    }
+  }
+}
+
+impl From<ConstraintVariable> for ElementExpr {
+  fn from(variable: ConstraintVariable) -> Self {
+    Self {
+      terms: vec![(variable, 1.0)],
+    }
+  }
+}
+
 pub enum ElementVariable {
   Width,
   Height,
@@ -246,12 +352,92 @@ pub struct UserElementConstraint {
   pub strength: f32,
 }
 
+impl UserElementConstraint {
+  /// Signed slack of the constraint for the looked up values. Equalities are satisfied at zero,
+  /// inequalities at any non-negative value, a negative residual is how far they are violated.
+  pub fn residual(&self, lookup: impl Fn(&ConstraintVariable) -> f32) -> f32 {
+    let value = self.expression.evaluate(lookup);
+    match self.operator {
+      UserElementConstraintOperator::Equal | UserElementConstraintOperator::GreaterOrEqual => value,
+      UserElementConstraintOperator::LessOrEqual => -value,
+    }
+  }
+}
+
+impl fmt::Display for UserElementConstraint {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{} {} 0 (strength {})",
+      self.expression, self.operator, self.strength
+    )
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct UserElementConstraintExpression {
   pub constant: f32,
   pub terms: Vec<UserElementConstraintTerm>,
 }
 
+impl UserElementConstraintExpression {
+  /// Sum of the terms with their variables looked up, plus the constant.
+  pub fn evaluate(&self, lookup: impl Fn(&ConstraintVariable) -> f32) -> f32 {
+    self
+      .terms
+      .iter()
+      .map(|term| term.coefficient * lookup(&term.variable))
+      .sum::<f32>()
+      + self.constant
+  }
+}
+
+impl fmt::Display for UserElementConstraintExpression {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let terms = self
+      .terms
+      .iter()
+      .map(|term| (term.variable, term.coefficient))
+      .collect::<Vec<_>>();
+    write_terms(f, &terms, self.constant)
+  }
+}
+
+/// Writes a sum of terms as in `self_width - 0.5 * parent_width + 10`, leaving out terms with a
+/// zero coefficient.
+fn write_terms(
+  out: &mut impl fmt::Write,
+  terms: &[(ConstraintVariable, f32)],
+  constant: f32,
+) -> fmt::Result {
+  let mut empty = true;
+  for (variable, coefficient) in terms.iter().filter(|(_, coefficient)| *coefficient != 0.0) {
+    if empty {
+      if *coefficient < 0.0 {
+        out.write_str("-")?;
+      }
+    } else {
+      out.write_str(if *coefficient < 0.0 { " - " } else { " + " })?;
+    }
+    let magnitude = coefficient.abs();
+    if magnitude != 1.0 {
+      write!(out, "{} * ", magnitude)?;
+    }
+    out.write_str(&variable.formular_name())?;
+    empty = false;
+  }
+
+  if empty {
+    write!(out, "{}", constant)
+  } else if constant < 0.0 {
+    write!(out, " - {}", -constant)
+  } else if constant > 0.0 {
+    write!(out, " + {}", constant)
+  } else {
+    Ok(())
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct UserElementConstraintTerm {
   pub variable: ConstraintVariable,
@@ -265,8 +451,135 @@ pub enum UserElementConstraintOperator {
   LessOrEqual,
 }
 
+impl fmt::Display for UserElementConstraintOperator {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      UserElementConstraintOperator::Equal => "==",
+      UserElementConstraintOperator::GreaterOrEqual => ">=",
+      UserElementConstraintOperator::LessOrEqual => "<=",
+    })
+  }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub enum Dimension {
   Width,
   Height,
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// `self_width - 0.5 * parent_width - 10 <op> 0`
+  fn constraint(operator: UserElementConstraintOperator) -> UserElementConstraint {
+    UserElementConstraint {
+      operator,
+      expression: UserElementConstraintExpression {
+        constant: -10.0,
+        terms: vec![
+          UserElementConstraintTerm {
+            variable: ConstraintVariable::SelfWidth,
+            coefficient: 1.0,
+          },
+          UserElementConstraintTerm {
+            variable: ConstraintVariable::ParentWidth,
+            coefficient: -0.5,
+          },
+        ],
+      },
+      strength: 1.0,
+    }
+  }
+
+  fn lookup(variable: &ConstraintVariable) -> f32 {
+    match variable {
+      ConstraintVariable::SelfWidth => 40.0,
+      ConstraintVariable::ParentWidth => 100.0,
+      _ => unreachable!(),
+    }
+  }
+
+  #[test]
+  fn test_expression_evaluate() {
+    let expression = constraint(UserElementConstraintOperator::Equal).expression;
+    assert_eq!(expression.evaluate(lookup), -20.0);
+    assert_eq!(
+      UserElementConstraintExpression {
+        constant: 3.0,
+        terms: vec![],
+      }
+      .evaluate(lookup),
+      3.0
+    );
+  }
+
+  #[test]
+  fn test_residual_sign_follows_operator() {
+    assert_eq!(
+      constraint(UserElementConstraintOperator::Equal).residual(lookup),
+      -20.0
+    );
+    // self_width is 20 short of the minimum
+    assert_eq!(
+      constraint(UserElementConstraintOperator::GreaterOrEqual).residual(lookup),
+      -20.0
+    );
+    // and 20 below the maximum
+    assert_eq!(
+      constraint(UserElementConstraintOperator::LessOrEqual).residual(lookup),
+      20.0
+    );
+  }
+
+  #[test]
+  fn test_inequality_with_constant_display() {
+    let mut constraint = constraint(UserElementConstraintOperator::GreaterOrEqual);
+    constraint.strength = 1000.0;
+    assert_eq!(
+      constraint.to_string(),
+      "self_width - 0.5 * parent_width - 10 >= 0 (strength 1000)"
+    );
+  }
+
+  #[test]
+  fn test_expression_display_edge_cases() {
+    let expression = |constant: f32, coefficients: &[f32]| UserElementConstraintExpression {
+      constant,
+      terms: coefficients
+        .iter()
+        .map(|&coefficient| UserElementConstraintTerm {
+          variable: ConstraintVariable::SelfX,
+          coefficient,
+        })
+        .collect(),
+    };
+
+    assert_eq!(expression(10.0, &[-1.0]).to_string(), "-self_x + 10");
+    assert_eq!(
+      expression(0.0, &[-2.0, 1.0]).to_string(),
+      "-2 * self_x + self_x"
+    );
+    // Zero coefficients are skipped, also for the leading term
+    assert_eq!(expression(0.0, &[0.0, -1.0]).to_string(), "-self_x");
+    assert_eq!(expression(-4.0, &[0.0]).to_string(), "-4");
+    assert_eq!(expression(0.0, &[]).to_string(), "0");
+  }
+
+  #[test]
+  fn test_terms_formular() {
+    let constraint = CompiledConstraint::ForcedVariableAssignmentTerms {
+      target_variable: ConstraintVariable::SelfWidth,
+      source_variables: vec![
+        (ConstraintVariable::ParentWidth, -1.0),
+        (ConstraintVariable::SelfX, 0.0),
+        (ConstraintVariable::SelfHeight, 0.5),
+      ],
+      constant_offset: -4.0,
+    };
+    assert_eq!(
+      constraint.to_string(),
+      "self_width = -parent_width + 0.5 * self_height - 4"
+    );
+  }
+}
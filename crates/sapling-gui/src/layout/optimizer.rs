@@ -35,6 +35,40 @@ impl CompiledConstraintResult {
   }
 }
 
+/// Reason a user constraint can't be compiled into direct assignments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintCompileError {
+  /// `constant == 0` with a non-zero constant
+  Unsatisfiable,
+  /// A variable term has a zero coefficient, it can't be solved for
+  ZeroCoefficient,
+  /// Only constraints with 1-2 variable terms can be compiled
+  TooManyTerms,
+  /// Inequalities only compile into a clamp of a single variable term
+  UnsupportedInequality,
+}
+
+impl std::fmt::Display for ConstraintCompileError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let message = match self {
+      ConstraintCompileError::Unsatisfiable => {
+        "Constraint is unsatisfiable: constant == 0 but constant != 0"
+      }
+      ConstraintCompileError::ZeroCoefficient => "Cannot compile constraint with zero coefficient",
+      ConstraintCompileError::TooManyTerms => {
+        "Cannot compile constraint with 3+ terms into CompiledConstraint"
+      }
+      ConstraintCompileError::UnsupportedInequality => {
+        "Cannot compile inequality into CompiledConstraint, only inequalities with a single \
+         variable term can be compiled into a clamp"
+      }
+    };
+    write!(f, "{}", message)
+  }
+}
+
+impl std::error::Error for ConstraintCompileError {}
+
 /// Compile-time optimizer that converts user-defined constraints into optimized compiled constraints.
 ///
 /// This optimizer analyzes constraint expressions and transforms them into direct assignment
@@ -48,28 +82,27 @@ impl CompiledConstraintResult {
 /// 2. **Variable Assignment**: `var1 == var2 + constant` → `ForcedVariableAssignment`
 ///    - Example: `x - y - 5 == 0` → `x = y + 5`
 ///
+///    - Example: `x - 0.5y == 0` → `x = 0.5 * y` (`ForcedVariableAssignmentScaled`)
+///
+/// 3. **Clamp**: `var >= constant` / `var <= constant` → `ClampMin` / `ClampMax`
+///    - Example: `x - 100 >= 0` → `x = max(x, 100)`
+///
 /// # Compilation Rules
 ///
-/// - Only `Equal` operator constraints can be compiled (for now)
-/// - `GreaterOrEqual` and `LessOrEqual` cannot be compiled to forced assignments
+/// - `Equal` constraints compile to forced assignments
+/// - `GreaterOrEqual` and `LessOrEqual` only compile with a single variable term
 /// - Constraints with more than 2 terms (excluding constant) cannot be compiled
 /// - The constraint must be solvable for exactly one variable
-pub const fn optimize_constraint(constraint: &UserElementConstraint) -> CompiledConstraintResult {
-  // Only Equal operator can be compiled into forced assignments
-  // Inequalities (>= and <=) cannot be directly compiled to assignments
+///
+/// Any other constraint is rejected with a [`ConstraintCompileError`].
+pub const fn optimize_constraint(
+  constraint: &UserElementConstraint,
+) -> Result<CompiledConstraintResult, ConstraintCompileError> {
+  // Inequalities can't be turned into assignments, only into clamps of a single variable
   match constraint.operator {
     UserElementConstraintOperator::Equal => {}
-    UserElementConstraintOperator::GreaterOrEqual => {
-      panic!(
-        "Cannot compile GreaterOrEqual constraint into CompiledConstraint. \
-                Inequality constraints require a full constraint solver."
-      );
-    }
-    UserElementConstraintOperator::LessOrEqual => {
-      panic!(
-        "Cannot compile LessOrEqual constraint into CompiledConstraint. \
-                Inequality constraints require a full constraint solver."
-      );
+    UserElementConstraintOperator::GreaterOrEqual | UserElementConstraintOperator::LessOrEqual => {
+      return compile_inequality_constraint(constraint);
     }
   }
 
@@ -84,10 +117,10 @@ pub const fn optimize_constraint(constraint: &UserElementConstraint) -> Compiled
       // Expression like: constant == 0
       // This is a tautology or contradiction
       if expr.constant > f32::EPSILON || expr.constant < -f32::EPSILON {
-        panic!("Constraint is unsatisfiable: constant == 0 but constant != 0");
+        return Err(ConstraintCompileError::Unsatisfiable);
       }
       // Empty constraint (0 == 0), return empty result
-      CompiledConstraintResult::empty()
+      Ok(CompiledConstraintResult::empty())
     }
     1 => {
       // Expression like: coeff * var + constant == 0
@@ -101,10 +134,7 @@ pub const fn optimize_constraint(constraint: &UserElementConstraint) -> Compiled
     }
     _ => {
       // Expression with 3+ terms cannot be compiled to simple assignments
-      panic!(
-        "Cannot compile constraint with 3+ terms into CompiledConstraint. \
-                Only constraints with 1-2 variable terms can be compiled."
-      );
+      Err(ConstraintCompileError::TooManyTerms)
     }
   }
 }
@@ -116,21 +146,65 @@ pub const fn optimize_constraint(constraint: &UserElementConstraint) -> Compiled
 const fn compile_single_term_constraint(
   terms: &[super::constraint::UserElementConstraintTerm],
   constant: f32,
-) -> CompiledConstraintResult {
+) -> Result<CompiledConstraintResult, ConstraintCompileError> {
   let term = &terms[0];
 
   if term.coefficient > -f32::EPSILON && term.coefficient < f32::EPSILON {
-    panic!("Cannot compile constraint with zero coefficient");
+    return Err(ConstraintCompileError::ZeroCoefficient);
   }
 
   // Solve: coeff * var + constant == 0
   // => var = -constant / coeff
   let value = -constant / term.coefficient;
 
-  CompiledConstraintResult::single(CompiledConstraint::ForcedConstAssignment {
-    variable: term.variable,
-    constant: value,
-  })
+  Ok(CompiledConstraintResult::single(
+    CompiledConstraint::ForcedConstAssignment {
+      variable: term.variable,
+      constant: value,
+    },
+  ))
+}
+
+/// Compile an inequality with a single variable term.
+///
+/// Pattern: `coeff * var + constant >= 0` (or `<= 0`)
+/// Solves to: `var >= -constant / coeff`, flipping the direction for negative coefficients
+const fn compile_inequality_constraint(
+  constraint: &UserElementConstraint,
+) -> Result<CompiledConstraintResult, ConstraintCompileError> {
+  let is_greater = matches!(
+    constraint.operator,
+    UserElementConstraintOperator::GreaterOrEqual
+  );
+  let terms = constraint.expression.terms.as_slice();
+
+  if terms.len() != 1 {
+    return Err(ConstraintCompileError::UnsupportedInequality);
+  }
+
+  let term = &terms[0];
+  if term.coefficient > -f32::EPSILON && term.coefficient < f32::EPSILON {
+    return Err(ConstraintCompileError::ZeroCoefficient);
+  }
+
+  let bound = -constraint.expression.constant / term.coefficient;
+
+  // Dividing by a negative coefficient flips the inequality
+  if is_greater == (term.coefficient > 0.0) {
+    Ok(CompiledConstraintResult::single(
+      CompiledConstraint::ClampMin {
+        variable: term.variable,
+        constant: bound,
+      },
+    ))
+  } else {
+    Ok(CompiledConstraintResult::single(
+      CompiledConstraint::ClampMax {
+        variable: term.variable,
+        constant: bound,
+      },
+    ))
+  }
 }
 
 /// Compile a constraint with two variable terms.
@@ -143,16 +217,16 @@ const fn compile_single_term_constraint(
 const fn compile_two_term_constraint(
   terms: &[super::constraint::UserElementConstraintTerm],
   constant: f32,
-) -> CompiledConstraintResult {
+) -> Result<CompiledConstraintResult, ConstraintCompileError> {
   let term1 = &terms[0];
   let term2 = &terms[1];
 
   // Check for zero coefficients
   if term1.coefficient > -f32::EPSILON && term1.coefficient < f32::EPSILON {
-    panic!("Cannot compile constraint with zero coefficient on first term");
+    return Err(ConstraintCompileError::ZeroCoefficient);
   }
   if term2.coefficient > -f32::EPSILON && term2.coefficient < f32::EPSILON {
-    panic!("Cannot compile constraint with zero coefficient on second term");
+    return Err(ConstraintCompileError::ZeroCoefficient);
   }
 
   // Choose which variable to solve for based on coefficient magnitude
@@ -202,24 +276,28 @@ const fn compile_two_term_constraint(
   let source_multiplier = -source_coeff / target_coeff;
   let constant_offset = -constant / target_coeff;
 
-  // Check if source_multiplier is 1.0 or -1.0 (or very close to it)
+  // Check if source_multiplier is 1.0 (or very close to it)
   let is_one = source_multiplier > 0.999 && source_multiplier < 1.001;
-  let is_neg_one = source_multiplier > -1.001 && source_multiplier < -0.999;
-
-  if is_one || is_neg_one {
-    // Simple case: target_var = ±source_var + constant_offset
-    CompiledConstraintResult::single(CompiledConstraint::ForcedVariableAssignment {
-      target_variable: target_var,
-      source_variable: source_var,
-      constant_offset,
-    })
+
+  if is_one {
+    // Simple case: target_var = source_var + constant_offset
+    Ok(CompiledConstraintResult::single(
+      CompiledConstraint::ForcedVariableAssignment {
+        target_variable: target_var,
+        source_variable: source_var,
+        constant_offset,
+      },
+    ))
   } else {
     // General case: target_var = source_multiplier * source_var + constant_offset
-    // This requires multiplication which ForcedVariableAssignment doesn't support
-    panic!(
-      "Cannot compile constraint where the source variable has a coefficient other than 1.0 or -1.0. \
-            The current CompiledConstraint variants only support assignments of the form 'var = other_var + constant'."
-    );
+    Ok(CompiledConstraintResult::single(
+      CompiledConstraint::ForcedVariableAssignmentScaled {
+        target_variable: target_var,
+        source_variable: source_var,
+        multiplier: source_multiplier,
+        constant_offset,
+      },
+    ))
   }
 }
 
@@ -246,7 +324,7 @@ mod tests {
       strength: 1.0,
     };
 
-    let result = optimize_constraint(&constraint);
+    let result = optimize_constraint(&constraint).unwrap();
     assert_eq!(result.count, 1);
     match result.constraints[0] {
       Some(CompiledConstraint::ForcedConstAssignment { variable, constant }) => {
@@ -278,7 +356,7 @@ mod tests {
       strength: 1.0,
     };
 
-    let result = optimize_constraint(&constraint);
+    let result = optimize_constraint(&constraint).unwrap();
     assert_eq!(result.count, 1);
     match result.constraints[0] {
       Some(CompiledConstraint::ForcedVariableAssignment {
@@ -295,9 +373,8 @@ mod tests {
   }
 
   #[test]
-  #[should_panic(expected = "Cannot compile constraint where the source variable")]
   fn test_scaled_coefficient() {
-    // 2x - y == 0 => x = 0.5y (not supported)
+    // 2x - y == 0 => x = 0.5y
     let constraint = UserElementConstraint {
       operator: UserElementConstraintOperator::Equal,
       expression: UserElementConstraintExpression {
@@ -316,30 +393,169 @@ mod tests {
       strength: 1.0,
     };
 
-    optimize_constraint(&constraint);
+    let result = optimize_constraint(&constraint).unwrap();
+    assert_eq!(result.count, 1);
+    match result.constraints[0] {
+      Some(CompiledConstraint::ForcedVariableAssignmentScaled {
+        target_variable,
+        source_variable,
+        multiplier,
+        constant_offset,
+      }) => {
+        // y has the coefficient closest to 1.0, so it's solved for: y = 2x
+        assert_eq!(target_variable, ConstraintVariable::SelfY);
+        assert_eq!(source_variable, ConstraintVariable::SelfX);
+        assert!((multiplier - 2.0).abs() < f32::EPSILON);
+        assert!(constant_offset.abs() < f32::EPSILON);
+      }
+      _ => panic!("Expected ForcedVariableAssignmentScaled"),
+    }
   }
 
   #[test]
-  #[should_panic(expected = "Cannot compile GreaterOrEqual constraint")]
-  fn test_inequality_panics() {
+  fn test_negated_variable_assignment() {
+    // x + y - 10 == 0 => x = -y + 10
+    let constraint = UserElementConstraint {
+      operator: UserElementConstraintOperator::Equal,
+      expression: UserElementConstraintExpression {
+        constant: -10.0,
+        terms: vec![
+          UserElementConstraintTerm {
+            variable: ConstraintVariable::SelfX,
+            coefficient: 1.0,
+          },
+          UserElementConstraintTerm {
+            variable: ConstraintVariable::SelfY,
+            coefficient: 1.0,
+          },
+        ],
+      },
+      strength: 1.0,
+    };
+
+    let result = optimize_constraint(&constraint).unwrap();
+    match result.constraints[0] {
+      Some(CompiledConstraint::ForcedVariableAssignmentScaled {
+        target_variable,
+        source_variable,
+        multiplier,
+        constant_offset,
+      }) => {
+        assert_eq!(target_variable, ConstraintVariable::SelfX);
+        assert_eq!(source_variable, ConstraintVariable::SelfY);
+        assert!((multiplier + 1.0).abs() < f32::EPSILON);
+        assert!((constant_offset - 10.0).abs() < f32::EPSILON);
+      }
+      _ => panic!("Expected ForcedVariableAssignmentScaled"),
+    }
+  }
+
+  #[test]
+  fn test_single_term_clamp_min() {
+    // x - 100 >= 0 => x >= 100
     let constraint = UserElementConstraint {
       operator: UserElementConstraintOperator::GreaterOrEqual,
       expression: UserElementConstraintExpression {
-        constant: 0.0,
+        constant: -100.0,
         terms: vec![UserElementConstraintTerm {
-          variable: ConstraintVariable::SelfX,
+          variable: ConstraintVariable::SelfWidth,
           coefficient: 1.0,
         }],
       },
       strength: 1.0,
     };
 
-    optimize_constraint(&constraint);
+    let result = optimize_constraint(&constraint).unwrap();
+    assert_eq!(result.count, 1);
+    match result.constraints[0] {
+      Some(CompiledConstraint::ClampMin { variable, constant }) => {
+        assert_eq!(variable, ConstraintVariable::SelfWidth);
+        assert!((constant - 100.0).abs() < f32::EPSILON);
+      }
+      _ => panic!("Expected ClampMin"),
+    }
+  }
+
+  #[test]
+  fn test_single_term_clamp_max() {
+    // x - 200 <= 0 => x <= 200
+    let constraint = UserElementConstraint {
+      operator: UserElementConstraintOperator::LessOrEqual,
+      expression: UserElementConstraintExpression {
+        constant: -200.0,
+        terms: vec![UserElementConstraintTerm {
+          variable: ConstraintVariable::SelfWidth,
+          coefficient: 1.0,
+        }],
+      },
+      strength: 1.0,
+    };
+
+    let result = optimize_constraint(&constraint).unwrap();
+    assert_eq!(result.count, 1);
+    match result.constraints[0] {
+      Some(CompiledConstraint::ClampMax { variable, constant }) => {
+        assert_eq!(variable, ConstraintVariable::SelfWidth);
+        assert!((constant - 200.0).abs() < f32::EPSILON);
+      }
+      _ => panic!("Expected ClampMax"),
+    }
+  }
+
+  #[test]
+  fn test_negative_coefficient_flips_clamp() {
+    // -x + 50 >= 0 => x <= 50
+    let constraint = UserElementConstraint {
+      operator: UserElementConstraintOperator::GreaterOrEqual,
+      expression: UserElementConstraintExpression {
+        constant: 50.0,
+        terms: vec![UserElementConstraintTerm {
+          variable: ConstraintVariable::SelfHeight,
+          coefficient: -1.0,
+        }],
+      },
+      strength: 1.0,
+    };
+
+    let result = optimize_constraint(&constraint).unwrap();
+    match result.constraints[0] {
+      Some(CompiledConstraint::ClampMax { variable, constant }) => {
+        assert_eq!(variable, ConstraintVariable::SelfHeight);
+        assert!((constant - 50.0).abs() < f32::EPSILON);
+      }
+      _ => panic!("Expected ClampMax"),
+    }
   }
 
   #[test]
-  #[should_panic(expected = "Cannot compile constraint with 3+ terms")]
-  fn test_too_many_terms_panics() {
+  fn test_inequality_rejected() {
+    // x - y >= 0 can't be expressed as a clamp against a constant
+    let constraint = UserElementConstraint {
+      operator: UserElementConstraintOperator::GreaterOrEqual,
+      expression: UserElementConstraintExpression {
+        constant: 0.0,
+        terms: vec![
+          UserElementConstraintTerm {
+            variable: ConstraintVariable::SelfX,
+            coefficient: 1.0,
+          },
+          UserElementConstraintTerm {
+            variable: ConstraintVariable::SelfY,
+            coefficient: -1.0,
+          },
+        ],
+      },
+      strength: 1.0,
+    };
+
+    assert_eq!(
+      optimize_constraint(&constraint).err(),
+      Some(ConstraintCompileError::UnsupportedInequality)
+    );
+  }
+
+  #[test]
+  fn test_too_many_terms_rejected() {
     let constraint = UserElementConstraint {
       operator: UserElementConstraintOperator::Equal,
       expression: UserElementConstraintExpression {
@@ -362,7 +578,28 @@ mod tests {
       strength: 1.0,
     };
 
-    optimize_constraint(&constraint);
+    assert_eq!(
+      optimize_constraint(&constraint).err(),
+      Some(ConstraintCompileError::TooManyTerms)
+    );
+  }
+
+  #[test]
+  fn test_unsatisfiable_rejected() {
+    // 5 == 0
+    let constraint = UserElementConstraint {
+      operator: UserElementConstraintOperator::Equal,
+      expression: UserElementConstraintExpression {
+        constant: 5.0,
+        terms: vec![],
+      },
+      strength: 1.0,
+    };
+
+    assert_eq!(
+      optimize_constraint(&constraint).err(),
+      Some(ConstraintCompileError::Unsatisfiable)
+    );
   }
 
   #[test]
@@ -377,7 +614,7 @@ mod tests {
       strength: 1.0,
     };
 
-    let result = optimize_constraint(&constraint);
+    let result = optimize_constraint(&constraint).unwrap();
     assert_eq!(result.count, 0);
   }
 
@@ -396,7 +633,7 @@ mod tests {
       strength: 1.0,
     };
 
-    let result = optimize_constraint(&constraint);
+    let result = optimize_constraint(&constraint).unwrap();
     assert_eq!(result.count, 1);
     match result.constraints[0] {
       Some(CompiledConstraint::ForcedConstAssignment { variable, constant }) => {
@@ -3,6 +3,15 @@ use crate::{
   prelude::Element,
 };
 
+/// How an element participates in [`UserElementConstraints::distribute_horizontal`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Flex {
+  /// Keeps the size of its content
+  Fixed,
+  /// Takes a share of the space left over by fixed elements, proportional to the weight
+  Grow(f32),
+}
+
 impl UserElementConstraints {
   pub fn absolute_position(x: f32, y: f32) -> Self {
     Self {
@@ -150,11 +159,17 @@ impl UserElementConstraints {
       .merged(&Self::cover_element_vertical(element, spacing_y))
   }
 
-  pub fn center_in_parent_horizontal() -> Self {
+  /// Centers the element horizontally within its parent,
+  /// `self_x = parent_x + (parent_width - self_width) / 2`.
+  ///
+  /// The element needs an explicit width, a width derived from its children would in turn depend
+  /// on the centered position and form a cycle.
+  pub fn center_horizontally_in_parent() -> Self {
     Self {
       constraints: vec![CompiledConstraint::ForcedVariableAssignmentTerms {
         target_variable: ConstraintVariable::SelfX,
         source_variables: vec![
+          (ConstraintVariable::ParentX, 1.0),
           (ConstraintVariable::ParentWidth, 0.5),
           (ConstraintVariable::SelfWidth, -0.5),
         ],
@@ -163,11 +178,14 @@ impl UserElementConstraints {
     }
   }
 
-  pub fn center_in_parent_vertical() -> Self {
+  /// Vertical counterpart of [`Self::center_horizontally_in_parent`], requires an explicit
+  /// height.
+  pub fn center_vertically_in_parent() -> Self {
     Self {
       constraints: vec![CompiledConstraint::ForcedVariableAssignmentTerms {
         target_variable: ConstraintVariable::SelfY,
         source_variables: vec![
+          (ConstraintVariable::ParentY, 1.0),
           (ConstraintVariable::ParentHeight, 0.5),
           (ConstraintVariable::SelfHeight, -0.5),
         ],
@@ -177,7 +195,17 @@ impl UserElementConstraints {
   }
 
   pub fn center_in_parent() -> Self {
-    Self::center_in_parent_horizontal().merged(&Self::center_in_parent_vertical())
+    Self::center_horizontally_in_parent().merged(&Self::center_vertically_in_parent())
+  }
+
+  #[deprecated(note = "renamed to `center_horizontally_in_parent`")]
+  pub fn center_in_parent_horizontal() -> Self {
+    Self::center_horizontally_in_parent()
+  }
+
+  #[deprecated(note = "renamed to `center_vertically_in_parent`")]
+  pub fn center_in_parent_vertical() -> Self {
+    Self::center_vertically_in_parent()
   }
 
   pub fn fixed_width(width: f32) -> Self {
@@ -202,6 +230,30 @@ impl UserElementConstraints {
     Self::fixed_height(height).merged(&Self::fixed_width(width))
   }
 
+  /// Keeps `self_width = self_height * width_over_height`.
+  ///
+  /// Only relates the two dimensions, combine it with a constraint on the height, otherwise the
+  /// element is underconstrained and its height falls back to the size of its children.
+  pub fn aspect_ratio(width_over_height: f32) -> Self {
+    Self {
+      constraints: vec![CompiledConstraint::ForcedVariableAssignmentScaled {
+        target_variable: ConstraintVariable::SelfWidth,
+        source_variable: ConstraintVariable::SelfHeight,
+        multiplier: width_over_height,
+        constant_offset: 0.0,
+      }],
+    }
+  }
+
+  /// Largest fixed size with the given aspect ratio fitting into `max_width` x `max_height`.
+  pub fn fixed_aspect_within(max_width: f32, max_height: f32, width_over_height: f32) -> Self {
+    if max_width / max_height > width_over_height {
+      Self::fixed_size(max_height * width_over_height, max_height)
+    } else {
+      Self::fixed_size(max_width, max_width / width_over_height)
+    }
+  }
+
   pub fn anchor_to_right_of(element: Element, spacing: f32) -> Self {
     Self {
       constraints: vec![CompiledConstraint::ForcedVariableAssignmentTerms {
@@ -251,4 +303,67 @@ impl UserElementConstraints {
       }],
     }
   }
+
+  /// Places the elements left to right inside `parent`, separated by `gap`. [`Flex::Fixed`]
+  /// elements keep their content width, the remaining width of the parent is shared among the
+  /// [`Flex::Grow`] elements by weight. Returns the constraints to apply to each element.
+  pub fn distribute_horizontal(
+    children: &[(Element, Flex)],
+    parent: Element,
+    gap: f32,
+  ) -> Vec<(Element, UserElementConstraints)> {
+    let total_gap = gap * children.len().saturating_sub(1) as f32;
+    let total_weight: f32 = children
+      .iter()
+      .map(|(_, flex)| match flex {
+        Flex::Fixed => 0.0,
+        Flex::Grow(weight) => *weight,
+      })
+      .sum();
+
+    let mut previous: Option<Element> = None;
+    children
+      .iter()
+      .map(|(element, flex)| {
+        let mut constraints = match previous {
+          Some(previous) => Self::anchor_to_right_of(previous, gap),
+          None => Self {
+            constraints: vec![CompiledConstraint::ForcedVariableAssignment {
+              target_variable: ConstraintVariable::SelfX,
+              source_variable: ConstraintVariable::ElementX { id: parent.id },
+              constant_offset: 0.0,
+            }],
+          },
+        };
+        previous = Some(*element);
+
+        if let Flex::Grow(weight) = flex {
+          let share = if total_weight > 0.0 {
+            weight / total_weight
+          } else {
+            0.0
+          };
+
+          // width = (parent_width - fixed_widths - gaps) * share
+          let mut source_variables =
+            vec![(ConstraintVariable::ElementWidth { id: parent.id }, share)];
+          source_variables.extend(
+            children
+              .iter()
+              .filter(|(_, flex)| *flex == Flex::Fixed)
+              .map(|(fixed, _)| (ConstraintVariable::ElementWidth { id: fixed.id }, -share)),
+          );
+          constraints
+            .constraints
+            .push(CompiledConstraint::ForcedVariableAssignmentTerms {
+              target_variable: ConstraintVariable::SelfWidth,
+              source_variables,
+              constant_offset: -total_gap * share,
+            });
+        }
+
+        (*element, constraints)
+      })
+      .collect()
+  }
 }
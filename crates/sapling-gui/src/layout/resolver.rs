@@ -8,14 +8,18 @@ use petgraph::{
 };
 
 use crate::layout::{
-  Dimension,
+  Dimension, ResolvedLayout,
   constraint::{CompiledConstraint, ConstraintVariable, ElementVariable},
 };
 
+/// Constraint ids as nodes, edges point from a constraint to the ones depending on it.
+type DependencyGraph = Graph<usize, usize, Directed>;
+
 pub struct ConstraintResolver {
   constraints: Vec<(usize, CompiledConstraint)>,
   relationships: Vec<RelationshipMeta>,
   resolved_variables: Vec<f32>,
+  snap: Option<f32>,
 }
 
 impl ConstraintResolver {
@@ -35,13 +39,23 @@ impl ConstraintResolver {
           + Self::ROOT_VARIABLES
       ],
       relationships,
+      snap: None,
     };
     resolver.resolved_variables[0] = window.0;
     resolver.resolved_variables[1] = window.1;
     resolver
   }
 
-  fn build_dependency_graph(&mut self) -> Graph<usize, usize, Directed> {
+  /// Rounds the resolved layouts to multiples of `grid` after resolving. The edges are rounded
+  /// instead of the sizes, the width becomes `round(x + width) - round(x)`. Elements sharing an
+  /// edge before snapping still share it afterwards, at the cost of sizes changing by up to one
+  /// grid step depending on the position.
+  pub fn with_snap(mut self, grid: f32) -> Self {
+    self.snap = Some(grid).filter(|grid| *grid > 0.0);
+    self
+  }
+
+  fn build_dependency_graph(&mut self) -> DependencyGraph {
     let mut graph = StableGraph::<usize, usize>::with_capacity(
       self.constraints.len(),
       self.constraints.len() * 10,
@@ -51,6 +65,7 @@ impl ConstraintResolver {
     let mut variable_assign_map = HashMap::<usize, Vec<NodeIndex>>::new();
     let mut post_child_parent_queue: HashSet<(usize, usize, usize, NodeIndex)> = HashSet::new();
     let mut post_parent_child_queue: HashSet<(usize, usize, usize, NodeIndex)> = HashSet::new();
+    let mut clamps = Vec::<(usize, NodeIndex)>::new();
 
     for (constraint_id, (element_id, constraint)) in self.constraints.iter().enumerate() {
       match constraint {
@@ -73,7 +88,18 @@ impl ConstraintResolver {
             node_index,
           ));
         }
-        CompiledConstraint::TryAssumeParentSize { .. } => {}
+        CompiledConstraint::TryAssumeParentSize { dimension, .. } => {
+          let variable = match dimension {
+            Dimension::Width => ConstraintVariable::SelfWidth,
+            Dimension::Height => ConstraintVariable::SelfHeight,
+          };
+          let variable_index = self.map_element_variable_to_index(*element_id, variable);
+          let node_index = graph.add_node(constraint_id);
+          variable_assign_map
+            .entry(variable_index)
+            .or_default()
+            .push(node_index);
+        }
         CompiledConstraint::ForcedConstAssignment { variable, .. } => {
           let variable_index = self.map_element_variable_to_index(*element_id, *variable);
           let node_index = graph.add_node(constraint_id);
@@ -82,8 +108,17 @@ impl ConstraintResolver {
             .or_default()
             .push(node_index);
         }
+        CompiledConstraint::ClampMin { variable, .. }
+        | CompiledConstraint::ClampMax { variable, .. } => {
+          let variable_index = self.map_element_variable_to_index(*element_id, *variable);
+          let node_index = graph.add_node(constraint_id);
+          clamps.push((variable_index, node_index));
+        }
         CompiledConstraint::ForcedVariableAssignment {
           target_variable, ..
+        }
+        | CompiledConstraint::ForcedVariableAssignmentScaled {
+          target_variable, ..
         } => {
           let variable_index = self.map_element_variable_to_index(*element_id, *target_variable);
           let node_index = graph.add_node(constraint_id);
@@ -115,6 +150,16 @@ impl ConstraintResolver {
       }
     }
 
+    // Clamps run after every other assignment of their variable and take part in the assignment
+    // map themselves, so anything reading the variable sees the clamped value.
+    for (variable_index, node_index) in clamps {
+      let assignments = variable_assign_map.entry(variable_index).or_default();
+      for assignment in assignments.iter() {
+        graph.add_edge(*assignment, node_index, variable_index);
+      }
+      assignments.push(node_index);
+    }
+
     // 2. Add direct edges between constraints
     fn add_variable_assignment_edge(
       graph: &mut StableGraph<usize, usize>,
@@ -136,12 +181,28 @@ impl ConstraintResolver {
       match constraint {
         // No op as everything is constant
         CompiledConstraint::TryAssumeMaxChildSize { .. } => {}
-        CompiledConstraint::TryAssumeParentSize { .. } => {}
+        CompiledConstraint::TryAssumeParentSize { dimension, .. } => {
+          let (target_variable, source_variable) =
+            self.parent_size_variables(*element_id, dimension);
+          add_variable_assignment_edge(
+            &mut graph,
+            &variable_assign_map,
+            self.map_element_variable_to_index(*element_id, target_variable),
+            self.map_element_variable_to_index(*element_id, source_variable),
+          );
+        }
         CompiledConstraint::ForcedConstAssignment { .. } => {}
+        CompiledConstraint::ClampMin { .. } => {}
+        CompiledConstraint::ClampMax { .. } => {}
         CompiledConstraint::ForcedVariableAssignment {
           target_variable,
           source_variable,
           ..
+        }
+        | CompiledConstraint::ForcedVariableAssignmentScaled {
+          target_variable,
+          source_variable,
+          ..
         } => {
           let target_variable_index =
             self.map_element_variable_to_index(*element_id, *target_variable);
@@ -317,38 +378,61 @@ impl ConstraintResolver {
     self.resolved_variables[index]
   }
 
-  pub fn resolve(&mut self) {
+  /// Resolved position and size of an element, the variables of an element are stored next to
+  /// each other so they are read with a single index lookup.
+  pub fn get_element_layout(&self, element_id: usize) -> ResolvedLayout {
+    let offset = self.map_element_variable_to_index(
+      element_id,
+      ConstraintVariable::ElementWidth { id: element_id },
+    );
+    ResolvedLayout {
+      width: self.resolved_variables[offset],
+      height: self.resolved_variables[offset + 1],
+      x: self.resolved_variables[offset + 2],
+      y: self.resolved_variables[offset + 3],
+    }
+  }
+
+  /// Checks that the constraints don't depend on each other in a cycle, which `resolve` can't
+  /// handle.
+  pub fn validate(&mut self) -> Result<(), LayoutCycleError> {
+    self.sort_constraints().map(|_| ())
+  }
+
+  fn sort_constraints(&mut self) -> Result<(DependencyGraph, Vec<NodeIndex>), LayoutCycleError> {
     let graph = self.build_dependency_graph();
 
-    let topology = match toposort(&graph, None) {
-      Ok(mut topology) => {
-        //topology.reverse();
-        topology
-      }
+    match toposort(&graph, None) {
+      Ok(topology) => Ok((graph, topology)),
       Err(err) => {
-        let constraint_id = graph.node_weight(err.node_id()).unwrap();
-        let constraint = &self.constraints[*constraint_id];
-
-        eprintln!(
-          "DOT: \n{:?}\n\n",
-          Dot::with_attr_getters(
-            &graph,
-            &[],
-            &|_graph, edge| "".to_string(),
-            &|graph, (node, _)| {
-              let constraint_id = graph.node_weight(node).copied().unwrap();
-              let (element_id, constraint) = &self.constraints[constraint_id];
-              format!("label=\"{:?} on {}\"", constraint, element_id)
-            },
-          )
-        );
-        panic!(
-          "Cycle found in layout constraints, not supported yet! ID: {} Constraint: {:?}",
-          constraint_id, constraint
+        let constraint_id = *graph.node_weight(err.node_id()).unwrap();
+        let (element_id, constraint) = &self.constraints[constraint_id];
+
+        let dot = format!(
+          "{:?}",
+          Dot::with_attr_getters(&graph, &[], &|_graph, _edge| "".to_string(), &|graph,
+                                                                                 (
+            node,
+            _,
+          )| {
+            let constraint_id = graph.node_weight(node).copied().unwrap();
+            let (element_id, constraint) = &self.constraints[constraint_id];
+            format!("label=\"{:?} on {}\"", constraint, element_id)
+          },)
         );
+        Err(LayoutCycleError {
+          constraint_id,
+          element_id: *element_id,
+          constraint: format!("{:?}", constraint),
+          dot,
+        })
       }
-    };
-    //println!("START EXECUTION OF CONSTRAINTS =========");
+    }
+  }
+
+  /// Resolves all variables, fails without resolving anything if the constraints contain a cycle.
+  pub fn resolve(&mut self) -> Result<(), LayoutCycleError> {
+    let (graph, topology) = self.sort_constraints()?;
 
     for node_index in topology {
       let constraint_id = *graph.node_weight(node_index).unwrap();
@@ -407,11 +491,31 @@ impl ConstraintResolver {
           self.resolved_variables[dimension_variable_index] =
             (absolute_end - current_coordinate_value).max(0.0) + constant_offset;
         }
-        CompiledConstraint::TryAssumeParentSize { .. } => {}
+        CompiledConstraint::TryAssumeParentSize {
+          dimension,
+          constant_offset,
+        } => {
+          let (target_variable, source_variable) =
+            self.parent_size_variables(*element_id, dimension);
+          let source_index = self.map_element_variable_to_index(*element_id, source_variable);
+          let target_index = self.map_element_variable_to_index(*element_id, target_variable);
+          self.resolved_variables[target_index] =
+            self.resolved_variables[source_index] - *constant_offset;
+        }
         CompiledConstraint::ForcedConstAssignment { variable, constant } => {
           let target_variable_index = self.map_element_variable_to_index(*element_id, *variable);
           self.resolved_variables[target_variable_index] = *constant;
         }
+        CompiledConstraint::ClampMin { variable, constant } => {
+          let variable_index = self.map_element_variable_to_index(*element_id, *variable);
+          self.resolved_variables[variable_index] =
+            self.resolved_variables[variable_index].max(*constant);
+        }
+        CompiledConstraint::ClampMax { variable, constant } => {
+          let variable_index = self.map_element_variable_to_index(*element_id, *variable);
+          self.resolved_variables[variable_index] =
+            self.resolved_variables[variable_index].min(*constant);
+        }
         CompiledConstraint::ForcedVariableAssignment {
           target_variable,
           source_variable,
@@ -422,6 +526,17 @@ impl ConstraintResolver {
           self.resolved_variables[target_index] =
             self.resolved_variables[source_index] + *constant_offset;
         }
+        CompiledConstraint::ForcedVariableAssignmentScaled {
+          target_variable,
+          source_variable,
+          multiplier,
+          constant_offset,
+        } => {
+          let source_index = self.map_element_variable_to_index(*element_id, *source_variable);
+          let target_index = self.map_element_variable_to_index(*element_id, *target_variable);
+          self.resolved_variables[target_index] =
+            self.resolved_variables[source_index] * *multiplier + *constant_offset;
+        }
         CompiledConstraint::ForcedVariableAssignmentMaxOf {
           target_variable,
           source_variables,
@@ -451,6 +566,57 @@ impl ConstraintResolver {
         }
       }
     }
+
+    if let Some(grid) = self.snap {
+      self.snap_to_grid(grid);
+    }
+    Ok(())
+  }
+
+  fn snap_to_grid(&mut self, grid: f32) {
+    let snap = |value: f32| (value / grid).round() * grid;
+    for element_id in 0..self.relationships.len() {
+      let offset = self.map_element_variable_to_index(
+        element_id,
+        ConstraintVariable::ElementWidth { id: element_id },
+      );
+      let [width, height, x, y] = &mut self.resolved_variables[offset..offset + 4] else {
+        unreachable!();
+      };
+      let (right, bottom) = (snap(*x + *width), snap(*y + *height));
+      *x = snap(*x);
+      *y = snap(*y);
+      *width = right - *x;
+      *height = bottom - *y;
+    }
+  }
+
+  /// Variable assigned by a parent size constraint and the one it is read from, the root element
+  /// has no parent and takes the window size instead.
+  fn parent_size_variables(
+    &self,
+    element_id: usize,
+    dimension: &Dimension,
+  ) -> (ConstraintVariable, ConstraintVariable) {
+    let has_parent = self.relationships[element_id].parent_id.is_some();
+    match dimension {
+      Dimension::Width if has_parent => (
+        ConstraintVariable::SelfWidth,
+        ConstraintVariable::ParentWidth,
+      ),
+      Dimension::Width => (
+        ConstraintVariable::SelfWidth,
+        ConstraintVariable::WindowWidth,
+      ),
+      Dimension::Height if has_parent => (
+        ConstraintVariable::SelfHeight,
+        ConstraintVariable::ParentHeight,
+      ),
+      Dimension::Height => (
+        ConstraintVariable::SelfHeight,
+        ConstraintVariable::WindowHeight,
+      ),
+    }
   }
 
   fn map_element_variable_to_index(&self, self_id: usize, variable: ConstraintVariable) -> usize {
@@ -498,3 +664,27 @@ pub struct RelationshipMeta {
   pub children: Vec<usize>,
   pub depth: usize,
 }
+
+/// Constraints of a layout depend on each other in a cycle.
+#[derive(Debug, Clone)]
+pub struct LayoutCycleError {
+  /// Index of a constraint on the cycle
+  pub constraint_id: usize,
+  /// Element the constraint belongs to
+  pub element_id: usize,
+  pub constraint: String,
+  /// Dependency graph of all constraints in Graphviz format
+  pub dot: String,
+}
+
+impl std::fmt::Display for LayoutCycleError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "Cycle found in layout constraints at constraint {} of element {}: {}",
+      self.constraint_id, self.element_id, self.constraint
+    )
+  }
+}
+
+impl std::error::Error for LayoutCycleError {}
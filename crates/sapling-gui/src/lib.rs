@@ -1,3 +1,4 @@
+mod animation;
 mod base;
 mod component;
 mod debugger;
@@ -10,5 +11,9 @@ mod renderer;
 mod theme;
 
 pub use debugger::DebuggerView;
-pub use layout::{ConstraintResolver, ElementVariable, RelationshipMeta};
-pub use renderer::{NoopRenderer, RaylibRenderer, RaylibRendererState};
+pub use layout::{ConstraintResolver, ElementVariable, LayoutCycleError, RelationshipMeta};
+#[cfg(feature = "offscreen")]
+pub use renderer::RenderedImage;
+pub use renderer::{
+  NoopRenderer, RaylibRenderer, RaylibRendererState, RecordedDrawCall, SvgRenderer,
+};
@@ -1,16 +1,18 @@
 use std::{
   any::{Any, TypeId},
-  collections::HashMap,
-  time::Duration,
+  collections::{HashMap, HashSet},
+  time::{Duration, Instant},
 };
 
+use raylib::math::Rectangle;
 use sapling_app::App;
 
 use crate::{
+  animation::{AnimationConfig, Easing, LayoutAnimation},
   component::Component,
   input::InputState,
   layout::{
-    CompiledConstraint, ConstraintResolver, ConstraintVariable, Dimension, ElementVariable,
+    CompiledConstraint, ConstraintResolver, ConstraintVariable, Dimension, ElementExpr,
     RelationshipMeta, ResolvedLayout, UserElementConstraints,
   },
   prelude::Renderer,
@@ -19,21 +21,101 @@ use crate::{
 
 pub struct Orchestrator {
   elements: Vec<AllocatedElement>,
+  /// Elements of the previous frame, kept alive so their components can be unmounted
+  previous_elements: Vec<AllocatedElement>,
   debug_enabled: bool,
   debug_tree: Option<Vec<DebugAllocatedElement>>,
   mutable_state: HashMap<ComponentStateKey, Box<dyn Any>>,
+  animation: Option<AnimationConfig>,
+  /// Keyed by element path, state keys only hold the parent's key and repeat across subtrees
+  layout_animations: HashMap<String, LayoutAnimation>,
+  last_frame: Option<Instant>,
+  fixed_frame_time: Option<Duration>,
+  /// Layout cycle of the previous frames, the same cycle is only reported once
+  last_layout_error: Option<String>,
 }
 
 impl Orchestrator {
   pub fn new(debug_enabled: bool) -> Self {
     Orchestrator {
       elements: Vec::new(),
+      previous_elements: Vec::new(),
       debug_enabled,
       debug_tree: None,
       mutable_state: HashMap::new(),
+      animation: None,
+      layout_animations: HashMap::new(),
+      last_frame: None,
+      fixed_frame_time: None,
+      last_layout_error: None,
     }
   }
 
+  /// Interpolates the rendered layout of elements whose solved layout changed between frames.
+  ///
+  /// The solver still computes the target layout every frame, only the layout passed to
+  /// [`Component::render`] is animated. Newly appearing elements are rendered at their target
+  /// right away.
+  pub fn with_animations(mut self, duration: Duration, easing: Easing) -> Self {
+    self.animation = Some(AnimationConfig { duration, easing });
+    self
+  }
+
+  /// Advances animations by a fixed amount per frame instead of the measured wall clock time.
+  pub fn with_fixed_frame_time(mut self, frame_time: Duration) -> Self {
+    self.fixed_frame_time = Some(frame_time);
+    self
+  }
+
+  /// Drops all mutable state, every component re-runs its state initializer on the next frame.
+  ///
+  /// State keys are derived from the position of an element in the tree, hence switching the
+  /// document shown by a component reuses the state of the previous one. A per-frame collection
+  /// of unused state would only remove state of elements which disappeared, not of elements
+  /// that are still mounted at the same position, so this has to be triggered explicitly.
+  pub fn clear_state(&mut self) {
+    self.mutable_state.clear();
+  }
+
+  /// Drops the mutable state whose name starts with `key_prefix`, wherever it is in the tree.
+  ///
+  /// Names are chosen by the components, a shared prefix such as `structure_editor_` groups the
+  /// state belonging to one document. See [`Orchestrator::clear_state`] for why this isn't done
+  /// automatically.
+  pub fn clear_state_for(&mut self, key_prefix: &str) {
+    self
+      .mutable_state
+      .retain(|state_key, _| !state_key.name.starts_with(key_prefix));
+  }
+
+  /// Drops the mutable state of `element` and all of its descendants in the last frame.
+  ///
+  /// State keys only hold an element's own and its parent's key, so an element elsewhere in the
+  /// tree sharing both with an element of the subtree loses its state as well.
+  pub fn clear_state_for_element(&mut self, element: Element) {
+    // Parents are allocated before their children, a single pass finds the whole subtree
+    let mut in_subtree = vec![false; self.elements.len()];
+    let mut cleared = HashSet::new();
+    for id in element.id..self.elements.len() {
+      let allocated = &self.elements[id];
+      in_subtree[id] = id == element.id
+        || allocated
+          .parent_element
+          .is_some_and(|parent_id| in_subtree[parent_id]);
+      if in_subtree[id] {
+        let parent_key = allocated
+          .parent_element
+          .map(|parent_id| self.elements[parent_id].key.as_str())
+          .unwrap_or_default();
+        cleared.insert((allocated.key.as_str(), parent_key));
+      }
+    }
+
+    self.mutable_state.retain(|state_key, _| {
+      !cleared.contains(&(state_key.key.as_str(), state_key.parent_key.as_str()))
+    });
+  }
+
   pub fn construct_and_render<T: Component + 'static, TRenderer: Renderer>(
     &mut self,
     root: T,
@@ -45,34 +127,44 @@ impl Orchestrator {
     input_state: &InputState,
   ) -> OrchestratorStats {
     let construction_start = std::time::Instant::now();
-    self.elements.clear();
+    self.previous_elements = std::mem::take(&mut self.elements);
 
     // construction phase
     self.elements.push(AllocatedElement {
       parent_element: None,
       depth: 0,
       key: "root".to_string(),
+      layout_only: root.is_layout_only(),
       component: Some(Box::new(root)),
       direct_child_component_occurrences: HashMap::new(),
       constraints: vec![],
+      z_index: 0,
+      has_width_constraints: false,
+      has_height_constraints: false,
     });
 
     let element = self.elements.last_mut().unwrap();
+    let layout_only = element.layout_only;
     let mut component = element.component.take().unwrap();
 
-    component.construct(&mut ElementContext {
-      parent_element: Some(0),
-      depth: 1,
-      elements: &mut self.elements,
-      mutable_state: &mut self.mutable_state,
-      debug_enabled: self.debug_enabled,
-      render_width: width,
-      render_height: height,
-      prev_debug_nodes: &self.debug_tree,
-      input_state,
-      theme,
-      app,
-    });
+    if !layout_only {
+      component.construct(&mut ElementContext {
+        parent_element: Some(0),
+        depth: 1,
+        elements: &mut self.elements,
+        mutable_state: &mut self.mutable_state,
+        debug_enabled: self.debug_enabled,
+        render_width: width,
+        render_height: height,
+        prev_debug_nodes: &self.debug_tree,
+        input_state,
+        theme,
+        app,
+      });
+    }
+
+    self.elements[0].component = Some(component);
+    self.run_lifecycle_hooks(width, height, theme, app, input_state);
 
     let mut parent_children_relationship: HashMap<usize, Vec<usize>> = HashMap::new();
     let construction_end = std::time::Instant::now();
@@ -82,7 +174,8 @@ impl Orchestrator {
       element.constraints = UserElementConstraints::fixed_size(width, height)
         .merged(&UserElementConstraints::absolute_position(0.0, 0.0))
         .constraints;
-      element.component = Some(component);
+      element.has_width_constraints = true;
+      element.has_height_constraints = true;
 
       // Create tree info
       for (index, _) in self.elements.iter().enumerate() {
@@ -101,8 +194,8 @@ impl Orchestrator {
     // Post processing layout to add default assignments if needed
     let elements = self.elements.len();
     for element_id in 0..elements {
-      let mut has_explicit_width = false;
-      let mut has_explicit_height = false;
+      let has_explicit_width = self.elements[element_id].has_width_constraints;
+      let has_explicit_height = self.elements[element_id].has_height_constraints;
       let mut has_explicit_x = false;
       let mut has_explicit_y = false;
 
@@ -110,12 +203,6 @@ impl Orchestrator {
         let element = &mut self.elements[element_id];
         for constraint in element.constraints.iter() {
           match constraint.get_explicit_target() {
-            Some(ConstraintVariable::SelfWidth { .. }) => {
-              has_explicit_width = true;
-            }
-            Some(ConstraintVariable::SelfHeight { .. }) => {
-              has_explicit_height = true;
-            }
             Some(ConstraintVariable::SelfX { .. }) => {
               has_explicit_x = true;
             }
@@ -191,27 +278,142 @@ impl Orchestrator {
         .collect(),
       (width, height),
     );
-    solver.resolve();
+    // A cycle leaves every element unresolved for this frame instead of taking down the app
+    match solver.resolve() {
+      Ok(()) => self.last_layout_error = None,
+      Err(err) => {
+        let message = err.to_string();
+        if self.last_layout_error.as_ref() != Some(&message) {
+          eprintln!("{}\nDOT: \n{}\n", message, err.dot);
+          self.last_layout_error = Some(message);
+        }
+      }
+    }
 
     let layouting_end = std::time::Instant::now();
+
+    // Pointer events go to the topmost element under the pointer and bubble up its parents until
+    // one of them handles the event. Elements allocated while handling an event are discarded.
+    if !input_state.pointer_events.is_empty() {
+      let element_count = self.elements.len();
+      let layouts = (0..element_count)
+        .map(|id| solver.get_element_layout(id))
+        .collect::<Vec<_>>();
+
+      // The topmost element is the one drawn last
+      let hit_order = render_order(&self.elements);
+
+      for event in &input_state.pointer_events {
+        // Parts of an element clipped by its ancestors aren't visible and can't be hit either
+        let mut current = hit_order.iter().rev().copied().find(|id| {
+          !self.elements[*id].layout_only
+            && layouts[*id].contains(event.position)
+            && clipping_ancestors(&self.elements, *id)
+              .iter()
+              .all(|clip_id| layouts[*clip_id].contains(event.position))
+        });
+
+        while let Some(id) = current {
+          current = self.elements[id].parent_element;
+          if self.elements[id].layout_only {
+            continue;
+          }
+          let Some(mut component) = self.elements[id].component.take() else {
+            continue;
+          };
+
+          let handled = component.on_pointer(
+            event.relative_to(&layouts[id]),
+            &mut ElementContext {
+              parent_element: Some(id),
+              depth: self.elements[id].depth + 1,
+              elements: &mut self.elements,
+              mutable_state: &mut self.mutable_state,
+              debug_enabled: self.debug_enabled,
+              render_width: width,
+              render_height: height,
+              prev_debug_nodes: &self.debug_tree,
+              input_state,
+              theme,
+              app,
+            },
+          );
+          self.elements.truncate(element_count);
+          self.elements[id].component = Some(component);
+
+          if handled {
+            break;
+          }
+        }
+      }
+    }
+
     let rendering_start = std::time::Instant::now();
 
+    let now = Instant::now();
+    let frame_time = self.fixed_frame_time.unwrap_or_else(|| {
+      self
+        .last_frame
+        .map(|last_frame| now - last_frame)
+        .unwrap_or_default()
+    });
+    self.last_frame = Some(now);
+    let mut animated_paths = HashSet::new();
+    let paths = self
+      .animation
+      .as_ref()
+      .map(|_| element_paths(&self.elements))
+      .unwrap_or_default();
+
     let mut total_constraints = 0;
+    let mut layouts = Vec::with_capacity(self.elements.len());
     for (id, element) in self.elements.iter().enumerate() {
-      let x = solver.get_element_variable_resolution(id, ElementVariable::X);
-      let y = solver.get_element_variable_resolution(id, ElementVariable::Y);
-      let width = solver.get_element_variable_resolution(id, ElementVariable::Width);
-      let height = solver.get_element_variable_resolution(id, ElementVariable::Height);
       total_constraints += element.constraints.len();
 
+      let mut layout = solver.get_element_layout(id);
+      if let Some(config) = self.animation.as_ref().filter(|_| !element.layout_only) {
+        let path = &paths[id];
+        layout = self
+          .layout_animations
+          .entry(path.clone())
+          .or_insert_with(|| LayoutAnimation::settled(layout.clone(), config))
+          .advance(&layout, frame_time, config);
+        animated_paths.insert(path);
+      }
+      layouts.push(layout);
+    }
+
+    renderer.begin_frame(theme);
+    let mut active_clips: Vec<usize> = Vec::new();
+    for id in render_order(&self.elements) {
+      let element = &self.elements[id];
+
+      // Layout only elements have nothing to draw
+      if element.layout_only {
+        continue;
+      }
+
+      // Elements of a subtree don't have to be drawn in one go, so the clips are switched to the
+      // ones of the current element's ancestors
+      let clips = clipping_ancestors(&self.elements, id);
+      let shared_clips = active_clips
+        .iter()
+        .zip(&clips)
+        .take_while(|(active, clip)| active == clip)
+        .count();
+      for _ in shared_clips..active_clips.len() {
+        renderer.end_clip();
+      }
+      active_clips.truncate(shared_clips);
+      for clip_id in &clips[shared_clips..] {
+        let clip = &layouts[*clip_id];
+        renderer.begin_clip(Rectangle::new(clip.x, clip.y, clip.width, clip.height));
+        active_clips.push(*clip_id);
+      }
+
       if let Some(component) = &element.component {
         component.render(&mut RenderContext {
-          layout: &ResolvedLayout {
-            x: x as f32,
-            y: y as f32,
-            width: width as f32,
-            height: height as f32,
-          },
+          layout: &layouts[id],
           theme,
           renderer,
           input_state,
@@ -223,6 +425,18 @@ impl Orchestrator {
         eprintln!("Allocated element has no component")
       }
     }
+    for _ in active_clips {
+      renderer.end_clip();
+    }
+    // Clips a component began without ending them would otherwise leak into the next frame
+    while renderer.clip_depth() > 0 {
+      renderer.end_clip();
+    }
+
+    // Elements which disappeared start from their target again once they reappear
+    self
+      .layout_animations
+      .retain(|path, _| animated_paths.contains(path));
 
     let rendering_end = std::time::Instant::now();
 
@@ -248,6 +462,74 @@ impl Orchestrator {
     }
   }
 
+  /// Mounts elements whose key path didn't exist in the previous frame and unmounts the previous
+  /// frame's elements whose key path is gone. Unmounting runs first and children are unmounted
+  /// before their parents, while parents are mounted before their children.
+  fn run_lifecycle_hooks(
+    &mut self,
+    width: f32,
+    height: f32,
+    theme: &mut Theme,
+    app: &mut App,
+    input_state: &InputState,
+  ) {
+    let paths = element_paths(&self.elements);
+    let previous_paths = element_paths(&self.previous_elements);
+    let mounted = paths.iter().collect::<HashSet<_>>();
+    let previously_mounted = previous_paths.iter().collect::<HashSet<_>>();
+
+    let previous_count = self.previous_elements.len();
+    for id in (0..previous_count).rev() {
+      if mounted.contains(&previous_paths[id]) || self.previous_elements[id].layout_only {
+        continue;
+      }
+      let Some(mut component) = self.previous_elements[id].component.take() else {
+        continue;
+      };
+      component.on_unmount(&mut ElementContext {
+        parent_element: Some(id),
+        depth: self.previous_elements[id].depth + 1,
+        elements: &mut self.previous_elements,
+        mutable_state: &mut self.mutable_state,
+        debug_enabled: self.debug_enabled,
+        render_width: width,
+        render_height: height,
+        prev_debug_nodes: &self.debug_tree,
+        input_state,
+        theme,
+        app,
+      });
+      self.previous_elements.truncate(previous_count);
+      self.previous_elements[id].component = Some(component);
+    }
+
+    // Elements allocated while mounting are discarded, like the ones of pointer handlers
+    let element_count = self.elements.len();
+    for id in 0..element_count {
+      if previously_mounted.contains(&paths[id]) || self.elements[id].layout_only {
+        continue;
+      }
+      let Some(mut component) = self.elements[id].component.take() else {
+        continue;
+      };
+      component.on_mount(&mut ElementContext {
+        parent_element: Some(id),
+        depth: self.elements[id].depth + 1,
+        elements: &mut self.elements,
+        mutable_state: &mut self.mutable_state,
+        debug_enabled: self.debug_enabled,
+        render_width: width,
+        render_height: height,
+        prev_debug_nodes: &self.debug_tree,
+        input_state,
+        theme,
+        app,
+      });
+      self.elements.truncate(element_count);
+      self.elements[id].component = Some(component);
+    }
+  }
+
   fn print_debug_tree(&self, depth: usize, element: &DebugAllocatedElement) {
     println!(
       "{}{} (X:{}, Y:{}, W:{}, H:{})",
@@ -279,18 +561,7 @@ impl Orchestrator {
   ) -> DebugAllocatedElement {
     let children = relationships.get(&element_id).unwrap();
 
-    let layout = {
-      let x = solver.get_element_variable_resolution(element_id, ElementVariable::X);
-      let y = solver.get_element_variable_resolution(element_id, ElementVariable::Y);
-      let width = solver.get_element_variable_resolution(element_id, ElementVariable::Width);
-      let height = solver.get_element_variable_resolution(element_id, ElementVariable::Height);
-      ResolvedLayout {
-        x,
-        y,
-        width,
-        height,
-      }
-    };
+    let layout = solver.get_element_layout(element_id);
 
     let debug_info = format!("{:#?}", element.component);
     let component_name = format!("{:?}", element.component)
@@ -318,6 +589,54 @@ impl Orchestrator {
   }
 }
 
+/// Keys of every element joined with the keys of its ancestors, unique within a frame.
+fn element_paths(elements: &[AllocatedElement]) -> Vec<String> {
+  let mut paths: Vec<String> = Vec::with_capacity(elements.len());
+  for element in elements {
+    let path = match element.parent_element {
+      Some(parent_id) => format!("{}/{}", paths[parent_id], element.key),
+      None => element.key.clone(),
+    };
+    paths.push(path);
+  }
+  paths
+}
+
+/// Element ids sorted by their z-index summed up along their ancestors, ties keep the allocation
+/// order.
+fn render_order(elements: &[AllocatedElement]) -> Vec<usize> {
+  // Parents are allocated before their children, so their z-index is always known already
+  let mut z_indices: Vec<i32> = Vec::with_capacity(elements.len());
+  for element in elements {
+    let parent_z_index = element
+      .parent_element
+      .map(|parent_id| z_indices[parent_id])
+      .unwrap_or(0);
+    z_indices.push(parent_z_index + element.z_index);
+  }
+  let mut order = (0..elements.len()).collect::<Vec<_>>();
+  order.sort_by_key(|id| (z_indices[*id], *id));
+  order
+}
+
+/// Ancestors of the element clipping their children, outermost first.
+fn clipping_ancestors(elements: &[AllocatedElement], id: usize) -> Vec<usize> {
+  let mut clips = Vec::new();
+  let mut current = elements[id].parent_element;
+  while let Some(parent_id) = current {
+    let clips_children = elements[parent_id]
+      .component
+      .as_ref()
+      .is_some_and(|component| component.clips_children());
+    if clips_children {
+      clips.push(parent_id);
+    }
+    current = elements[parent_id].parent_element;
+  }
+  clips.reverse();
+  clips
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Element {
   pub id: usize,
@@ -339,6 +658,34 @@ impl Element {
   pub fn height(&self) -> ConstraintVariable {
     ConstraintVariable::ElementHeight { id: self.id }
   }
+
+  /// `x + width`
+  pub fn right(&self) -> ElementExpr {
+    ElementExpr {
+      terms: vec![(self.x(), 1.0), (self.width(), 1.0)],
+    }
+  }
+
+  /// `y + height`
+  pub fn bottom(&self) -> ElementExpr {
+    ElementExpr {
+      terms: vec![(self.y(), 1.0), (self.height(), 1.0)],
+    }
+  }
+
+  /// `x + width / 2`
+  pub fn center_x(&self) -> ElementExpr {
+    ElementExpr {
+      terms: vec![(self.x(), 1.0), (self.width(), 0.5)],
+    }
+  }
+
+  /// `y + height / 2`
+  pub fn center_y(&self) -> ElementExpr {
+    ElementExpr {
+      terms: vec![(self.y(), 1.0), (self.height(), 0.5)],
+    }
+  }
 }
 
 struct AllocatedElement {
@@ -348,6 +695,14 @@ struct AllocatedElement {
   constraints: Vec<CompiledConstraint>,
   direct_child_component_occurrences: HashMap<TypeId, usize>,
   key: String,
+  layout_only: bool,
+  z_index: i32,
+  /// Set once a constraint assigns the element's width, see
+  /// [`ElementContext::set_element_constraints`]. Constraints only reading it, like centering,
+  /// leave the width to default to the size of the children.
+  has_width_constraints: bool,
+  /// Height counterpart of `has_width_constraints`
+  has_height_constraints: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -406,8 +761,6 @@ pub struct ElementContext<'a> {
 
 impl<'a> ElementContext<'a> {
   pub fn allocate_element<T: Component + 'static>(&mut self, component: T) -> Element {
-    let id = self.elements.len();
-
     let mut key = "root".to_string();
     if let Some(parent_id) = self.parent_element {
       let parent = &mut self.elements[parent_id];
@@ -423,18 +776,43 @@ impl<'a> ElementContext<'a> {
       key = format!("{:?}{}", type_id, index);
     }
 
+    self.push_element(component, key)
+  }
+
+  /// Allocates the element with a key supplied by the caller instead of its position among the
+  /// siblings of the same type. State keyed this way follows the element when a list reorders,
+  /// the key has to be unique among the element's siblings.
+  pub fn allocate_element_keyed<T: Component + 'static>(
+    &mut self,
+    component: T,
+    key: &str,
+  ) -> Element {
+    let key = format!("{:?}#{}", component.type_id(), key);
+    self.push_element(component, key)
+  }
+
+  fn push_element<T: Component + 'static>(&mut self, component: T, key: String) -> Element {
+    let id = self.elements.len();
     self.elements.push(AllocatedElement {
       parent_element: self.parent_element,
+      layout_only: component.is_layout_only(),
       component: Some(Box::new(component)),
       constraints: Vec::new(),
       depth: self.depth,
       key,
       direct_child_component_occurrences: HashMap::new(),
+      z_index: 0,
+      has_width_constraints: false,
+      has_height_constraints: false,
     });
     Element { id }
   }
 
   pub fn construct_element(&mut self, element: &Element) {
+    if self.elements[element.id].layout_only {
+      return;
+    }
+
     let mut component = self.elements[element.id].component.take().unwrap();
     component.construct(&mut ElementContext {
       elements: self.elements,
@@ -472,6 +850,21 @@ impl<'a> ElementContext<'a> {
     self.parent_element.unwrap_or_default()
   }
 
+  /// Direct children allocated so far for the element currently being constructed.
+  pub fn child_elements(&self) -> Vec<Element> {
+    let Some(parent_id) = self.parent_element else {
+      return Vec::new();
+    };
+    self
+      .elements
+      .iter()
+      .enumerate()
+      .skip(parent_id + 1)
+      .filter(|(_, element)| element.parent_element == Some(parent_id))
+      .map(|(id, _)| Element { id })
+      .collect()
+  }
+
   pub fn set_parent_element_constraints(&mut self, constraints: Vec<CompiledConstraint>) {
     if let Some(parent_id) = self.parent_element {
       self.set_element_constraints(&Element { id: parent_id }, constraints);
@@ -485,8 +878,21 @@ impl<'a> ElementContext<'a> {
   ) {
     let id = element.id;
     let element = self.elements.get_mut(id).unwrap();
+    for constraint in &constraints {
+      match constraint.get_explicit_target() {
+        Some(ConstraintVariable::SelfWidth) => element.has_width_constraints = true,
+        Some(ConstraintVariable::SelfHeight) => element.has_height_constraints = true,
+        _ => {}
+      }
+    }
     element.constraints.extend(constraints);
   }
+
+  /// Moves the element and its subtree in front of (or behind) its siblings. The z-index is
+  /// relative to the parent's, elements with the same z-index are drawn in allocation order.
+  pub fn set_z_index(&mut self, element: &Element, z_index: i32) {
+    self.elements[element.id].z_index = z_index;
+  }
 }
 
 pub trait StatefulContext {
@@ -1,20 +1,24 @@
+pub use crate::animation::Easing;
 pub use crate::base::{
-  DropShadowStyle, FocusableInteractiveView, FormattedTextView, LayoutView, MutableState,
-  StyledView, TextFormat, TextHorizontalAlignment, TextVerticalAlignment, TextView, ViewStyle,
+  BackgroundFill, DropShadowStyle, FocusableInteractiveView, FormattedTextView, GridView, HStack,
+  LayoutView, MutableState, Pressable, ScrollView, StyledView, TextFormat, TextHorizontalAlignment,
+  TextVerticalAlignment, TextView, VStack, ViewStyle,
 };
 pub use crate::component::{
   ChildrenProperty, Component, ComponentElement, LayoutedComponent, ParentComponent,
 };
-pub use crate::input::{ActionMap, InputState};
+pub use crate::input::{
+  ActionMap, InputState, KeyModifiers, PointerEvent, PointerEventKind, action_hash,
+};
 pub use crate::layout::{
-  CompiledConstraint, ConstraintVariable, Dimension, ResolvedLayout, UserElementConstraint,
-  UserElementConstraintExpression, UserElementConstraintOperator, UserElementConstraintTerm,
-  UserElementConstraints,
+  CompiledConstraint, ConstraintVariable, Dimension, ElementExpr, Flex, ResolvedLayout,
+  UserElementConstraint, UserElementConstraintExpression, UserElementConstraintOperator,
+  UserElementConstraintTerm, UserElementConstraints,
 };
 pub use crate::orchestrator::{
   Element, ElementContext, Orchestrator, RenderContext, StatefulContext,
 };
 pub use crate::renderer::{RenderFilter, Renderer};
-pub use crate::theme::{FontVariant, Theme};
+pub use crate::theme::{FontVariant, Theme, ThemeVariant};
 
 pub use raylib::prelude::{Color, KeyboardKey, Rectangle, Vector2, Vector3, Vector4};
@@ -1,9 +1,13 @@
+use std::{cell::RefCell, rc::Rc};
+
 use raylib::{
   color::Color,
   math::{Rectangle, Vector2, Vector4},
 };
 
 pub trait Renderer {
+  /// Called by the orchestrator before the first element of a frame is drawn.
+  fn begin_frame(&mut self, _theme: &Theme) {}
   fn draw_with_filter(
     &mut self,
     _ty: RenderFilter,
@@ -20,6 +24,25 @@ pub trait Renderer {
   ) {
   }
   fn draw_rectangle(&mut self, _rect: Rectangle, _radii: Vector4, _fill: Color) {}
+  /// Fills the rectangle with a linear gradient, see [`crate::prelude::BackgroundFill`] for the
+  /// meaning of `angle`.
+  fn draw_rectangle_gradient(
+    &mut self,
+    _rect: Rectangle,
+    _radii: Vector4,
+    _start: Color,
+    _end: Color,
+    _angle: f32,
+  ) {
+  }
+  /// Restricts drawing to `rect` until the matching [`Renderer::end_clip`], nested clips only
+  /// draw inside the intersection with the outer clip.
+  fn begin_clip(&mut self, _rect: Rectangle) {}
+  fn end_clip(&mut self) {}
+  /// Number of clips begun but not ended yet.
+  fn clip_depth(&self) -> usize {
+    0
+  }
   fn draw_rectangle_border(
     &mut self,
     _rect: Rectangle,
@@ -30,17 +53,185 @@ pub trait Renderer {
   }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum RenderFilter {
-  Blur { amount: f32 },
+  Blur {
+    amount: f32,
+  },
+  /// Blends the filtered drawing with the given alpha, clamped to `[0, 1]`.
+  Opacity {
+    alpha: f32,
+  },
+}
+
+/// Drawing recorded by [`NoopRenderer::recording`], with the arguments it was called with.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecordedDrawCall {
+  Rectangle {
+    rect: Rectangle,
+    radii: Vector4,
+    fill: Color,
+  },
+  Gradient {
+    rect: Rectangle,
+    radii: Vector4,
+    start: Color,
+    end: Color,
+    angle: f32,
+  },
+  Border {
+    rect: Rectangle,
+    radii: Vector4,
+    border: Color,
+    thickness: f32,
+  },
+  Text {
+    text: String,
+    position: Vector2,
+    font_size: f32,
+    color: Color,
+  },
+  /// The drawing done inside the filter is kept apart in `calls`.
+  Filter {
+    filter: RenderFilter,
+    calls: Vec<RecordedDrawCall>,
+  },
+}
+
+/// Renderer drawing nothing, it only keeps track of the clips like a real renderer would.
+#[derive(Default)]
+pub struct NoopRenderer {
+  clip_stack: Vec<Rectangle>,
+  // Shared so the drawing of a filter can be collected after its renderer was dropped
+  recorded: Option<Rc<RefCell<Vec<RecordedDrawCall>>>>,
+}
+
+impl NoopRenderer {
+  /// Renderer remembering every draw call, see [`NoopRenderer::drawn_calls`].
+  pub fn recording() -> Self {
+    Self {
+      clip_stack: Vec::new(),
+      recorded: Some(Rc::default()),
+    }
+  }
+
+  /// Clips begun but not ended yet, outermost first.
+  pub fn clip_stack(&self) -> &[Rectangle] {
+    &self.clip_stack
+  }
+
+  /// Draw calls in the order they were made, always empty unless created with
+  /// [`NoopRenderer::recording`].
+  pub fn drawn_calls(&self) -> Vec<RecordedDrawCall> {
+    self
+      .recorded
+      .as_ref()
+      .map(|recorded| recorded.borrow().clone())
+      .unwrap_or_default()
+  }
+
+  fn record(&mut self, call: RecordedDrawCall) {
+    if let Some(recorded) = &self.recorded {
+      recorded.borrow_mut().push(call);
+    }
+  }
 }
 
-pub struct NoopRenderer;
+impl Renderer for NoopRenderer {
+  fn draw_with_filter(
+    &mut self,
+    ty: RenderFilter,
+    filter: Box<dyn for<'a> FnOnce(Box<dyn Renderer + 'a>)>,
+  ) {
+    let Some(recorded) = &self.recorded else {
+      filter(Box::new(NoopRenderer::default()));
+      return;
+    };
+
+    let inner = NoopRenderer::recording();
+    let calls = inner.recorded.clone().unwrap();
+    filter(Box::new(inner));
+    recorded.borrow_mut().push(RecordedDrawCall::Filter {
+      filter: ty,
+      calls: calls.take(),
+    });
+  }
+
+  fn draw_text(
+    &mut self,
+    _font: &mut Font,
+    text: &str,
+    position: Vector2,
+    font_size: f32,
+    color: Color,
+  ) {
+    if self.recorded.is_some() {
+      self.record(RecordedDrawCall::Text {
+        text: text.to_string(),
+        position,
+        font_size,
+        color,
+      });
+    }
+  }
+
+  fn draw_rectangle(&mut self, rect: Rectangle, radii: Vector4, fill: Color) {
+    self.record(RecordedDrawCall::Rectangle { rect, radii, fill });
+  }
+
+  fn draw_rectangle_gradient(
+    &mut self,
+    rect: Rectangle,
+    radii: Vector4,
+    start: Color,
+    end: Color,
+    angle: f32,
+  ) {
+    self.record(RecordedDrawCall::Gradient {
+      rect,
+      radii,
+      start,
+      end,
+      angle,
+    });
+  }
+
+  fn begin_clip(&mut self, rect: Rectangle) {
+    self.clip_stack.push(rect);
+  }
+
+  fn end_clip(&mut self) {
+    self.clip_stack.pop();
+  }
+
+  fn clip_depth(&self) -> usize {
+    self.clip_stack.len()
+  }
 
-impl Renderer for NoopRenderer {}
+  fn draw_rectangle_border(
+    &mut self,
+    rect: Rectangle,
+    radii: Vector4,
+    border: Color,
+    thickness: f32,
+  ) {
+    self.record(RecordedDrawCall::Border {
+      rect,
+      radii,
+      border,
+      thickness,
+    });
+  }
+}
 
 mod raylib_renderer;
 mod raylib_util;
+mod shape_cache;
+mod svg_renderer;
 
+#[cfg(feature = "offscreen")]
+pub use raylib_renderer::RenderedImage;
 pub use raylib_renderer::{RaylibRenderer, RaylibRendererState};
+pub use svg_renderer::SvgRenderer;
 
-use crate::font::Font;
+use crate::{font::Font, theme::Theme};
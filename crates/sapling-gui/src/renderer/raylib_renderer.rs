@@ -9,19 +9,28 @@ use raylib::{
   texture::RenderTexture2D,
 };
 
+#[cfg(feature = "offscreen")]
+use raylib::prelude::RaylibDrawHandle;
+
 use crate::{
   prelude::RenderFilter,
   renderer::{
     Renderer,
     raylib_util::{CornerRadii, draw_round_rect_per_corner},
+    shape_cache::{ShapeCache, ShapeKey},
   },
+  theme::Theme,
 };
 
+const DEFAULT_MAX_CACHE_ENTRIES: usize = 512;
+
 pub struct RaylibRendererState {
   render_texture: RenderTexture2D,
   blur_shader: Shader,
   blur_shader_location_radius: i32,
   blur_shader_location_render_width: i32,
+  shape_cache: ShapeCache<RenderTexture2D>,
+  theme_revision: Option<u64>,
 }
 
 impl RaylibRendererState {
@@ -47,14 +56,122 @@ impl RaylibRendererState {
       blur_shader,
       blur_shader_location_radius,
       blur_shader_location_render_width,
+      shape_cache: ShapeCache::new(DEFAULT_MAX_CACHE_ENTRIES),
+      theme_revision: None,
+    }
+  }
+
+  /// Limits how many rasterized rectangles are kept around, the least recently drawn ones are
+  /// dropped first.
+  pub fn with_max_cache_entries(mut self, max_cache_entries: usize) -> Self {
+    self.shape_cache.set_max_entries(max_cache_entries);
+    self
+  }
+
+  /// Drops all rasterized rectangles. This already happens when a frame is drawn with another
+  /// theme, see [`Theme::revision`].
+  pub fn invalidate_shape_cache(&mut self) {
+    self.shape_cache.clear();
+  }
+
+  fn sync_theme(&mut self, theme: &Theme) {
+    if self.theme_revision != Some(theme.revision()) {
+      self.theme_revision = Some(theme.revision());
+      self.shape_cache.clear();
     }
   }
+
+  /// Number of rectangle textures rasterized so far.
+  pub fn shape_textures_created(&self) -> usize {
+    self.shape_cache.created()
+  }
+
+  /// Renders `f` into an offscreen texture of the given size and reads back its pixels, meant for
+  /// comparing against reference images in tests.
+  #[cfg(feature = "offscreen")]
+  pub fn render_to_image(
+    &mut self,
+    handle: &mut RaylibHandle,
+    thread: &RaylibThread,
+    width: u32,
+    height: u32,
+    f: impl FnOnce(&mut RaylibRenderer<'_, RaylibDrawHandle<'_>>),
+  ) -> RenderedImage {
+    let target = handle.load_render_texture(thread, width, height).unwrap();
+
+    {
+      let mut draw = handle.begin_drawing(thread);
+      unsafe { raylib::ffi::BeginTextureMode(*target) };
+      draw.clear_background(Color::BLANK);
+
+      let mut renderer = RaylibRenderer::new(draw, self, thread.clone());
+      renderer.target = Some(*target);
+      f(&mut renderer);
+      unsafe { raylib::ffi::EndTextureMode() };
+    }
+
+    RenderedImage::read_back(&target)
+  }
+}
+
+/// RGBA pixels of an offscreen rendering, row by row starting at the top.
+#[cfg(feature = "offscreen")]
+pub struct RenderedImage {
+  width: u32,
+  height: u32,
+  pixels: Vec<u8>,
+}
+
+#[cfg(feature = "offscreen")]
+impl RenderedImage {
+  fn read_back(target: &RenderTexture2D) -> Self {
+    unsafe {
+      let mut image = raylib::ffi::LoadImageFromTexture(target.texture);
+      // Render textures are stored bottom up
+      raylib::ffi::ImageFlipVertical(&mut image);
+      raylib::ffi::ImageFormat(
+        &mut image,
+        raylib::ffi::PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8 as i32,
+      );
+      let len = image.width as usize * image.height as usize * 4;
+      let pixels = std::slice::from_raw_parts(image.data as *const u8, len).to_vec();
+      raylib::ffi::UnloadImage(image);
+
+      Self {
+        width: image.width as u32,
+        height: image.height as u32,
+        pixels,
+      }
+    }
+  }
+
+  pub fn width(&self) -> u32 {
+    self.width
+  }
+
+  pub fn height(&self) -> u32 {
+    self.height
+  }
+
+  pub fn pixels(&self) -> &[u8] {
+    &self.pixels
+  }
+
+  pub fn pixel(&self, x: u32, y: u32) -> Color {
+    let index = (y as usize * self.width as usize + x as usize) * 4;
+    let rgba = &self.pixels[index..index + 4];
+    Color::new(rgba[0], rgba[1], rgba[2], rgba[3])
+  }
 }
 
 pub struct RaylibRenderer<'state, THandle: RaylibDraw> {
   draw: THandle,
   thread: RaylibThread,
   state: Option<&'state mut RaylibRendererState>,
+  clip_stack: Vec<Rectangle>,
+  /// Texture drawn into instead of the screen, raylib has no nested texture modes so it is
+  /// restored after each pass rendering into a texture of its own.
+  target: Option<raylib::ffi::RenderTexture2D>,
 }
 
 impl<'state, THandle: RaylibDraw + DerefMut<Target = RaylibHandle>>
@@ -65,6 +182,8 @@ impl<'state, THandle: RaylibDraw + DerefMut<Target = RaylibHandle>>
       draw,
       state: Some(state),
       thread,
+      clip_stack: Vec::new(),
+      target: None,
     }
   }
 
@@ -72,6 +191,72 @@ impl<'state, THandle: RaylibDraw + DerefMut<Target = RaylibHandle>>
     self.draw
   }
 
+  /// Draws a rounded rectangle from the shape cache, rasterizing it on the first use. The texture
+  /// has the quantized size of the cache key and is stretched to the exact rectangle. Outside of
+  /// the root renderer there is no cache and the shape is drawn directly.
+  fn draw_cached_shape(
+    &mut self,
+    rect: Rectangle,
+    radii: Vector4,
+    color: Color,
+    border: Option<f32>,
+  ) {
+    let key = ShapeKey::new(rect.width, rect.height, radii, color, border);
+    let Some(state) = self
+      .state
+      .as_mut()
+      .filter(|_| key.width() > 0 && key.height() > 0)
+    else {
+      draw_shape(&mut self.draw, rect, radii, color, border);
+      return;
+    };
+
+    let draw = &mut self.draw;
+    let thread = &self.thread;
+    let clip_stack = &self.clip_stack;
+    let target = self.target;
+    let texture = state.shape_cache.get_or_insert_with(key, |key| {
+      // The scissor rectangle is in screen coordinates and would cut into the texture
+      if !clip_stack.is_empty() {
+        unsafe { raylib::ffi::EndScissorMode() };
+      }
+      let texture = rasterize_shape(draw, thread, key);
+      if let Some(target) = target {
+        unsafe { raylib::ffi::BeginTextureMode(target) };
+      }
+      if let Some(clip) = clip_stack.last() {
+        begin_scissor(clip);
+      }
+      texture
+    });
+
+    let padding = shape_padding(key.border());
+    draw.draw_texture_pro(
+      texture,
+      Rectangle {
+        x: 0.0,
+        y: 0.0,
+        width: texture.texture.width as f32,
+        height: -(texture.texture.height as f32), // Flip vertically for render texture
+      },
+      Rectangle::new(
+        rect.x - padding,
+        rect.y - padding,
+        rect.width + 2.0 * padding,
+        rect.height + 2.0 * padding,
+      ),
+      Vector2::zero(),
+      0.0,
+      Color::WHITE.alpha(color.a as f32 / 255.0),
+    );
+  }
+
+  fn restore_target(&self) {
+    if let Some(target) = self.target {
+      unsafe { raylib::ffi::BeginTextureMode(target) };
+    }
+  }
+
   fn prepare_render_texture(&mut self) {
     let height = self.draw.get_render_height();
     let width = self.draw.get_render_width();
@@ -89,6 +274,12 @@ impl<'state, THandle: RaylibDraw + DerefMut<Target = RaylibHandle>>
 impl<'state, THandle: RaylibDraw + DerefMut<Target = RaylibHandle>> Renderer
   for RaylibRenderer<'state, THandle>
 {
+  fn begin_frame(&mut self, theme: &Theme) {
+    if let Some(state) = self.state.as_mut() {
+      state.sync_theme(theme);
+    }
+  }
+
   fn draw_with_filter(
     &mut self,
     ty: RenderFilter,
@@ -109,10 +300,13 @@ impl<'state, THandle: RaylibDraw + DerefMut<Target = RaylibHandle>> Renderer
         draw: texture_mode,
         state: None,
         thread,
+        clip_stack: Vec::new(),
+        target: None,
       });
 
       filter(renderer);
     }
+    self.restore_target();
 
     let width = self.draw.get_render_width();
     let height = self.draw.get_render_height();
@@ -141,24 +335,67 @@ impl<'state, THandle: RaylibDraw + DerefMut<Target = RaylibHandle>> Renderer
           Color::WHITE,
         );
       }
+      RenderFilter::Opacity { alpha } => {
+        let state = self.state.as_ref().unwrap();
+
+        self.draw.draw_texture_rec(
+          &state.render_texture,
+          Rectangle {
+            x: 0.0,
+            y: 0.0,
+            width: width as f32,
+            height: -(height as f32), // Flip vertically for render texture
+          },
+          Vector2::new(0.0, 0.0),
+          Color::WHITE.alpha(alpha.clamp(0.0, 1.0)),
+        );
+      }
     }
   }
 
   fn draw_rectangle(&mut self, rect: Rectangle, radii: Vector4, fill: Color) {
-    draw_round_rect_per_corner(
-      &mut self.draw,
-      rect,
-      CornerRadii {
-        tl: radii.x,
-        tr: radii.y,
-        br: radii.z,
-        bl: radii.w,
-      },
-      16,
-      true,
-      0.0,
-      fill,
-    );
+    self.draw_cached_shape(rect, radii, fill, None);
+  }
+
+  /// Raylib can't blend colors across rounded corners, the gradient is drawn with square corners.
+  fn draw_rectangle_gradient(
+    &mut self,
+    rect: Rectangle,
+    _radii: Vector4,
+    start: Color,
+    end: Color,
+    angle: f32,
+  ) {
+    let color_at = |x: f32, y: f32| gradient_color_at(&rect, start, end, angle, x, y);
+    let top_left = color_at(rect.x, rect.y);
+    let bottom_left = color_at(rect.x, rect.y + rect.height);
+    let top_right = color_at(rect.x + rect.width, rect.y);
+    let bottom_right = color_at(rect.x + rect.width, rect.y + rect.height);
+    self
+      .draw
+      .draw_rectangle_gradient_ex(rect, top_left, bottom_left, top_right, bottom_right);
+  }
+
+  fn begin_clip(&mut self, rect: Rectangle) {
+    let rect = match self.clip_stack.last() {
+      Some(outer) => intersect_rectangles(outer, &rect),
+      None => rect,
+    };
+    self.clip_stack.push(rect);
+    begin_scissor(&rect);
+  }
+
+  fn end_clip(&mut self) {
+    self.clip_stack.pop();
+    // Raylib has no nested scissor modes, the outer clip has to be restored manually
+    match self.clip_stack.last() {
+      Some(outer) => begin_scissor(outer),
+      None => unsafe { raylib::ffi::EndScissorMode() },
+    }
+  }
+
+  fn clip_depth(&self) -> usize {
+    self.clip_stack.len()
   }
 
   fn draw_rectangle_border(
@@ -168,20 +405,7 @@ impl<'state, THandle: RaylibDraw + DerefMut<Target = RaylibHandle>> Renderer
     border: Color,
     thickness: f32,
   ) {
-    draw_round_rect_per_corner(
-      &mut self.draw,
-      rect,
-      CornerRadii {
-        tl: radii.x,
-        tr: radii.y,
-        br: radii.z,
-        bl: radii.w,
-      },
-      16,
-      false,
-      thickness,
-      border,
-    );
+    self.draw_cached_shape(rect, radii, border, Some(thickness));
   }
 
   fn draw_text(
@@ -195,3 +419,113 @@ impl<'state, THandle: RaylibDraw + DerefMut<Target = RaylibHandle>> Renderer
     font.draw_text(&mut self.draw, text, position, font_size, color);
   }
 }
+
+/// Draws a filled rounded rectangle, or only its outline when a border thickness is given.
+fn draw_shape<T: RaylibDraw>(
+  draw: &mut T,
+  rect: Rectangle,
+  radii: Vector4,
+  color: Color,
+  border: Option<f32>,
+) {
+  draw_round_rect_per_corner(
+    draw,
+    rect,
+    CornerRadii {
+      tl: radii.x,
+      tr: radii.y,
+      br: radii.z,
+      bl: radii.w,
+    },
+    16,
+    border.is_none(),
+    border.unwrap_or(0.0),
+    color,
+  );
+}
+
+/// Outlines are centered on the rectangle edge and spill over by half their thickness.
+fn shape_padding(border: Option<f32>) -> f32 {
+  border.map_or(0.0, |thickness| (thickness / 2.0).ceil())
+}
+
+/// Renders the shape opaque into a texture of its own, the alpha is applied as tint when drawing
+/// the texture so translucent colors aren't blended twice.
+fn rasterize_shape<THandle: RaylibDraw + DerefMut<Target = RaylibHandle>>(
+  draw: &mut THandle,
+  thread: &RaylibThread,
+  key: &ShapeKey,
+) -> RenderTexture2D {
+  let padding = shape_padding(key.border());
+  let mut texture = draw
+    .load_render_texture(
+      thread,
+      key.width() + 2 * padding as u32,
+      key.height() + 2 * padding as u32,
+    )
+    .unwrap();
+
+  {
+    let mut texture_mode = draw.begin_texture_mode(thread, &mut texture);
+    texture_mode.clear_background(Color::BLANK);
+    let color = key.color();
+    draw_shape(
+      &mut texture_mode,
+      Rectangle::new(padding, padding, key.width() as f32, key.height() as f32),
+      key.radii(),
+      Color::new(color.r, color.g, color.b, 255),
+      key.border(),
+    );
+  }
+
+  texture
+}
+
+fn begin_scissor(rect: &Rectangle) {
+  unsafe {
+    raylib::ffi::BeginScissorMode(
+      rect.x as i32,
+      rect.y as i32,
+      rect.width as i32,
+      rect.height as i32,
+    )
+  }
+}
+
+fn intersect_rectangles(a: &Rectangle, b: &Rectangle) -> Rectangle {
+  let x = a.x.max(b.x);
+  let y = a.y.max(b.y);
+  let width = ((a.x + a.width).min(b.x + b.width) - x).max(0.0);
+  let height = ((a.y + a.height).min(b.y + b.height) - y).max(0.0);
+  Rectangle::new(x, y, width, height)
+}
+
+/// Color of the gradient at a point, the gradient spans the projection of `rect` onto the
+/// direction given by `angle`.
+fn gradient_color_at(
+  rect: &Rectangle,
+  start: Color,
+  end: Color,
+  angle: f32,
+  x: f32,
+  y: f32,
+) -> Color {
+  let (sin, cos) = angle.to_radians().sin_cos();
+  let half_extent = (rect.width * cos.abs() + rect.height * sin.abs()) / 2.0;
+  if half_extent <= 0.0 {
+    return start;
+  }
+
+  let center_x = rect.x + rect.width / 2.0;
+  let center_y = rect.y + rect.height / 2.0;
+  let projection = (x - center_x) * cos + (y - center_y) * sin;
+  let t = ((projection + half_extent) / (2.0 * half_extent)).clamp(0.0, 1.0);
+  let lerp = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * t).round() as u8;
+
+  Color::new(
+    lerp(start.r, end.r),
+    lerp(start.g, end.g),
+    lerp(start.b, end.b),
+    lerp(start.a, end.a),
+  )
+}
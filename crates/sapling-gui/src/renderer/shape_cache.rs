@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use raylib::{color::Color, math::Vector4};
+
+/// Identifies a rasterized shape. Sizes are rounded to whole pixels, otherwise every sub-pixel
+/// layout change would add another entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ShapeKey {
+  width: u32,
+  height: u32,
+  radii: [u32; 4],
+  color: [u8; 4],
+  border: Option<u32>,
+}
+
+impl ShapeKey {
+  pub fn new(width: f32, height: f32, radii: Vector4, color: Color, border: Option<f32>) -> Self {
+    Self {
+      width: quantize(width),
+      height: quantize(height),
+      radii: [
+        quantize(radii.x),
+        quantize(radii.y),
+        quantize(radii.z),
+        quantize(radii.w),
+      ],
+      color: [color.r, color.g, color.b, color.a],
+      border: border.map(quantize),
+    }
+  }
+
+  pub fn width(&self) -> u32 {
+    self.width
+  }
+
+  pub fn height(&self) -> u32 {
+    self.height
+  }
+
+  pub fn color(&self) -> Color {
+    Color::new(self.color[0], self.color[1], self.color[2], self.color[3])
+  }
+
+  pub fn radii(&self) -> Vector4 {
+    Vector4::new(
+      self.radii[0] as f32,
+      self.radii[1] as f32,
+      self.radii[2] as f32,
+      self.radii[3] as f32,
+    )
+  }
+
+  pub fn border(&self) -> Option<f32> {
+    self.border.map(|border| border as f32)
+  }
+}
+
+fn quantize(value: f32) -> u32 {
+  value.max(0.0).round() as u32
+}
+
+/// Least recently used cache of rasterized shapes.
+pub struct ShapeCache<T> {
+  entries: HashMap<ShapeKey, (T, u64)>,
+  max_entries: usize,
+  tick: u64,
+  created: usize,
+}
+
+impl<T> ShapeCache<T> {
+  pub fn new(max_entries: usize) -> Self {
+    Self {
+      entries: HashMap::new(),
+      max_entries,
+      tick: 0,
+      created: 0,
+    }
+  }
+
+  /// Returns the cached shape for `key`, rasterizing it with `create` on a miss.
+  pub fn get_or_insert_with(&mut self, key: ShapeKey, create: impl FnOnce(&ShapeKey) -> T) -> &T {
+    self.tick += 1;
+    if !self.entries.contains_key(&key) {
+      self.evict_to(self.max_entries.saturating_sub(1));
+      self.entries.insert(key, (create(&key), self.tick));
+      self.created += 1;
+    }
+
+    let (shape, last_used) = self.entries.get_mut(&key).unwrap();
+    *last_used = self.tick;
+    shape
+  }
+
+  pub fn set_max_entries(&mut self, max_entries: usize) {
+    self.max_entries = max_entries;
+    self.evict_to(max_entries);
+  }
+
+  pub fn clear(&mut self) {
+    self.entries.clear();
+  }
+
+  /// Number of shapes rasterized since the cache was created.
+  pub fn created(&self) -> usize {
+    self.created
+  }
+
+  fn evict_to(&mut self, max_entries: usize) {
+    while self.entries.len() > max_entries {
+      let oldest = *self
+        .entries
+        .iter()
+        .min_by_key(|(_, (_, last_used))| *last_used)
+        .map(|(key, _)| key)
+        .unwrap();
+      self.entries.remove(&oldest);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn key(width: f32) -> ShapeKey {
+    ShapeKey::new(
+      width,
+      10.0,
+      Vector4::new(4.0, 4.0, 4.0, 4.0),
+      Color::RED,
+      None,
+    )
+  }
+
+  #[test]
+  fn test_sizes_are_quantized() {
+    let mut cache = ShapeCache::new(8);
+    cache.get_or_insert_with(key(20.2), |_| ());
+    cache.get_or_insert_with(key(19.8), |_| ());
+    cache.get_or_insert_with(key(20.6), |_| ());
+
+    assert_eq!(cache.created(), 2);
+    assert_eq!(key(20.2).width(), 20);
+  }
+
+  #[test]
+  fn test_least_recently_used_is_evicted() {
+    let mut cache = ShapeCache::new(2);
+    cache.get_or_insert_with(key(1.0), |_| 1);
+    cache.get_or_insert_with(key(2.0), |_| 2);
+    cache.get_or_insert_with(key(1.0), |_| 1);
+    cache.get_or_insert_with(key(3.0), |_| 3);
+
+    assert_eq!(*cache.get_or_insert_with(key(1.0), |_| 0), 1);
+    assert_eq!(cache.created(), 3);
+    // The second shape was evicted and has to be created again
+    assert_eq!(*cache.get_or_insert_with(key(2.0), |_| 0), 0);
+    assert_eq!(cache.created(), 4);
+  }
+}
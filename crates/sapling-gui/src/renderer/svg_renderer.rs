@@ -0,0 +1,273 @@
+use std::{cell::RefCell, fmt::Write, rc::Rc};
+
+use raylib::{
+  color::Color,
+  math::{Rectangle, Vector2, Vector4},
+};
+
+use crate::{
+  font::Font,
+  renderer::{RenderFilter, Renderer},
+};
+
+#[derive(Default)]
+struct SvgDocument {
+  defs: String,
+  body: String,
+  next_id: usize,
+}
+
+impl SvgDocument {
+  fn allocate_id(&mut self, prefix: &str) -> String {
+    self.next_id += 1;
+    format!("{}{}", prefix, self.next_id)
+  }
+}
+
+/// Renderer writing the drawing into an SVG document, e.g. to export layouts for documentation.
+pub struct SvgRenderer {
+  width: f32,
+  height: f32,
+  // Shared with the renderers handed to filters, their drawing ends up in the same document
+  document: Rc<RefCell<SvgDocument>>,
+  clip_depth: usize,
+}
+
+impl SvgRenderer {
+  pub fn new(width: f32, height: f32) -> Self {
+    Self {
+      width,
+      height,
+      document: Rc::default(),
+      clip_depth: 0,
+    }
+  }
+
+  /// Closes the clips still open and returns the complete document.
+  pub fn finish(mut self) -> String {
+    while self.clip_depth > 0 {
+      self.end_clip();
+    }
+
+    let document = self.document.borrow();
+    let mut svg = format!(
+      r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+      self.width, self.height, self.width, self.height
+    );
+    if !document.defs.is_empty() {
+      write!(svg, "<defs>{}</defs>", document.defs).unwrap();
+    }
+    svg.push_str(&document.body);
+    svg.push_str("</svg>");
+    svg
+  }
+}
+
+impl Renderer for SvgRenderer {
+  fn draw_with_filter(
+    &mut self,
+    ty: RenderFilter,
+    filter: Box<dyn for<'a> FnOnce(Box<dyn Renderer + 'a>)>,
+  ) {
+    {
+      let mut document = self.document.borrow_mut();
+      match ty {
+        RenderFilter::Blur { amount } => {
+          let id = document.allocate_id("blur");
+          write!(
+            document.defs,
+            r#"<filter id="{}"><feGaussianBlur stdDeviation="{}"/></filter>"#,
+            id, amount
+          )
+          .unwrap();
+          write!(document.body, r#"<g filter="url(#{})">"#, id).unwrap();
+        }
+        RenderFilter::Opacity { alpha } => {
+          write!(document.body, r#"<g opacity="{}">"#, alpha.clamp(0.0, 1.0)).unwrap();
+        }
+      }
+    }
+
+    filter(Box::new(SvgRenderer {
+      width: self.width,
+      height: self.height,
+      document: self.document.clone(),
+      clip_depth: 0,
+    }));
+
+    self.document.borrow_mut().body.push_str("</g>");
+  }
+
+  fn draw_text(
+    &mut self,
+    _font: &mut Font,
+    text: &str,
+    position: Vector2,
+    font_size: f32,
+    color: Color,
+  ) {
+    write!(
+      self.document.borrow_mut().body,
+      r#"<text x="{}" y="{}" font-size="{}" dominant-baseline="hanging"{}>{}</text>"#,
+      position.x,
+      position.y,
+      font_size,
+      paint("fill", color),
+      escape(text)
+    )
+    .unwrap();
+  }
+
+  fn draw_rectangle(&mut self, rect: Rectangle, radii: Vector4, fill: Color) {
+    let shape = shape(rect, radii, &paint("fill", fill));
+    self.document.borrow_mut().body.push_str(&shape);
+  }
+
+  fn draw_rectangle_gradient(
+    &mut self,
+    rect: Rectangle,
+    radii: Vector4,
+    start: Color,
+    end: Color,
+    angle: f32,
+  ) {
+    let mut document = self.document.borrow_mut();
+    let id = document.allocate_id("gradient");
+
+    // Spans the projection of the rectangle onto the gradient direction, like the raylib renderer
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let half_extent = (rect.width * cos.abs() + rect.height * sin.abs()) / 2.0;
+    let center_x = rect.x + rect.width / 2.0;
+    let center_y = rect.y + rect.height / 2.0;
+    write!(
+      document.defs,
+      r#"<linearGradient id="{}" gradientUnits="userSpaceOnUse" x1="{}" y1="{}" x2="{}" y2="{}"><stop offset="0"{}/><stop offset="1"{}/></linearGradient>"#,
+      id,
+      center_x - cos * half_extent,
+      center_y - sin * half_extent,
+      center_x + cos * half_extent,
+      center_y + sin * half_extent,
+      paint("stop-color", start),
+      paint("stop-color", end),
+    )
+    .unwrap();
+
+    let shape = shape(rect, radii, &format!(r#" fill="url(#{})""#, id));
+    document.body.push_str(&shape);
+  }
+
+  fn begin_clip(&mut self, rect: Rectangle) {
+    let mut document = self.document.borrow_mut();
+    let id = document.allocate_id("clip");
+    write!(
+      document.defs,
+      r#"<clipPath id="{}"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath>"#,
+      id, rect.x, rect.y, rect.width, rect.height
+    )
+    .unwrap();
+    // Nested groups only show the intersection of their clips
+    write!(document.body, r#"<g clip-path="url(#{})">"#, id).unwrap();
+    self.clip_depth += 1;
+  }
+
+  fn end_clip(&mut self) {
+    if self.clip_depth > 0 {
+      self.clip_depth -= 1;
+      self.document.borrow_mut().body.push_str("</g>");
+    }
+  }
+
+  fn clip_depth(&self) -> usize {
+    self.clip_depth
+  }
+
+  fn draw_rectangle_border(
+    &mut self,
+    rect: Rectangle,
+    radii: Vector4,
+    border: Color,
+    thickness: f32,
+  ) {
+    let attributes = format!(
+      r#" fill="none"{} stroke-width="{}""#,
+      paint("stroke", border),
+      thickness
+    );
+    let shape = shape(rect, radii, &attributes);
+    self.document.borrow_mut().body.push_str(&shape);
+  }
+}
+
+/// Color attribute, the alpha goes into a separate opacity attribute as SVG 1.1 has no RGBA.
+fn paint(attribute: &str, color: Color) -> String {
+  let mut paint = format!(
+    r##" {}="#{:02x}{:02x}{:02x}""##,
+    attribute, color.r, color.g, color.b
+  );
+  if color.a < 255 {
+    let opacity = if attribute == "stop-color" {
+      "stop-opacity".to_string()
+    } else {
+      format!("{}-opacity", attribute)
+    };
+    write!(paint, r#" {}="{}""#, opacity, color.a as f32 / 255.0).unwrap();
+  }
+  paint
+}
+
+/// A `<rect>` when all corners share the radius, otherwise a path with an arc per corner.
+fn shape(rect: Rectangle, radii: Vector4, attributes: &str) -> String {
+  let Rectangle {
+    x,
+    y,
+    width,
+    height,
+  } = rect;
+
+  if radii.x == radii.y && radii.y == radii.z && radii.z == radii.w {
+    let rounding = if radii.x > 0.0 {
+      format!(r#" rx="{}" ry="{}""#, radii.x, radii.x)
+    } else {
+      String::new()
+    };
+    return format!(
+      r#"<rect x="{}" y="{}" width="{}" height="{}"{}{}/>"#,
+      x, y, width, height, rounding, attributes
+    );
+  }
+
+  let (top_left, top_right, bottom_right, bottom_left) = (radii.x, radii.y, radii.z, radii.w);
+  format!(
+    r#"<path d="M {} {} H {} A {} {} 0 0 1 {} {} V {} A {} {} 0 0 1 {} {} H {} A {} {} 0 0 1 {} {} V {} A {} {} 0 0 1 {} {} Z"{}/>"#,
+    x + top_left,
+    y,
+    x + width - top_right,
+    top_right,
+    top_right,
+    x + width,
+    y + top_right,
+    y + height - bottom_right,
+    bottom_right,
+    bottom_right,
+    x + width - bottom_right,
+    y + height,
+    x + bottom_left,
+    bottom_left,
+    bottom_left,
+    x,
+    y + height - bottom_left,
+    y + top_left,
+    top_left,
+    top_left,
+    x + top_left,
+    y,
+    attributes
+  )
+}
+
+fn escape(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+}
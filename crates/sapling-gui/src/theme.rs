@@ -1,8 +1,17 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
 use raylib::{RaylibHandle, RaylibThread, color::Color, math::Vector2};
 
 use crate::{base::DropShadowStyle, font::Font};
 
+static NEXT_THEME_REVISION: AtomicU64 = AtomicU64::new(0);
+
+fn next_theme_revision() -> u64 {
+  NEXT_THEME_REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
 pub struct Theme {
+  pub variant: ThemeVariant,
   pub font_primary: Option<Font>,
   pub font_primary_bold: Option<Font>,
   pub color_primary: Color,
@@ -23,6 +32,25 @@ pub struct Theme {
   pub spacing_large: f32,
   pub spacing_xlarge: f32,
   pub drop_shadow_default: DropShadowStyle,
+  font_scale: f32,
+  revision: u64,
+}
+
+/// Color set of a [`Theme`], spacing and radius tokens are the same for every variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThemeVariant {
+  #[default]
+  Dark,
+  Light,
+}
+
+impl ThemeVariant {
+  pub fn toggled(self) -> Self {
+    match self {
+      ThemeVariant::Dark => ThemeVariant::Light,
+      ThemeVariant::Light => ThemeVariant::Dark,
+    }
+  }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -42,6 +70,14 @@ pub enum FontVariant {
 
 impl Theme {
   pub fn new(rl: &mut RaylibHandle, thread: &RaylibThread) -> Self {
+    Self::new_with_variant(rl, thread, ThemeVariant::default())
+  }
+
+  pub fn new_with_variant(
+    rl: &mut RaylibHandle,
+    thread: &RaylibThread,
+    variant: ThemeVariant,
+  ) -> Self {
     let primary_font =
       Font::new(rl, thread, "./apps/ide/assets/fonts/FiraMono-Medium.ttf").unwrap();
 
@@ -51,12 +87,17 @@ impl Theme {
     Self {
       font_primary: Some(primary_font),
       font_primary_bold: Some(primary_font_bold),
-      ..Self::no_fonts()
+      ..Self::no_fonts_with_variant(variant)
     }
   }
 
   pub fn no_fonts() -> Self {
-    Self {
+    Self::no_fonts_with_variant(ThemeVariant::default())
+  }
+
+  pub fn no_fonts_with_variant(variant: ThemeVariant) -> Self {
+    let base = Self {
+      variant: ThemeVariant::Dark,
       font_primary: None,
       font_primary_bold: None,
       color_primary: Color::from_hex("16A085").unwrap(),
@@ -81,11 +122,48 @@ impl Theme {
         offset: Vector2::new(2.0, 4.0),
         blur_radius: 4.0,
       },
+      font_scale: 1.0,
+      revision: next_theme_revision(),
+    };
+
+    match variant {
+      ThemeVariant::Dark => base,
+      ThemeVariant::Light => Self {
+        variant: ThemeVariant::Light,
+        color_divider: Color::from_hex("3A3F44").unwrap(),
+        color_background: Color::from_hex("F4F4F4").unwrap(),
+        color_background_contrast: Color::from_hex("212121").unwrap(),
+        color_background_highlight: Color::from_hex("34383C").unwrap(),
+        color_foreground: Color::from_hex("ECF0F1").unwrap(),
+        color_background_secondary: Color::from_hex("5C6670").unwrap(),
+        color_tertiary_contrast: Color::from_hex("FFFFFF").unwrap(),
+        ..base
+      },
     }
   }
 
+  /// Multiplies the size of every font variant. The fonts are signed distance fields, so no glyphs
+  /// have to be regenerated and the next layout picks up the new size.
+  pub fn set_font_scale(&mut self, font_scale: f32) {
+    self.font_scale = font_scale;
+    self.revision = next_theme_revision();
+  }
+
+  /// Differs for every created theme and changes with the font scale. Renderers compare it with
+  /// the one of the previous frame to drop what they cached for the old theme, so colors are
+  /// changed by switching to another theme rather than editing the fields.
+  pub fn revision(&self) -> u64 {
+    self.revision
+  }
+
+  pub fn font_scale(&self) -> f32 {
+    self.font_scale
+  }
+
+  /// Font, color and size of the variant's text, the size has the font scale applied.
   pub fn text_config<'a>(&'a mut self, variant: FontVariant) -> FontConfig<'a> {
-    match variant {
+    let font_scale = self.font_scale;
+    let mut config = match variant {
       FontVariant::Primary => FontConfig {
         font: self.font_primary.as_mut().unwrap(),
         size: 14.0,
@@ -152,7 +230,9 @@ impl Theme {
         color: self.color_secondary,
         underline: false,
       },
-    }
+    };
+    config.size *= font_scale;
+    config
   }
 }
 
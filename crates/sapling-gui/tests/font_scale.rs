@@ -0,0 +1,46 @@
+#![cfg(feature = "offscreen")]
+
+use sapling_gui::prelude::{Color, FontVariant, Theme, Vector2};
+
+fn measure(theme: &mut Theme, variant: FontVariant) -> Vector2 {
+  let config = theme.text_config(variant);
+  config.font.calculate_text_size("Hello world", config.size)
+}
+
+fn assert_scaled(scaled: Vector2, base: Vector2, scale: f32) {
+  assert!(
+    (scaled.x - base.x * scale).abs() < 0.01,
+    "{scaled:?} vs {base:?}"
+  );
+  assert!(
+    (scaled.y - base.y * scale).abs() < 0.01,
+    "{scaled:?} vs {base:?}"
+  );
+}
+
+#[test]
+fn test_font_scale_scales_measured_text() {
+  let (mut handle, thread) = raylib::init().size(64, 64).title("offscreen").build();
+  // The theme loads its fonts relative to the workspace root
+  std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/../..")).unwrap();
+  let mut theme = Theme::new(&mut handle, &thread);
+  let custom = FontVariant::Custom {
+    color: Color::RED,
+    size: 10.0,
+  };
+
+  let primary = measure(&mut theme, FontVariant::Primary);
+  let subject = measure(&mut theme, FontVariant::EditorSubject);
+  let custom_size = measure(&mut theme, custom);
+
+  theme.set_font_scale(2.0);
+
+  assert_scaled(measure(&mut theme, FontVariant::Primary), primary, 2.0);
+  assert_scaled(
+    measure(&mut theme, FontVariant::EditorSubject),
+    subject,
+    2.0,
+  );
+  assert_scaled(measure(&mut theme, custom), custom_size, 2.0);
+  assert_eq!(theme.text_config(custom).size, 20.0);
+}
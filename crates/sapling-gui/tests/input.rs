@@ -0,0 +1,62 @@
+use sapling_gui::prelude::{
+  ActionMap, InputState, KeyModifiers, KeyboardKey, Vector2, action_hash,
+};
+
+#[derive(Hash)]
+enum Action {
+  SelectLeft,
+  ExtendSelectionLeft,
+}
+
+#[test]
+fn test_modifier_gated_action() {
+  let mut action_map = ActionMap::new();
+  action_map.add_action(Action::SelectLeft, KeyboardKey::KEY_LEFT);
+  action_map.add_action_with_modifiers(
+    Action::ExtendSelectionLeft,
+    KeyboardKey::KEY_LEFT,
+    KeyModifiers::SHIFT,
+  );
+
+  let input_state = InputState::default()
+    .with_modifiers(KeyModifiers::SHIFT)
+    .with_pressed_keys(action_map.clone(), &[KeyboardKey::KEY_LEFT]);
+  assert!(input_state.shift_down);
+  assert!(!input_state.ctrl_down);
+  assert!(input_state.is_action_pressed(action_hash(Action::ExtendSelectionLeft)));
+  assert!(!input_state.is_action_pressed(action_hash(Action::SelectLeft)));
+
+  let input_state = InputState::default().with_pressed_keys(action_map, &[KeyboardKey::KEY_LEFT]);
+  assert!(input_state.is_action_pressed(action_hash(Action::SelectLeft)));
+  assert!(!input_state.is_action_pressed(action_hash(Action::ExtendSelectionLeft)));
+}
+
+#[test]
+fn test_scroll_delta() {
+  assert_eq!(InputState::default().scroll_delta, Vector2::new(0.0, 0.0));
+
+  let input_state = InputState::default().with_scroll_delta(Vector2::new(-2.0, 3.5));
+  assert_eq!(input_state.scroll_delta, Vector2::new(-2.0, 3.5));
+}
+
+#[test]
+fn test_shift_scroll() {
+  let vertical = Vector2::new(0.0, 2.0);
+  let diagonal = Vector2::new(1.0, 2.0);
+
+  let input_state = InputState::default();
+  assert_eq!(input_state.shift_scroll(vertical), vertical);
+
+  let input_state = InputState::default().with_modifiers(KeyModifiers::SHIFT);
+  assert_eq!(input_state.shift_scroll(vertical), Vector2::new(2.0, 0.0));
+  // Wheels reporting horizontal movement themselves are left alone
+  assert_eq!(input_state.shift_scroll(diagonal), diagonal);
+}
+
+#[test]
+fn test_typed_text() {
+  assert_eq!(InputState::default().typed_text, "");
+
+  let input_state = InputState::default().with_typed_text("Hä1");
+  assert_eq!(input_state.typed_text, "Hä1");
+}
@@ -1,8 +1,11 @@
 use std::collections::HashMap;
 
 use sapling_gui::{
-  ConstraintResolver, ElementVariable, RelationshipMeta,
-  prelude::{CompiledConstraint, ConstraintVariable, Dimension},
+  ConstraintResolver, ElementVariable, LayoutCycleError, RelationshipMeta,
+  prelude::{
+    CompiledConstraint, ConstraintVariable, Dimension, Element, ElementExpr, Flex, ResolvedLayout,
+    UserElementConstraints,
+  },
 };
 
 fn create_relationship_meta(parent_map: Vec<Option<usize>>) -> Vec<RelationshipMeta> {
@@ -69,7 +72,7 @@ fn test_simple_layout() {
     create_relationship_meta(parent_map),
     (1.0, 1.0),
   );
-  resolver.resolve();
+  resolver.resolve().unwrap();
 
   assert_eq!(
     resolver.get_element_variable_resolution(root, ElementVariable::Width),
@@ -128,7 +131,7 @@ fn test_child_parent_sizing() {
     create_relationship_meta(parent_map),
     (1.0, 1.0),
   );
-  resolver.resolve();
+  resolver.resolve().unwrap();
 
   assert_eq!(
     resolver.get_element_variable_resolution(root, ElementVariable::Height),
@@ -197,7 +200,7 @@ fn test_child_parent_only_if_needed() {
     create_relationship_meta(parent_map),
     (1.0, 1.0),
   );
-  resolver.resolve();
+  resolver.resolve().unwrap();
 
   assert_eq!(
     resolver.get_element_variable_resolution(root, ElementVariable::Height),
@@ -266,7 +269,7 @@ fn test_child_parent_only_if_needed_reverse_order() {
     create_relationship_meta(parent_map),
     (1.0, 1.0),
   );
-  resolver.resolve();
+  resolver.resolve().unwrap();
 
   assert_eq!(
     resolver.get_element_variable_resolution(root, ElementVariable::Height),
@@ -285,3 +288,598 @@ fn test_child_parent_only_if_needed_reverse_order() {
     1.0
   );
 }
+
+#[test]
+fn test_distribute_horizontal() {
+  let root = 0;
+  let fixed_a = 1;
+  let grow = 2;
+  let fixed_b = 3;
+  let parent_map = vec![None, Some(0), Some(0), Some(0)];
+  let mut constraints = vec![
+    (
+      root,
+      CompiledConstraint::ForcedConstAssignment {
+        variable: ConstraintVariable::SelfWidth,
+        constant: 300.0,
+      },
+    ),
+    (
+      root,
+      CompiledConstraint::ForcedConstAssignment {
+        variable: ConstraintVariable::SelfX,
+        constant: 0.0,
+      },
+    ),
+    (
+      fixed_a,
+      CompiledConstraint::ForcedConstAssignment {
+        variable: ConstraintVariable::SelfWidth,
+        constant: 50.0,
+      },
+    ),
+    (
+      fixed_b,
+      CompiledConstraint::ForcedConstAssignment {
+        variable: ConstraintVariable::SelfWidth,
+        constant: 70.0,
+      },
+    ),
+  ];
+  for (element, element_constraints) in UserElementConstraints::distribute_horizontal(
+    &[
+      (Element { id: fixed_a }, Flex::Fixed),
+      (Element { id: grow }, Flex::Grow(1.0)),
+      (Element { id: fixed_b }, Flex::Fixed),
+    ],
+    Element { id: root },
+    10.0,
+  ) {
+    constraints.extend(
+      element_constraints
+        .constraints
+        .into_iter()
+        .map(|constraint| (element.id, constraint)),
+    );
+  }
+
+  let mut resolver = ConstraintResolver::new(
+    constraints,
+    create_relationship_meta(parent_map),
+    (1.0, 1.0),
+  );
+  resolver.resolve().unwrap();
+
+  let width = |id| resolver.get_element_variable_resolution(id, ElementVariable::Width);
+  let x = |id| resolver.get_element_variable_resolution(id, ElementVariable::X);
+
+  assert_eq!(width(grow), 160.0);
+  assert_eq!(x(fixed_a), 0.0);
+  assert_eq!(x(grow), 60.0);
+  assert_eq!(x(fixed_b), 230.0);
+  assert_eq!(
+    width(fixed_a) + width(grow) + width(fixed_b) + 2.0 * 10.0,
+    width(root)
+  );
+  assert_eq!(x(fixed_b) + width(fixed_b), width(root));
+}
+
+#[test]
+fn test_cycle_is_reported() {
+  let root = 0;
+  let parent_map = vec![None];
+  let constraints = vec![
+    (
+      root,
+      CompiledConstraint::ForcedVariableAssignment {
+        target_variable: ConstraintVariable::SelfWidth,
+        source_variable: ConstraintVariable::SelfHeight,
+        constant_offset: 0.0,
+      },
+    ),
+    (
+      root,
+      CompiledConstraint::ForcedVariableAssignment {
+        target_variable: ConstraintVariable::SelfHeight,
+        source_variable: ConstraintVariable::SelfWidth,
+        constant_offset: 0.0,
+      },
+    ),
+  ];
+  let mut resolver = ConstraintResolver::new(
+    constraints,
+    create_relationship_meta(parent_map),
+    (1.0, 1.0),
+  );
+
+  let LayoutCycleError {
+    constraint_id,
+    element_id,
+    dot,
+    ..
+  } = resolver.validate().unwrap_err();
+  assert!(constraint_id < 2);
+  assert_eq!(element_id, root);
+  assert!(dot.contains("digraph"));
+
+  assert!(resolver.resolve().is_err());
+}
+
+#[test]
+fn test_clamp_applies_after_assignment() {
+  let root = 0;
+  let child_a = 1;
+  let child_b = 2;
+  let parent_map = vec![None, Some(0), Some(0)];
+  let constraints = vec![
+    // Listed before the assignment on purpose, the clamp still has to run after it
+    (
+      root,
+      CompiledConstraint::ClampMax {
+        variable: ConstraintVariable::SelfWidth,
+        constant: 100.0,
+      },
+    ),
+    (
+      root,
+      CompiledConstraint::ForcedConstAssignment {
+        variable: ConstraintVariable::SelfWidth,
+        constant: 128.0,
+      },
+    ),
+    (
+      child_a,
+      CompiledConstraint::ForcedVariableAssignment {
+        target_variable: ConstraintVariable::SelfWidth,
+        source_variable: ConstraintVariable::ParentWidth,
+        constant_offset: -5.0,
+      },
+    ),
+    (
+      child_b,
+      CompiledConstraint::ForcedVariableAssignment {
+        target_variable: ConstraintVariable::SelfWidth,
+        source_variable: ConstraintVariable::ParentWidth,
+        constant_offset: -10.0,
+      },
+    ),
+    (
+      child_b,
+      CompiledConstraint::ClampMin {
+        variable: ConstraintVariable::SelfWidth,
+        constant: 120.0,
+      },
+    ),
+  ];
+  let mut resolver = ConstraintResolver::new(
+    constraints,
+    create_relationship_meta(parent_map),
+    (1.0, 1.0),
+  );
+  resolver.resolve().unwrap();
+
+  assert_eq!(
+    resolver.get_element_variable_resolution(root, ElementVariable::Width),
+    100.0
+  );
+  assert_eq!(
+    resolver.get_element_variable_resolution(child_a, ElementVariable::Width),
+    95.0
+  );
+  assert_eq!(
+    resolver.get_element_variable_resolution(child_b, ElementVariable::Width),
+    120.0
+  );
+}
+
+#[test]
+fn test_scaled_variable_assignment() {
+  let root = 0;
+  let child = 1;
+  let parent_map = vec![None, Some(0)];
+  let constraints = vec![
+    (
+      root,
+      CompiledConstraint::ForcedConstAssignment {
+        variable: ConstraintVariable::SelfWidth,
+        constant: 128.0,
+      },
+    ),
+    (
+      child,
+      CompiledConstraint::ForcedVariableAssignmentScaled {
+        target_variable: ConstraintVariable::SelfWidth,
+        source_variable: ConstraintVariable::ParentWidth,
+        multiplier: 0.5,
+        constant_offset: 4.0,
+      },
+    ),
+  ];
+  let mut resolver = ConstraintResolver::new(
+    constraints,
+    create_relationship_meta(parent_map),
+    (1.0, 1.0),
+  );
+  resolver.resolve().unwrap();
+
+  assert_eq!(
+    resolver.get_element_variable_resolution(child, ElementVariable::Width),
+    68.0
+  );
+}
+
+#[test]
+fn test_element_layout_matches_single_variables() {
+  let root = 0;
+  let child = 1;
+  let parent_map = vec![None, Some(0)];
+  let constraints = vec![
+    (
+      root,
+      CompiledConstraint::ForcedConstAssignment {
+        variable: ConstraintVariable::SelfWidth,
+        constant: 128.0,
+      },
+    ),
+    (
+      root,
+      CompiledConstraint::ForcedConstAssignment {
+        variable: ConstraintVariable::SelfHeight,
+        constant: 64.0,
+      },
+    ),
+    (
+      child,
+      CompiledConstraint::ForcedConstAssignment {
+        variable: ConstraintVariable::SelfX,
+        constant: 12.0,
+      },
+    ),
+    (
+      child,
+      CompiledConstraint::ForcedConstAssignment {
+        variable: ConstraintVariable::SelfY,
+        constant: 8.0,
+      },
+    ),
+    (
+      child,
+      CompiledConstraint::ForcedVariableAssignment {
+        target_variable: ConstraintVariable::SelfWidth,
+        source_variable: ConstraintVariable::ParentWidth,
+        constant_offset: -24.0,
+      },
+    ),
+    (
+      child,
+      CompiledConstraint::ForcedVariableAssignment {
+        target_variable: ConstraintVariable::SelfHeight,
+        source_variable: ConstraintVariable::ParentHeight,
+        constant_offset: -16.0,
+      },
+    ),
+  ];
+  let mut resolver = ConstraintResolver::new(
+    constraints,
+    create_relationship_meta(parent_map),
+    (1.0, 1.0),
+  );
+  resolver.resolve().unwrap();
+
+  for element_id in [root, child] {
+    let layout = resolver.get_element_layout(element_id);
+    assert_eq!(
+      layout,
+      ResolvedLayout {
+        x: resolver.get_element_variable_resolution(element_id, ElementVariable::X),
+        y: resolver.get_element_variable_resolution(element_id, ElementVariable::Y),
+        width: resolver.get_element_variable_resolution(element_id, ElementVariable::Width),
+        height: resolver.get_element_variable_resolution(element_id, ElementVariable::Height),
+      }
+    );
+  }
+  assert_eq!(
+    resolver.get_element_layout(child),
+    ResolvedLayout {
+      x: 12.0,
+      y: 8.0,
+      width: 104.0,
+      height: 48.0,
+    }
+  );
+}
+
+#[test]
+fn test_center_in_parent() {
+  let root = 0;
+  let child = 1;
+  let parent_map = vec![None, Some(0)];
+  let mut constraints = Vec::new();
+  for (element_id, element_constraints) in [
+    (
+      root,
+      UserElementConstraints::absolute_position(10.0, 20.0)
+        .merged(&UserElementConstraints::fixed_size(200.0, 100.0)),
+    ),
+    (
+      child,
+      UserElementConstraints::fixed_size(50.0, 30.0)
+        .merged(&UserElementConstraints::center_in_parent()),
+    ),
+  ] {
+    constraints.extend(
+      element_constraints
+        .constraints
+        .into_iter()
+        .map(|constraint| (element_id, constraint)),
+    );
+  }
+  let mut resolver = ConstraintResolver::new(
+    constraints,
+    create_relationship_meta(parent_map),
+    (1.0, 1.0),
+  );
+  resolver.resolve().unwrap();
+
+  assert_eq!(
+    resolver.get_element_layout(child),
+    ResolvedLayout {
+      x: 85.0,
+      y: 55.0,
+      width: 50.0,
+      height: 30.0,
+    }
+  );
+}
+
+#[test]
+fn test_aspect_ratio() {
+  let constraints = UserElementConstraints::aspect_ratio(16.0 / 9.0).constraints;
+  assert_eq!(constraints.len(), 1);
+  match constraints[0] {
+    CompiledConstraint::ForcedVariableAssignmentScaled {
+      target_variable,
+      source_variable,
+      multiplier,
+      constant_offset,
+    } => {
+      assert_eq!(target_variable, ConstraintVariable::SelfWidth);
+      assert_eq!(source_variable, ConstraintVariable::SelfHeight);
+      assert_eq!(multiplier, 16.0 / 9.0);
+      assert_eq!(constant_offset, 0.0);
+    }
+    _ => panic!("Expected ForcedVariableAssignmentScaled"),
+  }
+
+  let root = 0;
+  let wide = 1;
+  let tall = 2;
+  let parent_map = vec![None, Some(0), Some(0)];
+  let mut constraints = Vec::new();
+  for (element_id, element_constraints) in [
+    (
+      root,
+      UserElementConstraints::fixed_height(90.0).merged(&UserElementConstraints::aspect_ratio(2.0)),
+    ),
+    (
+      wide,
+      UserElementConstraints::fixed_aspect_within(400.0, 90.0, 16.0 / 9.0),
+    ),
+    (
+      tall,
+      UserElementConstraints::fixed_aspect_within(160.0, 400.0, 16.0 / 9.0),
+    ),
+  ] {
+    constraints.extend(
+      element_constraints
+        .constraints
+        .into_iter()
+        .map(|constraint| (element_id, constraint)),
+    );
+  }
+  let mut resolver = ConstraintResolver::new(
+    constraints,
+    create_relationship_meta(parent_map),
+    (1.0, 1.0),
+  );
+  resolver.resolve().unwrap();
+
+  assert_eq!(
+    resolver.get_element_variable_resolution(root, ElementVariable::Width),
+    180.0
+  );
+  let wide_layout = resolver.get_element_layout(wide);
+  assert_eq!((wide_layout.width, wide_layout.height), (160.0, 90.0));
+  let tall_layout = resolver.get_element_layout(tall);
+  assert_eq!((tall_layout.width, tall_layout.height), (160.0, 90.0));
+}
+
+#[test]
+fn test_element_edge_and_center_expressions() {
+  let anchor = Element { id: 1 };
+  assert_eq!(
+    anchor.bottom(),
+    ElementExpr {
+      terms: vec![(anchor.y(), 1.0), (anchor.height(), 1.0)],
+    }
+  );
+  assert_eq!(
+    anchor.center_y(),
+    ElementExpr {
+      terms: vec![(anchor.y(), 1.0), (anchor.height(), 0.5)],
+    }
+  );
+
+  let parent_map = vec![None, Some(0), Some(0), Some(0)];
+  let mut constraints = Vec::new();
+  for (element_id, element_constraints) in [
+    (0, UserElementConstraints::fixed_size(200.0, 100.0)),
+    (
+      1,
+      UserElementConstraints::absolute_position(10.0, 0.0)
+        .merged(&UserElementConstraints::fixed_size(50.0, 20.0)),
+    ),
+  ] {
+    constraints.extend(
+      element_constraints
+        .constraints
+        .into_iter()
+        .map(|constraint| (element_id, constraint)),
+    );
+  }
+  // The second element starts 8 right of the anchor, the third at its center
+  constraints.push((2, anchor.right().assign_to(ConstraintVariable::SelfX, 8.0)));
+  constraints.push((
+    3,
+    anchor.center_x().assign_to(ConstraintVariable::SelfX, 0.0),
+  ));
+  let mut resolver = ConstraintResolver::new(
+    constraints,
+    create_relationship_meta(parent_map),
+    (1.0, 1.0),
+  );
+  resolver.resolve().unwrap();
+
+  assert_eq!(
+    resolver.get_element_variable_resolution(2, ElementVariable::X),
+    68.0
+  );
+  assert_eq!(
+    resolver.get_element_variable_resolution(3, ElementVariable::X),
+    35.0
+  );
+}
+
+#[test]
+fn test_snapping_keeps_shared_edges() {
+  let parent_map = vec![None, Some(0), Some(0)];
+  let mut constraints = Vec::new();
+  for (element_id, element_constraints) in [
+    (
+      0,
+      UserElementConstraints::absolute_position(0.0, 0.0)
+        .merged(&UserElementConstraints::fixed_size(100.0, 50.0)),
+    ),
+    (
+      1,
+      UserElementConstraints::absolute_position(10.3, 4.6)
+        .merged(&UserElementConstraints::fixed_size(20.4, 10.2)),
+    ),
+  ] {
+    constraints.extend(
+      element_constraints
+        .constraints
+        .into_iter()
+        .map(|constraint| (element_id, constraint)),
+    );
+  }
+  constraints.push((
+    2,
+    Element { id: 1 }
+      .right()
+      .assign_to(ConstraintVariable::SelfX, 0.0),
+  ));
+  constraints.push((
+    2,
+    CompiledConstraint::ForcedConstAssignment {
+      variable: ConstraintVariable::SelfWidth,
+      constant: 20.4,
+    },
+  ));
+
+  let mut resolver = ConstraintResolver::new(
+    constraints,
+    create_relationship_meta(parent_map),
+    (1.0, 1.0),
+  )
+  .with_snap(1.0);
+  resolver.resolve().unwrap();
+
+  // The right edge at 30.7 rounds to 31, the width follows from the rounded edges
+  assert_eq!(
+    resolver.get_element_layout(1),
+    ResolvedLayout {
+      x: 10.0,
+      y: 5.0,
+      width: 21.0,
+      height: 10.0,
+    }
+  );
+  let next = resolver.get_element_layout(2);
+  assert_eq!((next.x, next.width), (31.0, 20.0));
+
+  let mut resolver = ConstraintResolver::new(
+    vec![(
+      0,
+      CompiledConstraint::ForcedConstAssignment {
+        variable: ConstraintVariable::SelfX,
+        constant: 3.7,
+      },
+    )],
+    create_relationship_meta(vec![None]),
+    (1.0, 1.0),
+  )
+  .with_snap(0.5);
+  resolver.resolve().unwrap();
+  assert_eq!(
+    resolver.get_element_variable_resolution(0, ElementVariable::X),
+    3.5
+  );
+}
+
+#[test]
+fn test_assume_parent_size() {
+  let root = 0;
+  let child = 1;
+  let parent_map = vec![None, Some(0)];
+  let constraints = vec![
+    (
+      root,
+      CompiledConstraint::ForcedConstAssignment {
+        variable: ConstraintVariable::SelfWidth,
+        constant: 200.0,
+      },
+    ),
+    // The root has no parent and takes the window height
+    (
+      root,
+      CompiledConstraint::TryAssumeParentSize {
+        dimension: Dimension::Height,
+        constant_offset: 0.0,
+      },
+    ),
+    (
+      child,
+      CompiledConstraint::TryAssumeParentSize {
+        dimension: Dimension::Width,
+        constant_offset: 20.0,
+      },
+    ),
+    (
+      child,
+      CompiledConstraint::TryAssumeParentSize {
+        dimension: Dimension::Height,
+        constant_offset: 10.0,
+      },
+    ),
+  ];
+  let mut resolver = ConstraintResolver::new(
+    constraints,
+    create_relationship_meta(parent_map),
+    (640.0, 480.0),
+  );
+  resolver.resolve().unwrap();
+
+  assert_eq!(
+    resolver.get_element_variable_resolution(root, ElementVariable::Height),
+    480.0
+  );
+  assert_eq!(
+    resolver.get_element_variable_resolution(child, ElementVariable::Width),
+    180.0
+  );
+  assert_eq!(
+    resolver.get_element_variable_resolution(child, ElementVariable::Height),
+    470.0
+  );
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "offscreen")]
+
+use sapling_gui::{
+  RaylibRendererState,
+  prelude::{Color, Rectangle, Renderer, Vector4},
+};
+
+#[test]
+fn test_solid_rectangle_is_read_back() {
+  let (mut handle, thread) = raylib::init().size(64, 64).title("offscreen").build();
+  let mut state = RaylibRendererState::new(&mut handle, &thread);
+
+  let image = state.render_to_image(&mut handle, &thread, 32, 16, |renderer| {
+    renderer.draw_rectangle(
+      Rectangle::new(0.0, 0.0, 32.0, 16.0),
+      Vector4::new(0.0, 0.0, 0.0, 0.0),
+      Color::RED,
+    );
+  });
+
+  assert_eq!((image.width(), image.height()), (32, 16));
+  assert_eq!(image.pixels().len(), 32 * 16 * 4);
+  assert_eq!(image.pixel(16, 8), Color::RED);
+}
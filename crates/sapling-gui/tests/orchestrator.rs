@@ -0,0 +1,1006 @@
+use std::{
+  cell::{Cell, RefCell},
+  rc::Rc,
+  time::Duration,
+};
+
+use sapling_app::App;
+use sapling_gui::{
+  NoopRenderer,
+  prelude::{
+    Component, ComponentElement, Easing, Element, ElementContext, GridView, InputState, LayoutView,
+    MutableState, Orchestrator, PointerEvent, PointerEventKind, RenderContext, ResolvedLayout,
+    ScrollView, Theme, UserElementConstraints, VStack, Vector2,
+  },
+};
+
+#[derive(Debug)]
+struct CountingView {
+  initializations: Rc<Cell<usize>>,
+}
+
+impl Component for CountingView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    let initializations = self.initializations.clone();
+    let (_, _state) = MutableState::new(
+      context,
+      move || {
+        initializations.set(initializations.get() + 1);
+        0usize
+      },
+      "counter",
+    );
+  }
+}
+
+fn render_frame(orchestrator: &mut Orchestrator, app: &mut App, initializations: &Rc<Cell<usize>>) {
+  orchestrator.construct_and_render(
+    CountingView {
+      initializations: initializations.clone(),
+    },
+    100.0,
+    100.0,
+    &mut NoopRenderer::default(),
+    &mut Theme::no_fonts(),
+    app,
+    &InputState::default(),
+  );
+}
+
+#[test]
+fn test_clear_state_reinitializes() {
+  let mut app = App::new(16);
+  let mut orchestrator = Orchestrator::new(false);
+  let initializations = Rc::new(Cell::new(0));
+
+  render_frame(&mut orchestrator, &mut app, &initializations);
+  render_frame(&mut orchestrator, &mut app, &initializations);
+  assert_eq!(initializations.get(), 1);
+
+  orchestrator.clear_state();
+  render_frame(&mut orchestrator, &mut app, &initializations);
+  assert_eq!(initializations.get(), 2);
+}
+
+#[derive(Debug)]
+struct CountingBranch {
+  initializations: Rc<Cell<usize>>,
+}
+
+impl Component for CountingBranch {
+  fn construct(&mut self, context: &mut ElementContext) {
+    let initializations = self.initializations.clone();
+    let (_, _state) = MutableState::new(
+      context,
+      move || {
+        initializations.set(initializations.get() + 1);
+        0usize
+      },
+      "branch_counter",
+    );
+    CountingView {
+      initializations: self.initializations.clone(),
+    }
+    .build(context);
+  }
+}
+
+#[derive(Debug)]
+struct CountingTree {
+  cleared: Rc<Cell<usize>>,
+  kept: Rc<Cell<usize>>,
+  cleared_branch: Rc<Cell<Option<Element>>>,
+}
+
+impl Component for CountingTree {
+  fn construct(&mut self, context: &mut ElementContext) {
+    let branch = CountingBranch {
+      initializations: self.cleared.clone(),
+    }
+    .build(context);
+    self.cleared_branch.set(Some(branch));
+    CountingBranch {
+      initializations: self.kept.clone(),
+    }
+    .build(context);
+  }
+}
+
+#[test]
+fn test_clear_state_for_subtree() {
+  let mut app = App::new(16);
+  let mut orchestrator = Orchestrator::new(false);
+  let cleared = Rc::new(Cell::new(0));
+  let kept = Rc::new(Cell::new(0));
+  let cleared_branch = Rc::new(Cell::new(None));
+
+  let mut render = |orchestrator: &mut Orchestrator| {
+    orchestrator.construct_and_render(
+      CountingTree {
+        cleared: cleared.clone(),
+        kept: kept.clone(),
+        cleared_branch: cleared_branch.clone(),
+      },
+      100.0,
+      100.0,
+      &mut NoopRenderer::default(),
+      &mut Theme::no_fonts(),
+      &mut app,
+      &InputState::default(),
+    );
+  };
+
+  render(&mut orchestrator);
+  render(&mut orchestrator);
+  assert_eq!((cleared.get(), kept.get()), (2, 2));
+
+  // Clears the state of the branch and of its child, not of the sibling branch
+  orchestrator.clear_state_for_element(cleared_branch.get().unwrap());
+  render(&mut orchestrator);
+  assert_eq!((cleared.get(), kept.get()), (4, 2));
+}
+
+#[test]
+fn test_clear_state_for_key_prefix() {
+  let mut app = App::new(16);
+  let mut orchestrator = Orchestrator::new(false);
+  let initializations = Rc::new(Cell::new(0));
+
+  let mut render = |orchestrator: &mut Orchestrator| {
+    orchestrator.construct_and_render(
+      CountingBranch {
+        initializations: initializations.clone(),
+      },
+      100.0,
+      100.0,
+      &mut NoopRenderer::default(),
+      &mut Theme::no_fonts(),
+      &mut app,
+      &InputState::default(),
+    );
+  };
+
+  render(&mut orchestrator);
+  assert_eq!(initializations.get(), 2);
+
+  // Only the branch's "branch_counter" matches, the child's "counter" state is kept
+  orchestrator.clear_state_for("branch_");
+  render(&mut orchestrator);
+  assert_eq!(initializations.get(), 3);
+}
+
+#[derive(Debug)]
+struct NestedLayoutView {
+  depth: usize,
+}
+
+fn build_nested_layout_views(context: &mut ElementContext, depth: usize) {
+  if depth == 0 {
+    return;
+  }
+
+  LayoutView
+    .with_children(move |context| build_nested_layout_views(context, depth - 1))
+    .build(context);
+}
+
+impl Component for NestedLayoutView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    build_nested_layout_views(context, self.depth);
+  }
+}
+
+#[test]
+fn test_layout_only_components_are_allocated() {
+  let mut app = App::new(16);
+  let mut orchestrator = Orchestrator::new(false);
+
+  let stats = orchestrator.construct_and_render(
+    NestedLayoutView { depth: 200 },
+    100.0,
+    100.0,
+    &mut NoopRenderer::default(),
+    &mut Theme::no_fonts(),
+    &mut app,
+    &InputState::default(),
+  );
+
+  // root + nested layout views
+  assert_eq!(stats.element_count, 201);
+}
+
+#[derive(Debug)]
+struct LayoutRecorder {
+  layouts: Rc<RefCell<Vec<ResolvedLayout>>>,
+}
+
+impl Component for LayoutRecorder {
+  fn render(&self, context: &mut RenderContext) {
+    self.layouts.borrow_mut().push(context.layout.clone());
+  }
+}
+
+#[derive(Debug)]
+struct MovingView {
+  x: Option<f32>,
+  layouts: Rc<RefCell<Vec<ResolvedLayout>>>,
+}
+
+impl Component for MovingView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    if let Some(x) = self.x {
+      LayoutRecorder {
+        layouts: self.layouts.clone(),
+      }
+      .with_layout(vec![
+        UserElementConstraints::absolute_position(x, 0.0),
+        UserElementConstraints::fixed_size(10.0, 10.0),
+      ])
+      .build(context);
+    }
+  }
+}
+
+#[test]
+fn test_layout_animation_interpolates() {
+  let mut app = App::new(16);
+  let mut orchestrator = Orchestrator::new(false)
+    .with_animations(Duration::from_millis(400), Easing::Linear)
+    .with_fixed_frame_time(Duration::from_millis(100));
+  let layouts = Rc::new(RefCell::new(Vec::new()));
+
+  let mut render = |x: Option<f32>| {
+    orchestrator.construct_and_render(
+      MovingView {
+        x,
+        layouts: layouts.clone(),
+      },
+      200.0,
+      200.0,
+      &mut NoopRenderer::default(),
+      &mut Theme::no_fonts(),
+      &mut app,
+      &InputState::default(),
+    );
+  };
+
+  render(Some(0.0));
+  for _ in 0..5 {
+    render(Some(100.0));
+  }
+  // Removing the element drops its animation, it reappears at its target without a transition
+  render(None);
+  render(Some(0.0));
+
+  let xs = layouts
+    .borrow()
+    .iter()
+    .map(|layout| layout.x)
+    .collect::<Vec<_>>();
+  let expected = [0.0, 25.0, 50.0, 75.0, 100.0, 100.0, 0.0];
+  assert_eq!(xs.len(), expected.len());
+  for (x, expected) in xs.iter().zip(expected) {
+    assert!((x - expected).abs() < 0.01, "{:?} != {:?}", xs, expected);
+  }
+  assert!(
+    layouts
+      .borrow()
+      .iter()
+      .all(|layout| layout.width == 10.0 && layout.height == 10.0)
+  );
+}
+
+#[derive(Debug)]
+struct TwinMovingViews {
+  x: f32,
+  moving: Rc<RefCell<Vec<ResolvedLayout>>>,
+  resting: Rc<RefCell<Vec<ResolvedLayout>>>,
+}
+
+impl Component for TwinMovingViews {
+  fn construct(&mut self, context: &mut ElementContext) {
+    // Both recorders share their key and their parent's key, only their grandparents differ
+    for (x, layouts) in [(self.x, self.moving.clone()), (0.0, self.resting.clone())] {
+      LayoutView
+        .with_children(move |context| {
+          MovingView {
+            x: Some(x),
+            layouts,
+          }
+          .build(context);
+        })
+        .build(context);
+    }
+  }
+}
+
+#[test]
+fn test_layout_animation_of_identical_subtrees() {
+  let mut app = App::new(16);
+  let mut orchestrator = Orchestrator::new(false)
+    .with_animations(Duration::from_millis(400), Easing::Linear)
+    .with_fixed_frame_time(Duration::from_millis(100));
+  let moving = Rc::new(RefCell::new(Vec::new()));
+  let resting = Rc::new(RefCell::new(Vec::new()));
+
+  for x in [0.0, 100.0, 100.0] {
+    orchestrator.construct_and_render(
+      TwinMovingViews {
+        x,
+        moving: moving.clone(),
+        resting: resting.clone(),
+      },
+      200.0,
+      200.0,
+      &mut NoopRenderer::default(),
+      &mut Theme::no_fonts(),
+      &mut app,
+      &InputState::default(),
+    );
+  }
+
+  let xs = |layouts: &Rc<RefCell<Vec<ResolvedLayout>>>| {
+    layouts
+      .borrow()
+      .iter()
+      .map(|layout| layout.x)
+      .collect::<Vec<_>>()
+  };
+  let moving_xs = xs(&moving);
+  let expected = [0.0, 25.0, 50.0];
+  assert_eq!(moving_xs.len(), expected.len());
+  for (x, expected) in moving_xs.iter().zip(expected) {
+    assert!(
+      (x - expected).abs() < 0.01,
+      "{:?} != {:?}",
+      moving_xs,
+      expected
+    );
+  }
+  assert_eq!(xs(&resting), vec![0.0, 0.0, 0.0]);
+}
+
+type PointerLog = Rc<RefCell<Vec<(&'static str, Vector2)>>>;
+
+#[derive(Debug)]
+struct PointerRecorder {
+  name: &'static str,
+  handles: bool,
+  log: PointerLog,
+}
+
+impl Component for PointerRecorder {
+  fn on_pointer(&mut self, event: PointerEvent, _context: &mut ElementContext) -> bool {
+    self.log.borrow_mut().push((self.name, event.position));
+    self.handles
+  }
+}
+
+#[derive(Debug)]
+struct PointerView {
+  log: PointerLog,
+}
+
+impl Component for PointerView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    let log = self.log.clone();
+    PointerRecorder {
+      name: "outer",
+      handles: true,
+      log: self.log.clone(),
+    }
+    .with_layout(vec![
+      UserElementConstraints::absolute_position(10.0, 10.0),
+      UserElementConstraints::fixed_size(50.0, 50.0),
+    ])
+    .with_children(move |context| {
+      PointerRecorder {
+        name: "inner",
+        handles: false,
+        log,
+      }
+      .with_layout(vec![
+        UserElementConstraints::relative_to_parent(10.0, 10.0),
+        UserElementConstraints::fixed_size(10.0, 10.0),
+      ])
+      .build(context);
+    })
+    .build(context);
+  }
+
+  fn on_pointer(&mut self, event: PointerEvent, _context: &mut ElementContext) -> bool {
+    self.log.borrow_mut().push(("root", event.position));
+    false
+  }
+}
+
+#[test]
+fn test_pointer_events_bubble_to_parents() {
+  let mut app = App::new(16);
+  let mut orchestrator = Orchestrator::new(false);
+  let log = PointerLog::default();
+
+  let input_state = InputState::default().with_pointer_events(vec![
+    PointerEvent::new(PointerEventKind::Up, Vector2::new(25.0, 25.0)),
+    PointerEvent::new(PointerEventKind::Down, Vector2::new(50.0, 50.0)),
+    PointerEvent::new(PointerEventKind::Move, Vector2::new(80.0, 80.0)),
+  ]);
+  orchestrator.construct_and_render(
+    PointerView { log: log.clone() },
+    100.0,
+    100.0,
+    &mut NoopRenderer::default(),
+    &mut Theme::no_fonts(),
+    &mut app,
+    &input_state,
+  );
+
+  assert_eq!(
+    *log.borrow(),
+    vec![
+      // Inner does not handle the event, outer does and stops the bubbling
+      ("inner", Vector2::new(5.0, 5.0)),
+      ("outer", Vector2::new(15.0, 15.0)),
+      // Outside of inner
+      ("outer", Vector2::new(40.0, 40.0)),
+      // Only the root is hit
+      ("root", Vector2::new(80.0, 80.0)),
+    ]
+  );
+}
+
+#[derive(Debug)]
+struct ClippedPointerView {
+  log: PointerLog,
+}
+
+impl Component for ClippedPointerView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    let log = self.log.clone();
+    ScrollView::new()
+      .with_content(move |context| {
+        // The second item lies below the viewport of the scroll view
+        for (name, y) in [("visible", 0.0), ("clipped", 150.0)] {
+          PointerRecorder {
+            name,
+            handles: true,
+            log: log.clone(),
+          }
+          .with_layout(vec![
+            UserElementConstraints::relative_to_parent(0.0, y),
+            UserElementConstraints::fixed_size(100.0, 50.0),
+          ])
+          .build(context);
+        }
+      })
+      .with_layout(vec![
+        UserElementConstraints::absolute_position(0.0, 0.0),
+        UserElementConstraints::fixed_size(100.0, 100.0),
+      ])
+      .build(context);
+  }
+
+  fn on_pointer(&mut self, event: PointerEvent, _context: &mut ElementContext) -> bool {
+    self.log.borrow_mut().push(("root", event.position));
+    true
+  }
+}
+
+#[test]
+fn test_pointer_events_skip_clipped_elements() {
+  let mut app = App::new(16);
+  let mut orchestrator = Orchestrator::new(false);
+  let log = PointerLog::default();
+
+  let input_state = InputState::default().with_pointer_events(vec![
+    PointerEvent::new(PointerEventKind::Down, Vector2::new(50.0, 20.0)),
+    PointerEvent::new(PointerEventKind::Down, Vector2::new(50.0, 160.0)),
+  ]);
+  orchestrator.construct_and_render(
+    ClippedPointerView { log: log.clone() },
+    200.0,
+    200.0,
+    &mut NoopRenderer::default(),
+    &mut Theme::no_fonts(),
+    &mut app,
+    &input_state,
+  );
+
+  assert_eq!(
+    *log.borrow(),
+    vec![
+      ("visible", Vector2::new(50.0, 20.0)),
+      ("root", Vector2::new(50.0, 160.0)),
+    ]
+  );
+}
+
+#[derive(Debug)]
+struct ScrollingView {
+  layouts: Rc<RefCell<Vec<ResolvedLayout>>>,
+}
+
+impl Component for ScrollingView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    let layouts = self.layouts.clone();
+    ScrollView::new()
+      .with_content(move |context| {
+        LayoutRecorder { layouts }
+          .with_layout(vec![
+            UserElementConstraints::relative_to_parent(0.0, 0.0),
+            UserElementConstraints::fixed_size(100.0, 300.0),
+          ])
+          .build(context);
+      })
+      .with_layout(vec![
+        UserElementConstraints::absolute_position(0.0, 0.0),
+        UserElementConstraints::fixed_size(100.0, 100.0),
+      ])
+      .build(context);
+  }
+}
+
+#[test]
+fn test_scroll_view_offsets_content() {
+  let mut app = App::new(16);
+  let mut orchestrator = Orchestrator::new(false);
+  let layouts = Rc::new(RefCell::new(Vec::new()));
+
+  let mut render = |scroll_y: f32, position: Vector2| {
+    let scroll = PointerEvent::new(
+      PointerEventKind::Scroll {
+        delta: Vector2::new(0.0, scroll_y),
+      },
+      position,
+    );
+    orchestrator.construct_and_render(
+      ScrollingView {
+        layouts: layouts.clone(),
+      },
+      200.0,
+      200.0,
+      &mut NoopRenderer::default(),
+      &mut Theme::no_fonts(),
+      &mut app,
+      &InputState::default().with_pointer_events(vec![scroll]),
+    );
+  };
+
+  // The first frame measures the content, scrolling is clamped to it from then on. Each scroll
+  // shows in the following frame.
+  let inside = Vector2::new(50.0, 50.0);
+  render(0.0, inside);
+  render(-2.0, inside);
+  render(-50.0, inside);
+  render(100.0, inside);
+  // Wheel movement outside of the view doesn't scroll it
+  render(-2.0, Vector2::new(150.0, 150.0));
+  render(0.0, inside);
+
+  let ys = layouts
+    .borrow()
+    .iter()
+    .map(|layout| layout.y)
+    .collect::<Vec<_>>();
+  assert_eq!(ys, vec![0.0, 0.0, -40.0, -200.0, 0.0, 0.0]);
+}
+
+#[derive(Debug)]
+struct StackView {
+  layouts: Rc<RefCell<Vec<ResolvedLayout>>>,
+}
+
+impl Component for StackView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    let layouts = self.layouts.clone();
+    VStack::new(10.0)
+      .with_content(move |context| {
+        for height in [20.0, 30.0, 40.0] {
+          LayoutRecorder {
+            layouts: layouts.clone(),
+          }
+          .with_layout(vec![UserElementConstraints::fixed_size(50.0, height)])
+          .build(context);
+        }
+      })
+      .with_layout(vec![UserElementConstraints::absolute_position(5.0, 5.0)])
+      .build(context);
+  }
+}
+
+#[test]
+fn test_vstack_spaces_children() {
+  let mut app = App::new(16);
+  let mut orchestrator = Orchestrator::new(false);
+  let layouts = Rc::new(RefCell::new(Vec::new()));
+
+  orchestrator.construct_and_render(
+    StackView {
+      layouts: layouts.clone(),
+    },
+    200.0,
+    200.0,
+    &mut NoopRenderer::default(),
+    &mut Theme::no_fonts(),
+    &mut app,
+    &InputState::default(),
+  );
+
+  let positions = layouts
+    .borrow()
+    .iter()
+    .map(|layout| (layout.x, layout.y))
+    .collect::<Vec<_>>();
+  assert_eq!(positions, vec![(5.0, 5.0), (5.0, 35.0), (5.0, 75.0)]);
+}
+
+#[derive(Debug)]
+struct CenteredView {
+  layouts: Rc<RefCell<Vec<ResolvedLayout>>>,
+}
+
+impl Component for CenteredView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    let layouts = self.layouts.clone();
+    LayoutView
+      .with_children(move |context| {
+        LayoutRecorder {
+          layouts: layouts.clone(),
+        }
+        .with_layout(vec![
+          UserElementConstraints::fixed_size(50.0, 30.0),
+          UserElementConstraints::center_in_parent(),
+        ])
+        .build(context);
+      })
+      .with_layout(vec![
+        UserElementConstraints::absolute_position(10.0, 20.0),
+        UserElementConstraints::fixed_size(200.0, 100.0),
+      ])
+      .build(context);
+  }
+}
+
+#[test]
+fn test_center_in_parent_keeps_fixed_size() {
+  let mut app = App::new(16);
+  let mut orchestrator = Orchestrator::new(false);
+  let layouts = Rc::new(RefCell::new(Vec::new()));
+
+  orchestrator.construct_and_render(
+    CenteredView {
+      layouts: layouts.clone(),
+    },
+    300.0,
+    300.0,
+    &mut NoopRenderer::default(),
+    &mut Theme::no_fonts(),
+    &mut app,
+    &InputState::default(),
+  );
+
+  assert_eq!(
+    *layouts.borrow(),
+    vec![ResolvedLayout {
+      x: 85.0,
+      y: 55.0,
+      width: 50.0,
+      height: 30.0,
+    }]
+  );
+}
+
+#[derive(Debug)]
+struct GridTestView {
+  children: usize,
+  layouts: Rc<RefCell<Vec<ResolvedLayout>>>,
+}
+
+impl Component for GridTestView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    let layouts = self.layouts.clone();
+    let children = self.children;
+    let grid = GridView::new(2, 5.0)
+      .with_content(move |context| {
+        for _ in 0..children {
+          LayoutRecorder {
+            layouts: layouts.clone(),
+          }
+          .with_layout(vec![UserElementConstraints::fixed_size(20.0, 10.0)])
+          .build(context);
+        }
+      })
+      .with_layout(vec![UserElementConstraints::absolute_position(0.0, 0.0)])
+      .build(context);
+
+    // Placed right below the grid to observe its height
+    LayoutRecorder {
+      layouts: self.layouts.clone(),
+    }
+    .with_layout(vec![
+      UserElementConstraints::relative_to_parent_horizontal(0.0),
+      UserElementConstraints::anchor_to_bottom_of(grid, 0.0),
+      UserElementConstraints::fixed_size(1.0, 1.0),
+    ])
+    .build(context);
+  }
+}
+
+fn render_grid(children: usize) -> Vec<(f32, f32)> {
+  let mut app = App::new(16);
+  let mut orchestrator = Orchestrator::new(false);
+  let layouts = Rc::new(RefCell::new(Vec::new()));
+
+  orchestrator.construct_and_render(
+    GridTestView {
+      children,
+      layouts: layouts.clone(),
+    },
+    200.0,
+    200.0,
+    &mut NoopRenderer::default(),
+    &mut Theme::no_fonts(),
+    &mut app,
+    &InputState::default(),
+  );
+
+  layouts
+    .borrow()
+    .iter()
+    .map(|layout| (layout.x, layout.y))
+    .collect()
+}
+
+#[test]
+fn test_grid_view_positions() {
+  assert_eq!(
+    render_grid(5),
+    vec![
+      (0.0, 0.0),
+      (25.0, 0.0),
+      (0.0, 15.0),
+      (25.0, 15.0),
+      // Partially filled last row
+      (0.0, 30.0),
+      // The grid grows to contain all rows
+      (0.0, 40.0),
+    ]
+  );
+  assert_eq!(render_grid(0), vec![(0.0, 0.0)]);
+}
+
+#[derive(Debug)]
+struct OrderRecorder {
+  name: &'static str,
+  order: Rc<RefCell<Vec<&'static str>>>,
+}
+
+impl Component for OrderRecorder {
+  fn render(&self, _context: &mut RenderContext) {
+    self.order.borrow_mut().push(self.name);
+  }
+}
+
+#[derive(Debug)]
+struct LayeredView {
+  order: Rc<RefCell<Vec<&'static str>>>,
+}
+
+impl Component for LayeredView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    for name in ["highlight", "first", "second"] {
+      let element = OrderRecorder {
+        name,
+        order: self.order.clone(),
+      }
+      .with_layout(vec![UserElementConstraints::fixed_size(10.0, 10.0)])
+      .build(context);
+
+      if name == "highlight" {
+        context.set_z_index(&element, 1);
+      }
+    }
+  }
+}
+
+#[test]
+fn test_higher_z_index_renders_last() {
+  let mut app = App::new(16);
+  let mut orchestrator = Orchestrator::new(false);
+  let order = Rc::new(RefCell::new(Vec::new()));
+
+  orchestrator.construct_and_render(
+    LayeredView {
+      order: order.clone(),
+    },
+    100.0,
+    100.0,
+    &mut NoopRenderer::default(),
+    &mut Theme::no_fonts(),
+    &mut app,
+    &InputState::default(),
+  );
+
+  assert_eq!(*order.borrow(), vec!["first", "second", "highlight"]);
+}
+
+#[derive(Debug)]
+struct OverlappingPointerView {
+  log: PointerLog,
+}
+
+impl Component for OverlappingPointerView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    // The front element is allocated first, only its z-index puts it on top
+    for name in ["front", "back"] {
+      let element = PointerRecorder {
+        name,
+        handles: true,
+        log: self.log.clone(),
+      }
+      .with_layout(vec![
+        UserElementConstraints::absolute_position(10.0, 10.0),
+        UserElementConstraints::fixed_size(50.0, 50.0),
+      ])
+      .build(context);
+
+      if name == "front" {
+        context.set_z_index(&element, 1);
+      }
+    }
+  }
+}
+
+#[test]
+fn test_pointer_events_hit_highest_z_index() {
+  let mut app = App::new(16);
+  let mut orchestrator = Orchestrator::new(false);
+  let log = PointerLog::default();
+
+  orchestrator.construct_and_render(
+    OverlappingPointerView { log: log.clone() },
+    100.0,
+    100.0,
+    &mut NoopRenderer::default(),
+    &mut Theme::no_fonts(),
+    &mut app,
+    &InputState::default().with_pointer_events(vec![PointerEvent::new(
+      PointerEventKind::Down,
+      Vector2::new(20.0, 20.0),
+    )]),
+  );
+
+  assert_eq!(*log.borrow(), vec![("front", Vector2::new(10.0, 10.0))]);
+}
+
+#[derive(Debug)]
+struct LifecycleCounter {
+  mounts: Rc<Cell<usize>>,
+  unmounts: Rc<Cell<usize>>,
+}
+
+impl Component for LifecycleCounter {
+  fn on_mount(&mut self, _context: &mut ElementContext) {
+    self.mounts.set(self.mounts.get() + 1);
+  }
+
+  fn on_unmount(&mut self, _context: &mut ElementContext) {
+    self.unmounts.set(self.unmounts.get() + 1);
+  }
+}
+
+#[derive(Debug)]
+struct LifecycleView {
+  show_counter: bool,
+  mounts: Rc<Cell<usize>>,
+  unmounts: Rc<Cell<usize>>,
+}
+
+impl Component for LifecycleView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    if self.show_counter {
+      LifecycleCounter {
+        mounts: self.mounts.clone(),
+        unmounts: self.unmounts.clone(),
+      }
+      .build(context);
+    }
+  }
+}
+
+#[test]
+fn test_mount_fires_once_across_frames() {
+  let mut app = App::new(16);
+  let mut orchestrator = Orchestrator::new(false);
+  let mounts = Rc::new(Cell::new(0));
+  let unmounts = Rc::new(Cell::new(0));
+
+  let mut render = |show_counter: bool| {
+    orchestrator.construct_and_render(
+      LifecycleView {
+        show_counter,
+        mounts: mounts.clone(),
+        unmounts: unmounts.clone(),
+      },
+      100.0,
+      100.0,
+      &mut NoopRenderer::default(),
+      &mut Theme::no_fonts(),
+      &mut app,
+      &InputState::default(),
+    );
+  };
+
+  render(true);
+  render(true);
+  assert_eq!(mounts.get(), 1);
+  assert_eq!(unmounts.get(), 0);
+
+  render(false);
+  assert_eq!(unmounts.get(), 1);
+
+  // Appearing again is a new mount
+  render(true);
+  assert_eq!(mounts.get(), 2);
+  assert_eq!(unmounts.get(), 1);
+}
+
+#[derive(Debug)]
+struct KeyedItem {
+  name: &'static str,
+  states: Rc<RefCell<Vec<(&'static str, &'static str)>>>,
+}
+
+impl Component for KeyedItem {
+  fn construct(&mut self, context: &mut ElementContext) {
+    let name = self.name;
+    let (state, _) = MutableState::new(context, move || name, "item");
+    self.states.borrow_mut().push((self.name, state));
+  }
+}
+
+#[derive(Debug)]
+struct KeyedListView {
+  order: Vec<&'static str>,
+  states: Rc<RefCell<Vec<(&'static str, &'static str)>>>,
+}
+
+impl Component for KeyedListView {
+  fn construct(&mut self, context: &mut ElementContext) {
+    for &name in &self.order {
+      KeyedItem {
+        name,
+        states: self.states.clone(),
+      }
+      .build_keyed(context, name);
+    }
+  }
+}
+
+#[test]
+fn test_keyed_state_follows_reordered_children() {
+  let mut app = App::new(16);
+  let mut orchestrator = Orchestrator::new(false);
+  let states = Rc::new(RefCell::new(Vec::new()));
+
+  let mut render = |order: Vec<&'static str>| {
+    states.borrow_mut().clear();
+    orchestrator.construct_and_render(
+      KeyedListView {
+        order,
+        states: states.clone(),
+      },
+      100.0,
+      100.0,
+      &mut NoopRenderer::default(),
+      &mut Theme::no_fonts(),
+      &mut app,
+      &InputState::default(),
+    );
+    states.borrow().clone()
+  };
+
+  assert_eq!(render(vec!["a", "b"]), vec![("a", "a"), ("b", "b")]);
+  // Positional keys would hand the first item's state to "b"
+  assert_eq!(render(vec!["b", "a"]), vec![("b", "b"), ("a", "a")]);
+}
@@ -0,0 +1,235 @@
+use std::{cell::Cell, rc::Rc};
+
+use sapling_app::App;
+use sapling_gui::{
+  NoopRenderer, RecordedDrawCall, SvgRenderer,
+  prelude::{
+    Color, Component, InputState, Orchestrator, Rectangle, RenderContext, RenderFilter, Renderer,
+    StyledView, Theme, Vector4,
+  },
+};
+
+/// Renderer remembering the filled rectangles and gradients, each in drawing order.
+#[derive(Default)]
+struct RecordingRenderer {
+  rectangles: Vec<(Rectangle, Color)>,
+  gradients: Vec<(Rectangle, Color, Color, f32)>,
+}
+
+impl Renderer for RecordingRenderer {
+  fn draw_rectangle(&mut self, rect: Rectangle, _radii: Vector4, fill: Color) {
+    self.rectangles.push((rect, fill));
+  }
+
+  fn draw_rectangle_gradient(
+    &mut self,
+    rect: Rectangle,
+    _radii: Vector4,
+    start: Color,
+    end: Color,
+    angle: f32,
+  ) {
+    self.gradients.push((rect, start, end, angle));
+  }
+}
+
+/// Renders the view as root, covering a 100x50 window.
+fn render_root(view: StyledView) -> RecordingRenderer {
+  let mut renderer = RecordingRenderer::default();
+  Orchestrator::new(false).construct_and_render(
+    view,
+    100.0,
+    50.0,
+    &mut renderer,
+    &mut Theme::no_fonts(),
+    &mut App::new(16),
+    &InputState::default(),
+  );
+  renderer
+}
+
+#[test]
+fn test_border_sides_are_drawn_independently() {
+  let renderer = render_root(StyledView::new().with_border_sides(1.0, 2.0, 3.0, 4.0, Color::RED));
+
+  assert_eq!(
+    renderer.rectangles,
+    vec![
+      (Rectangle::new(0.0, 0.0, 100.0, 1.0), Color::RED),
+      (Rectangle::new(98.0, 0.0, 2.0, 50.0), Color::RED),
+      (Rectangle::new(0.0, 47.0, 100.0, 3.0), Color::RED),
+      (Rectangle::new(0.0, 0.0, 4.0, 50.0), Color::RED),
+    ]
+  );
+}
+
+#[test]
+fn test_zero_width_border_sides_are_skipped() {
+  let renderer = render_root(
+    StyledView::new()
+      .with_background_color(Color::WHITE)
+      .with_border_sides(0.0, 0.0, 0.0, 2.0, Color::BLUE),
+  );
+
+  assert_eq!(
+    renderer.rectangles,
+    vec![
+      (Rectangle::new(0.0, 0.0, 2.0, 50.0), Color::BLUE),
+      // The background starts right of the left border
+      (Rectangle::new(2.0, 0.0, 98.0, 50.0), Color::WHITE),
+    ]
+  );
+}
+
+#[test]
+fn test_gradient_background_is_drawn() {
+  let renderer =
+    render_root(StyledView::new().with_background_gradient(Color::RED, Color::BLUE, 90.0));
+
+  assert!(renderer.rectangles.is_empty());
+  assert_eq!(
+    renderer.gradients,
+    vec![(
+      Rectangle::new(0.0, 0.0, 100.0, 50.0),
+      Color::RED,
+      Color::BLUE,
+      90.0
+    )]
+  );
+}
+
+#[test]
+fn test_opacity_filter_runs_inner_drawing() {
+  let ran = Rc::new(Cell::new(false));
+  let inner_ran = ran.clone();
+  NoopRenderer::default().draw_with_filter(
+    RenderFilter::Opacity { alpha: 0.5 },
+    Box::new(move |_renderer| inner_ran.set(true)),
+  );
+
+  assert!(ran.get());
+}
+
+#[test]
+fn test_noop_renderer_tracks_clips() {
+  let mut renderer = NoopRenderer::default();
+  renderer.begin_clip(Rectangle::new(0.0, 0.0, 10.0, 10.0));
+  renderer.begin_clip(Rectangle::new(2.0, 2.0, 4.0, 4.0));
+  assert_eq!(renderer.clip_depth(), 2);
+  assert_eq!(
+    renderer.clip_stack().last(),
+    Some(&Rectangle::new(2.0, 2.0, 4.0, 4.0))
+  );
+
+  renderer.end_clip();
+  renderer.end_clip();
+  assert_eq!(renderer.clip_depth(), 0);
+}
+
+#[derive(Debug)]
+struct UnbalancedClipView;
+
+impl Component for UnbalancedClipView {
+  fn render(&self, context: &mut RenderContext) {
+    context
+      .renderer
+      .begin_clip(Rectangle::new(0.0, 0.0, 10.0, 10.0));
+  }
+}
+
+#[test]
+fn test_unbalanced_clips_are_ended_at_frame_end() {
+  let mut renderer = NoopRenderer::default();
+  Orchestrator::new(false).construct_and_render(
+    UnbalancedClipView,
+    100.0,
+    50.0,
+    &mut renderer,
+    &mut Theme::no_fonts(),
+    &mut App::new(16),
+    &InputState::default(),
+  );
+
+  assert_eq!(renderer.clip_depth(), 0);
+}
+
+#[test]
+fn test_noop_renderer_records_styled_view() {
+  let mut renderer = NoopRenderer::recording();
+  Orchestrator::new(false).construct_and_render(
+    StyledView::new()
+      .with_background_color(Color::WHITE)
+      .with_border(2.0, Color::RED),
+    100.0,
+    50.0,
+    &mut renderer,
+    &mut Theme::no_fonts(),
+    &mut App::new(16),
+    &InputState::default(),
+  );
+
+  let no_radii = Vector4::new(0.0, 0.0, 0.0, 0.0);
+  assert_eq!(
+    renderer.drawn_calls(),
+    vec![
+      RecordedDrawCall::Border {
+        rect: Rectangle::new(0.0, 0.0, 100.0, 50.0),
+        radii: no_radii,
+        border: Color::RED,
+        thickness: 2.0,
+      },
+      // The background ends in the middle of the border
+      RecordedDrawCall::Rectangle {
+        rect: Rectangle::new(1.0, 1.0, 98.0, 48.0),
+        radii: no_radii,
+        fill: Color::WHITE,
+      },
+    ]
+  );
+}
+
+#[test]
+fn test_noop_renderer_records_filter_drawing_separately() {
+  let mut renderer = NoopRenderer::recording();
+  renderer.draw_with_filter(
+    RenderFilter::Opacity { alpha: 0.5 },
+    Box::new(|mut renderer| {
+      renderer.draw_rectangle(
+        Rectangle::new(0.0, 0.0, 1.0, 1.0),
+        Vector4::new(0.0, 0.0, 0.0, 0.0),
+        Color::BLUE,
+      );
+    }),
+  );
+
+  let drawn_calls = renderer.drawn_calls();
+  let [RecordedDrawCall::Filter { filter, calls }] = drawn_calls.as_slice() else {
+    panic!("expected a single filter");
+  };
+  assert_eq!(*filter, RenderFilter::Opacity { alpha: 0.5 });
+  assert_eq!(calls.len(), 1);
+  assert!(NoopRenderer::default().drawn_calls().is_empty());
+}
+
+#[test]
+fn test_svg_renderer_draws_rectangle() {
+  let mut renderer = SvgRenderer::new(100.0, 50.0);
+  Orchestrator::new(false).construct_and_render(
+    StyledView::new()
+      .with_background_color(Color::new(255, 0, 0, 255))
+      .with_border_radius_even(4.0),
+    100.0,
+    50.0,
+    &mut renderer,
+    &mut Theme::no_fonts(),
+    &mut App::new(16),
+    &InputState::default(),
+  );
+
+  let svg = renderer.finish();
+  assert!(svg.starts_with("<svg "));
+  assert!(
+    svg.contains(r##"<rect x="0" y="0" width="100" height="50" rx="4" ry="4" fill="#ff0000"/>"##)
+  );
+  assert!(svg.ends_with("</svg>"));
+}
@@ -0,0 +1,31 @@
+#![cfg(feature = "offscreen")]
+
+use sapling_gui::{
+  RaylibRendererState,
+  prelude::{Color, Rectangle, Renderer, Theme, Vector4},
+};
+
+#[test]
+fn test_same_rectangle_is_rasterized_once_per_theme() {
+  let (mut handle, thread) = raylib::init().size(64, 64).title("offscreen").build();
+  let mut state = RaylibRendererState::new(&mut handle, &thread);
+  let radii = Vector4::new(4.0, 4.0, 4.0, 4.0);
+
+  let theme = Theme::no_fonts();
+  let image = state.render_to_image(&mut handle, &thread, 32, 32, |renderer| {
+    renderer.begin_frame(&theme);
+    renderer.draw_rectangle(Rectangle::new(0.0, 0.0, 20.0, 10.0), radii, Color::RED);
+    // Sub-pixel differences in size share the texture
+    renderer.draw_rectangle(Rectangle::new(4.0, 16.0, 20.2, 9.8), radii, Color::RED);
+  });
+  assert_eq!(state.shape_textures_created(), 1);
+  assert_eq!(image.pixel(10, 5), Color::RED);
+  assert_eq!(image.pixel(14, 21), Color::RED);
+
+  let theme = Theme::no_fonts();
+  state.render_to_image(&mut handle, &thread, 32, 32, |renderer| {
+    renderer.begin_frame(&theme);
+    renderer.draw_rectangle(Rectangle::new(0.0, 0.0, 20.0, 10.0), radii, Color::RED);
+  });
+  assert_eq!(state.shape_textures_created(), 2);
+}
@@ -0,0 +1,25 @@
+use sapling_gui::prelude::{Theme, ThemeVariant};
+
+#[test]
+fn test_variants_differ_in_colors_and_share_spacing() {
+  let dark = Theme::no_fonts_with_variant(ThemeVariant::Dark);
+  let light = Theme::no_fonts_with_variant(ThemeVariant::Light);
+
+  assert_eq!(dark.variant, ThemeVariant::Dark);
+  assert_eq!(light.variant, ThemeVariant::Light);
+  assert_ne!(dark.color_background, light.color_background);
+
+  assert_eq!(dark.spacing_tiny, light.spacing_tiny);
+  assert_eq!(dark.spacing_small, light.spacing_small);
+  assert_eq!(dark.spacing_default, light.spacing_default);
+  assert_eq!(dark.spacing_large, light.spacing_large);
+  assert_eq!(dark.spacing_xlarge, light.spacing_xlarge);
+  assert_eq!(dark.radius_default, light.radius_default);
+  assert_eq!(dark.radius_large, light.radius_large);
+}
+
+#[test]
+fn test_toggled_variant() {
+  assert_eq!(ThemeVariant::Dark.toggled(), ThemeVariant::Light);
+  assert_eq!(ThemeVariant::Light.toggled(), ThemeVariant::Dark);
+}
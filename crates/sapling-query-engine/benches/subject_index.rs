@@ -0,0 +1,98 @@
+//! Compares subject queries using the subject index against scanning all facts.
+//!
+//! Run with `cargo bench -p sapling-query-engine --bench subject_index`.
+
+use std::{hint::black_box, time::Instant};
+
+use sapling_data_model::{Fact, Query, Subject, SubjectSelector};
+use sapling_query_engine::{
+  Database, QueryEngine, SharedVariableAllocator, SharedVariableBank, System,
+};
+
+const SUBJECT_COUNT: usize = 5_000;
+const FACTS_PER_SUBJECT: usize = 4;
+const QUERIED_SUBJECTS: usize = 500;
+
+fn selector(subject: Subject) -> SubjectSelector {
+  SubjectSelector {
+    evaluated: false,
+    subject,
+    property: None,
+  }
+}
+
+fn build_database() -> (Database, Vec<Subject>) {
+  let mut database = Database::new();
+  let property = database.new_static_subject();
+  let subjects = (0..SUBJECT_COUNT)
+    .map(|_| database.new_static_subject())
+    .collect::<Vec<_>>();
+
+  for value in 0..FACTS_PER_SUBJECT {
+    for subject in &subjects {
+      database.add_fact(Fact {
+        meta: Subject::String {
+          value: "default meta".into(),
+        },
+        operator: System::CORE_OPERATOR_IS.clone(),
+        subject: selector(subject.clone()),
+        property: selector(property.clone()),
+        value: selector(Subject::Integer {
+          value: value as i64,
+        }),
+      });
+    }
+  }
+
+  (database, subjects)
+}
+
+fn run_queries(database: &Database, subjects: &[Subject]) -> usize {
+  let query_engine = QueryEngine::new();
+  let bank = SharedVariableBank::new(128);
+  let allocator = SharedVariableAllocator::new();
+
+  subjects
+    .iter()
+    .step_by(SUBJECT_COUNT / QUERIED_SUBJECTS)
+    .map(|subject| {
+      bank.reset();
+      allocator.reset();
+      query_engine
+        .query(
+          database,
+          &Query {
+            evaluated: false,
+            meta: None,
+            property: None,
+            subject: subject.clone(),
+          },
+          bank.clone(),
+          allocator.clone(),
+        )
+        .count()
+    })
+    .sum()
+}
+
+fn measure(name: &str, database: &Database, subjects: &[Subject]) {
+  let start = Instant::now();
+  let found = black_box(run_queries(database, subjects));
+  let elapsed = start.elapsed();
+  println!(
+    "{name}: {QUERIED_SUBJECTS} queries over {} facts found {found} facts in {:.2}ms",
+    database.fact_count(),
+    elapsed.as_secs_f64() * 1000.0
+  );
+}
+
+fn main() {
+  let (indexed, subjects) = build_database();
+
+  // Handing out a fact mutably leaves the index stale, so queries scan every fact
+  let mut naive = indexed.clone();
+  naive.get_fact_mut(0);
+
+  measure("indexed", &indexed, &subjects);
+  measure("naive", &naive, &subjects);
+}
@@ -1,11 +1,38 @@
+use std::{
+  cmp::Ordering,
+  collections::{BTreeSet, HashMap},
+};
+
 use sapling_data_model::{Fact, Subject};
 
 use crate::{meta::QueryMeta, system::System};
 
+/// Failure to evaluate a subject outside of a query, e.g. an interop function whose operands have
+/// the wrong type. Explain shows the failures of the subjects it covers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineFailure {
+  pub subject: Subject,
+  pub message: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct Database {
   pub(crate) raw: Vec<Fact>,
   subject_next_id: u128,
+  /// Facts from this index onwards were added after the last `mark_clean`
+  clean_len: usize,
+  /// Facts before `clean_len` which were mutably accessed after the last `mark_clean`
+  modified: BTreeSet<usize>,
+  snapshot_id: u64,
+  /// Indices of the facts with a plain subject, by subject
+  subject_index: HashMap<Subject, Vec<usize>>,
+  /// Indices of the facts whose subject is evaluated or refers to a property, those may resolve
+  /// to any subject
+  dynamic_subject_facts: Vec<usize>,
+  /// Set when facts were handed out mutably, the index is rebuilt on the next insertion
+  index_stale: bool,
+  /// At most one failure per subject, the latest one
+  failures: Vec<EngineFailure>,
 }
 
 impl Database {
@@ -14,6 +41,13 @@ impl Database {
     let mut db = Self {
       raw: Vec::with_capacity(1024),
       subject_next_id: 0,
+      clean_len: 0,
+      modified: BTreeSet::new(),
+      snapshot_id: 0,
+      subject_index: HashMap::new(),
+      dynamic_subject_facts: Vec::new(),
+      index_stale: false,
+      failures: Vec::new(),
     };
     System::install(&mut db);
     db
@@ -28,8 +62,34 @@ impl Database {
   }
 
   pub fn add_fact(&mut self, fact: Fact) -> usize {
+    self.reindex();
     self.raw.push(fact);
-    self.raw.len() - 1
+    let index = self.raw.len() - 1;
+    self.index_fact(index);
+    index
+  }
+
+  /// Number of facts in the database. Unlike `facts_mut().len()` this doesn't mark the facts dirty.
+  pub fn fact_count(&self) -> usize {
+    self.raw.len()
+  }
+
+  /// Records that `subject` failed to evaluate, replacing an earlier failure of the subject.
+  pub fn report_failure(&mut self, subject: Subject, message: String) {
+    self.clear_failure(&subject);
+    self.failures.push(EngineFailure { subject, message });
+  }
+
+  /// Drops the failure of `subject`, e.g. once it evaluated successfully again.
+  pub fn clear_failure(&mut self, subject: &Subject) {
+    self.failures.retain(|failure| failure.subject != *subject);
+  }
+
+  pub fn get_failure(&self, subject: &Subject) -> Option<&EngineFailure> {
+    self
+      .failures
+      .iter()
+      .find(|failure| failure.subject == *subject)
   }
 
   pub fn get_fact(&self, index: usize) -> Option<&Fact> {
@@ -37,21 +97,153 @@ impl Database {
   }
 
   pub fn get_fact_mut(&mut self, index: usize) -> Option<&mut Fact> {
+    if index < self.clean_len {
+      self.modified.insert(index);
+    }
+    self.index_stale = true;
     self.raw.get_mut(index)
   }
 
+  /// Removes the fact at `index`, moving all later facts down by one index. Indices of those facts
+  /// held elsewhere are stale afterwards.
+  ///
+  /// As a removal can't be expressed as a dirty fact, removing a fact which was part of the last
+  /// snapshot marks every fact as dirty.
+  pub fn remove_fact(&mut self, index: usize) -> Option<Fact> {
+    if index >= self.raw.len() {
+      return None;
+    }
+
+    if index < self.clean_len {
+      self.clean_len = 0;
+      self.modified.clear();
+    }
+    let fact = self.raw.remove(index);
+    self.index_stale = true;
+    self.reindex();
+    Some(fact)
+  }
+
+  /// Gives raw access to all facts, which marks every fact as dirty.
   pub fn facts_mut(&mut self) -> &mut Vec<Fact> {
+    self.clean_len = 0;
+    self.modified.clear();
+    self.index_stale = true;
     &mut self.raw
   }
 
+  /// Rebuilds the subject index if facts were modified through [`Database::get_fact_mut`] or
+  /// [`Database::facts_mut`]. Until then lookups by subject scan all facts.
+  pub fn reindex(&mut self) {
+    if !self.index_stale {
+      return;
+    }
+
+    self.subject_index.clear();
+    self.dynamic_subject_facts.clear();
+    for index in 0..self.raw.len() {
+      self.index_fact(index);
+    }
+    self.index_stale = false;
+  }
+
+  fn index_fact(&mut self, index: usize) {
+    let subject = &self.raw[index].subject;
+    if subject.evaluated || subject.property.is_some() {
+      self.dynamic_subject_facts.push(index);
+    } else {
+      self
+        .subject_index
+        .entry(subject.subject.clone())
+        .or_default()
+        .push(index);
+    }
+  }
+
+  /// Indices of the facts whose subject is `subject`, and of all facts with a dynamic subject, if
+  /// the index is usable for the subject.
+  pub(crate) fn indexed_facts(&self, subject: &Subject) -> Option<(&[usize], &[usize])> {
+    // Floats match by `==` rather than by their bits, e.g. `0.0` matches `-0.0`
+    if self.index_stale || matches!(subject, Subject::Float { .. }) {
+      return None;
+    }
+
+    let subject_facts = self
+      .subject_index
+      .get(subject)
+      .map(Vec::as_slice)
+      .unwrap_or_default();
+    Some((subject_facts, &self.dynamic_subject_facts))
+  }
+
+  /// Marks all facts as clean, e.g. after they were persisted, and starts a new snapshot.
+  /// Returns the id of the snapshot that following dirty facts are relative to.
+  pub fn mark_clean(&mut self) -> u64 {
+    self.clean_len = self.raw.len();
+    self.modified.clear();
+    self.snapshot_id += 1;
+    self.snapshot_id
+  }
+
+  /// Id of the snapshot taken by the last [`Database::mark_clean`], `0` if there was none.
+  pub fn snapshot_id(&self) -> u64 {
+    self.snapshot_id
+  }
+
+  /// Indices of all facts added or mutably accessed since the last [`Database::mark_clean`], in
+  /// ascending order.
+  pub fn dirty_fact_indices(&self) -> impl Iterator<Item = usize> + '_ {
+    self
+      .modified
+      .iter()
+      .copied()
+      .chain(self.clean_len..self.raw.len())
+  }
+
+  /// Returns every subject with the given value assigned to the property, in order of their
+  /// first matching fact.
+  pub fn get_subjects_with_value(&self, property: &Subject, value: &Subject) -> Vec<Subject> {
+    let mut subjects: Vec<Subject> = Vec::new();
+    for fact in &self.raw {
+      if fact.subject.evaluated
+        || fact.subject.property.is_some()
+        || fact.value.evaluated
+        || fact.value.property.is_some()
+      {
+        continue;
+      }
+
+      if !match_subject(&fact.operator, &System::CORE_OPERATOR_IS)
+        || !match_subject(&fact.property.subject, property)
+        || !match_subject(&fact.value.subject, value)
+      {
+        continue;
+      }
+
+      if !subjects
+        .iter()
+        .any(|subject| match_subject(subject, &fact.subject.subject))
+      {
+        subjects.push(fact.subject.subject.clone());
+      }
+    }
+    subjects
+  }
+
+  /// The include marker makes a query match system facts alongside all others, any other meta
+  /// restricts it to facts with exactly that meta.
   pub fn get_query_meta(&self, meta_subject: &Subject) -> QueryMeta {
     if match_subject(meta_subject, &System::CORE_META_INCLUDE) {
       return QueryMeta {
         include_system_meta: true,
+        required_meta: None,
       };
     }
 
-    QueryMeta::default()
+    QueryMeta {
+      include_system_meta: match_subject(meta_subject, &System::CORE_META),
+      required_meta: Some(meta_subject.clone()),
+    }
   }
 
   pub fn get_facts_for_subject(
@@ -100,6 +292,9 @@ impl Database {
 pub(crate) fn match_subject(a: &Subject, b: &Subject) -> bool {
   match (a, b) {
     (Subject::Static { uuid: a_uuid }, Subject::Static { uuid: b_uuid }) => a_uuid == b_uuid,
+    (Subject::Boolean { value: a_value }, Subject::Boolean { value: b_value }) => {
+      a_value == b_value
+    }
     (Subject::Integer { value: a_value }, Subject::Integer { value: b_value }) => {
       a_value == b_value
     }
@@ -108,3 +303,123 @@ pub(crate) fn match_subject(a: &Subject, b: &Subject) -> bool {
     _ => false,
   }
 }
+
+/// Orders two numeric subjects, integers are compared to floats by their float value. Returns
+/// `None` if either subject isn't a number.
+pub(crate) fn compare_numeric(a: &Subject, b: &Subject) -> Option<Ordering> {
+  match (a, b) {
+    (Subject::Integer { value: a_value }, Subject::Integer { value: b_value }) => {
+      Some(a_value.cmp(b_value))
+    }
+    (Subject::Integer { value: a_value }, Subject::Float { value: b_value }) => {
+      (*a_value as f64).partial_cmp(b_value)
+    }
+    (Subject::Float { value: a_value }, Subject::Integer { value: b_value }) => {
+      a_value.partial_cmp(&(*b_value as f64))
+    }
+    (Subject::Float { value: a_value }, Subject::Float { value: b_value }) => {
+      a_value.partial_cmp(b_value)
+    }
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use sapling_data_model::{Fact, Query, Subject, SubjectSelector};
+
+  use crate::{Database, QueryEngine, SharedVariableAllocator, SharedVariableBank, System};
+
+  fn selector(subject: &Subject, evaluated: bool) -> SubjectSelector {
+    SubjectSelector {
+      evaluated,
+      subject: subject.clone(),
+      property: None,
+    }
+  }
+
+  fn add_fact(database: &mut Database, subject: SubjectSelector, property: &Subject, value: i64) {
+    database.add_fact(Fact {
+      meta: Subject::String {
+        value: "default meta".into(),
+      },
+      operator: System::CORE_OPERATOR_IS.clone(),
+      subject,
+      property: selector(property, false),
+      value: selector(&Subject::Integer { value }, false),
+    });
+  }
+
+  fn query_fact_indices(database: &Database, subject: &Subject) -> Vec<usize> {
+    QueryEngine::new()
+      .query(
+        database,
+        &Query {
+          evaluated: false,
+          meta: None,
+          property: None,
+          subject: subject.clone(),
+        },
+        SharedVariableBank::new(128),
+        SharedVariableAllocator::new(),
+      )
+      .map(|found| found.fact_index)
+      .collect()
+  }
+
+  #[test]
+  fn test_indexed_queries_match_naive_queries() {
+    let mut database = Database::new();
+    let property = database.new_static_subject();
+    let everything = database.new_static_subject();
+    let subjects = (0..4)
+      .map(|_| database.new_static_subject())
+      .chain([Subject::Integer { value: 7 }])
+      .collect::<Vec<_>>();
+
+    for (i, subject) in subjects.iter().enumerate() {
+      add_fact(&mut database, selector(subject, false), &property, i as i64);
+    }
+    // Evaluates to every subject, so it is part of every result
+    add_fact(&mut database, selector(&everything, true), &property, 10);
+    for (i, subject) in subjects.iter().enumerate().rev() {
+      add_fact(
+        &mut database,
+        selector(subject, false),
+        &property,
+        20 + i as i64,
+      );
+    }
+
+    // Handing out a fact mutably makes lookups scan all facts
+    let mut naive = database.clone();
+    naive.get_fact_mut(0);
+    assert!(naive.indexed_facts(&subjects[0]).is_none());
+    assert!(database.indexed_facts(&subjects[0]).is_some());
+
+    for subject in &subjects {
+      let indexed = query_fact_indices(&database, subject);
+      assert_eq!(indexed.len(), 3);
+      assert_eq!(indexed, query_fact_indices(&naive, subject));
+    }
+
+    // Adding a fact rebuilds the index
+    add_fact(&mut naive, selector(&subjects[0], false), &property, 30);
+    assert!(naive.indexed_facts(&subjects[0]).is_some());
+    assert_eq!(query_fact_indices(&naive, &subjects[0]).len(), 4);
+  }
+
+  #[test]
+  fn test_removing_a_fact_updates_the_index() {
+    let mut database = Database::new();
+    let property = database.new_static_subject();
+    let subject = database.new_static_subject();
+    let first = database.fact_count();
+    add_fact(&mut database, selector(&subject, false), &property, 1);
+    add_fact(&mut database, selector(&subject, false), &property, 2);
+
+    database.remove_fact(first);
+
+    assert_eq!(query_fact_indices(&database, &subject), vec![first]);
+  }
+}
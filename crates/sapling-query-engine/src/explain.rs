@@ -2,13 +2,17 @@ use std::collections::HashMap;
 
 use sapling_data_model::Subject;
 
-use crate::instructions::UnificationInstruction;
+use crate::{database::EngineFailure, instructions::UnificationInstruction};
 
 #[derive(Debug)]
 pub struct ExplainQuery {
   pub query_subject: Subject,
   pub target_subject: Option<Subject>,
+  /// Restricts the yielded facts to this property
+  pub property: Option<Subject>,
   pub facts: HashMap<usize, usize>,
+  /// Traces every fact investigated by constraints missing from `facts`
+  pub trace_all: bool,
 }
 
 #[derive(Debug)]
@@ -16,8 +20,19 @@ pub struct ExplainResult {
   pub constraints: Vec<(usize, usize)>,
   pub subject: Option<Subject>,
   pub variables: HashMap<String, Subject>,
+  /// Fact which was investigated when each variable in `variables` got its current value
+  pub variable_sources: HashMap<String, ExplainVariableSource>,
   pub fact_events: Vec<ExplainFactEvent>,
   pub instruction: Vec<UnificationInstruction>,
+  /// Failures of the explained query or its target subject, see [`crate::Database::report_failure`]
+  pub failures: Vec<EngineFailure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExplainVariableSource {
+  /// Set if the fact was investigated for a traced constraint
+  pub constraint_id: Option<usize>,
+  pub fact_index: usize,
 }
 
 #[derive(Debug)]
@@ -79,6 +94,8 @@ pub enum ExplainConstraintEvaluationOutcome {
 #[derive(Debug)]
 pub enum ExplainConstraintEvaluationOutcomeReason {
   NotFound,
+  /// Evaluating the fact needed more nested sub-queries than the machine allows.
+  MaxDepthExceeded,
 }
 
 #[derive(Debug)]
@@ -6,6 +6,10 @@ pub enum UnificationInstruction {
   AllocateFrame {
     size: usize,
   },
+  /// Like `AllocateFrame` but only iterates facts which may have the subject
+  AllocateSubjectFrame {
+    subject: Subject,
+  },
   AllocateFact {
     fact_index: usize,
     reset_address: Option<usize>,
@@ -27,11 +31,22 @@ pub enum UnificationInstruction {
     value: Subject,
     property: Option<Subject>,
   },
+  /// Passes only if the value does not match, used for `!=` constraints
+  CheckValueNot {
+    value: Subject,
+    property: Option<Subject>,
+  },
+  /// Compares the value numerically using one of the ordered operators, e.g. `<` or `>=`
+  CheckValueOrdered {
+    value: Subject,
+    operator: Subject,
+  },
   CheckOperator {
     operator: Subject,
   },
   CheckMeta {
     skip_system: bool,
+    meta: Option<Subject>,
   },
 
   // Unifications instructions
@@ -61,6 +76,10 @@ pub enum UnificationInstruction {
     constraint: usize,
     fact: usize,
   },
+  /// Like `TraceStartFact`, for whichever fact the constraint investigates
+  TraceAnyFact {
+    constraint: usize,
+  },
   TraceConstraintCreate {
     constraint: usize,
     fact_index: usize,
@@ -1,6 +1,8 @@
-use sapling_data_model::Fact;
+use std::collections::HashSet;
 
-use crate::Database;
+use sapling_data_model::{Fact, Subject};
+
+use crate::{Database, FoundFact};
 
 #[derive(Clone)]
 pub struct NaiveFactIterator<'a> {
@@ -30,4 +32,109 @@ impl Database {
       current_index: 0,
     }
   }
+
+  /// Iterates the facts which may have `subject`, that is facts with exactly this subject and all
+  /// facts whose subject is evaluated or refers to a property. Falls back to iterating every fact
+  /// if the index can't be used.
+  pub fn iter_facts_by_subject<'a>(&'a self, subject: &Subject) -> FactIterator<'a> {
+    match self.indexed_facts(subject) {
+      Some((subject_facts, dynamic_subject_facts)) => FactIterator::Indexed(IndexedFactIterator {
+        database: self,
+        subject_facts,
+        dynamic_subject_facts,
+      }),
+      None => FactIterator::Naive(self.iter_naive_facts()),
+    }
+  }
+}
+
+/// Merges the facts with a given subject and the facts with a dynamic subject in index order, so
+/// a frame sees the same facts in the same order as with [`NaiveFactIterator`], minus the ones
+/// which can't match the subject.
+#[derive(Clone)]
+pub struct IndexedFactIterator<'a> {
+  database: &'a Database,
+  subject_facts: &'a [usize],
+  dynamic_subject_facts: &'a [usize],
+}
+
+impl<'a> Iterator for IndexedFactIterator<'a> {
+  type Item = (usize, &'a Fact);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let index = match (
+      self.subject_facts.first(),
+      self.dynamic_subject_facts.first(),
+    ) {
+      (Some(&a), Some(&b)) if a < b => {
+        self.subject_facts = &self.subject_facts[1..];
+        a
+      }
+      (_, Some(&b)) => {
+        self.dynamic_subject_facts = &self.dynamic_subject_facts[1..];
+        b
+      }
+      (Some(&a), None) => {
+        self.subject_facts = &self.subject_facts[1..];
+        a
+      }
+      (None, None) => return None,
+    };
+    self.database.raw.get(index).map(|fact| (index, fact))
+  }
+}
+
+/// Facts a frame iterates.
+#[derive(Clone)]
+pub enum FactIterator<'a> {
+  Naive(NaiveFactIterator<'a>),
+  Indexed(IndexedFactIterator<'a>),
+}
+
+impl<'a> Iterator for FactIterator<'a> {
+  type Item = (usize, &'a Fact);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    match self {
+      FactIterator::Naive(iterator) => iterator.next(),
+      FactIterator::Indexed(iterator) => iterator.next(),
+    }
+  }
+}
+
+impl<'a> From<NaiveFactIterator<'a>> for FactIterator<'a> {
+  fn from(iterator: NaiveFactIterator<'a>) -> Self {
+    FactIterator::Naive(iterator)
+  }
+}
+
+/// Skips found facts whose fact index was yielded before. The first occurrence wins, so its
+/// subject binding is the one which is kept.
+pub struct UniqueByIndex<I> {
+  inner: I,
+  yielded: HashSet<usize>,
+}
+
+impl<'a, I: Iterator<Item = FoundFact<'a>>> Iterator for UniqueByIndex<I> {
+  type Item = FoundFact<'a>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self
+      .inner
+      .by_ref()
+      .find(|found| self.yielded.insert(found.fact_index))
+  }
+}
+
+/// Adapters for iterators over query results, e.g. an [`crate::AbstractMachine`].
+pub trait FoundFactIteratorExt<'a>: Iterator<Item = FoundFact<'a>> + Sized {
+  /// Yields every fact only once, even if the query matched it through several unifications.
+  fn unique_by_index(self) -> UniqueByIndex<Self> {
+    UniqueByIndex {
+      inner: self,
+      yielded: HashSet::new(),
+    }
+  }
 }
+
+impl<'a, I: Iterator<Item = FoundFact<'a>>> FoundFactIteratorExt<'a> for I {}
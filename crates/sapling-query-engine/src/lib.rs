@@ -10,14 +10,17 @@ mod variable_allocator;
 mod variable_bank;
 mod watcher;
 
-pub use database::Database;
+pub use database::{Database, EngineFailure};
 pub use explain::{
   EvaluationType, ExplainConstraintEvaluationOutcome, ExplainConstraintEvaluationOutcomeReason,
 };
-pub use explain::{ExplainConstraintEvaluation, ExplainFactEvent, ExplainQuery, ExplainResult};
-pub use machine::FoundFact;
+pub use explain::{
+  ExplainConstraintEvaluation, ExplainFactEvent, ExplainQuery, ExplainResult, ExplainVariableSource,
+};
+pub use iterators::{FoundFactIteratorExt, UniqueByIndex};
+pub use machine::{AbstractMachine, FoundFact};
 pub use query_engine::QueryEngine;
 pub use system::System;
 pub use variable_allocator::SharedVariableAllocator;
 pub use variable_bank::SharedVariableBank;
-pub use watcher::{DatabaseWatcher, QueryWatcher};
+pub use watcher::{DatabaseWatcher, QueryWatcher, WatcherId};
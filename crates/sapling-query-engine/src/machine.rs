@@ -1,23 +1,37 @@
 use std::{
+  cell::Cell,
   collections::{HashMap, VecDeque},
   fmt::Debug,
+  rc::Rc,
 };
 
-use sapling_data_model::{Fact, Query, Subject};
+use sapling_data_model::{Fact, Query, Subject, SubjectSelector};
 
 use crate::{
-  Database, ExplainConstraintEvaluation, ExplainFactEvent, ExplainResult, QueryEngine,
-  SharedVariableAllocator, SharedVariableBank, System,
-  database::match_subject,
+  Database, ExplainConstraintEvaluation, ExplainFactEvent, ExplainResult, ExplainVariableSource,
+  QueryEngine, SharedVariableAllocator, SharedVariableBank, System,
+  database::{compare_numeric, match_subject},
   explain::{
     EvaluationType, ExplainConstraintEvaluationOutcome, ExplainConstraintEvaluationOutcomeReason,
   },
   instructions::UnificationInstruction,
-  iterators::NaiveFactIterator,
+  iterators::FactIterator,
 };
 
 macro_rules! tracing_constraint_check {
   ($self:ident, $frame:ident, $created_trace_event:ident, $variant:ident, $target:expr, $fact_property:expr, $ty:expr) => {{
+    tracing_constraint_check!(
+      $self,
+      $frame,
+      $created_trace_event,
+      $variant,
+      $target,
+      $fact_property,
+      $ty,
+      System::CORE_OPERATOR_EQ
+    )
+  }};
+  ($self:ident, $frame:ident, $created_trace_event:ident, $variant:ident, $target:expr, $fact_property:expr, $ty:expr, $operator:expr) => {{
     if let Some(constraint) = $frame.tracing {
       if $target.is_some() {
         $self
@@ -29,7 +43,7 @@ macro_rules! tracing_constraint_check {
             evaluation: ExplainConstraintEvaluation::$variant {
               target: $target,
               actual: $fact_property.clone(),
-              operator: System::CORE_OPERATOR_EQ.clone(),
+              operator: $operator.clone(),
             },
             outcome: ExplainConstraintEvaluationOutcome::Passed,
           });
@@ -52,6 +66,36 @@ pub struct AbstractMachine<'a> {
   pub log_instructions: bool,
   pub explain_result: ExplainResult,
   explain_enabled: bool,
+  max_yields: Option<usize>,
+  yield_count: usize,
+  limits: SearchLimits,
+  depth: usize,
+  max_depth: usize,
+}
+
+/// Sub-queries nested deeper than this are rejected, which stops facts evaluating to themselves.
+const DEFAULT_MAX_DEPTH: usize = 64;
+
+/// Step budget shared between a machine and all sub-queries it spawns, so a search which keeps
+/// evaluating the same facts runs out of steps instead of hanging.
+#[derive(Clone, Debug, Default)]
+struct SearchLimits {
+  remaining_steps: Option<Rc<Cell<usize>>>,
+  limit_reached: Rc<Cell<bool>>,
+}
+
+impl SearchLimits {
+  fn consume_step(&self) -> bool {
+    let Some(remaining_steps) = &self.remaining_steps else {
+      return true;
+    };
+    if remaining_steps.get() == 0 {
+      self.limit_reached.set(true);
+      return false;
+    }
+    remaining_steps.set(remaining_steps.get() - 1);
+    true
+  }
 }
 
 #[derive(Clone, Debug)]
@@ -61,6 +105,13 @@ pub struct FoundFact<'a> {
   pub subject_binding: Option<Subject>,
 }
 
+impl FoundFact<'_> {
+  /// Meta of the matched fact, e.g. to tell system facts apart from user facts.
+  pub fn meta(&self) -> &Subject {
+    &self.fact.meta
+  }
+}
+
 impl<'a> AbstractMachine<'a> {
   pub fn new(
     instructions: Vec<UnificationInstruction>,
@@ -84,13 +135,45 @@ impl<'a> AbstractMachine<'a> {
         fact_events: vec![],
         instruction: instructions.clone(),
         variables: HashMap::new(),
+        variable_sources: HashMap::new(),
+        failures: vec![],
       },
       instructions,
       variable_bank,
       variable_allocator,
+      max_yields: None,
+      yield_count: 0,
+      limits: SearchLimits::default(),
+      depth: 0,
+      max_depth: DEFAULT_MAX_DEPTH,
     }
   }
 
+  /// How deep evaluated subjects and values may nest sub-queries before the fact is rejected.
+  pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+    self.max_depth = max_depth;
+    self
+  }
+
+  /// Stops the machine once `max_yields` facts have been yielded.
+  pub fn with_max_yields(mut self, max_yields: usize) -> Self {
+    self.max_yields = Some(max_yields);
+    self
+  }
+
+  /// Stops the machine after `max_steps` instructions, including the ones executed by
+  /// sub-queries. The facts yielded until then are kept.
+  pub fn with_step_budget(mut self, max_steps: usize) -> Self {
+    self.limits.remaining_steps = Some(Rc::new(Cell::new(max_steps)));
+    self
+  }
+
+  /// Whether the search was cut short by the yield limit or the step budget, in which case the
+  /// yielded facts are only a partial result.
+  pub fn limit_reached(&self) -> bool {
+    self.limits.limit_reached.get()
+  }
+
   pub fn reset_machine(&mut self) {
     self.fallback_instruction_pointer = 0;
     self.yielded.clear();
@@ -173,6 +256,10 @@ impl<'a> AbstractMachine<'a> {
   }
 
   fn step(&mut self) -> bool {
+    if !self.limits.consume_step() {
+      return false;
+    }
+
     let mut instruction_index = self
       .stack
       .last_mut()
@@ -217,7 +304,11 @@ impl<'a> AbstractMachine<'a> {
       .expect("Out of bounds instruction");
 
     let mut reset_frame = false;
+    let mut reject_reason = ExplainConstraintEvaluationOutcomeReason::NotFound;
     let mut created_trace_event = false;
+    // Sub-queries of evaluated values are pushed as frames, nested machines carry their own depth
+    let depth = self.depth + self.stack.last().map_or(0, |frame| frame.sub_query_depth);
+    let depth_exceeded = depth >= self.max_depth;
 
     if self.log_instructions {
       println!(
@@ -246,8 +337,21 @@ impl<'a> AbstractMachine<'a> {
       UnificationInstruction::AllocateFrame { size } => {
         self.variable_bank.push_checkpoint();
 
-        let new_frame =
-          SearchFrame::new_static(self.database, instruction_index + 1, self.stack.is_empty());
+        let new_frame = SearchFrame::new_static(
+          self.database.iter_naive_facts().into(),
+          instruction_index + 1,
+          self.stack.is_empty(),
+        );
+        self.stack.push(new_frame);
+      }
+      UnificationInstruction::AllocateSubjectFrame { subject } => {
+        self.variable_bank.push_checkpoint();
+
+        let new_frame = SearchFrame::new_static(
+          self.database.iter_facts_by_subject(subject),
+          instruction_index + 1,
+          self.stack.is_empty(),
+        );
         self.stack.push(new_frame);
       }
       UnificationInstruction::AllocateFact {
@@ -308,6 +412,9 @@ impl<'a> AbstractMachine<'a> {
           !fact.subject.evaluated && self.variable_bank.unify(*variable, &fact.subject.subject);
 
         if direct_match {
+        } else if fact.subject.evaluated && self.follow_evaluated_subjects && depth_exceeded {
+          reset_frame = true;
+          reject_reason = ExplainConstraintEvaluationOutcomeReason::MaxDepthExceeded;
         } else if fact.subject.evaluated && self.follow_evaluated_subjects {
           let mut machine = self.query_engine.query(
             self.database,
@@ -320,6 +427,9 @@ impl<'a> AbstractMachine<'a> {
             self.variable_bank.clone(),
             self.variable_allocator.clone(),
           );
+          machine.limits = self.limits.clone();
+          machine.depth = depth + 1;
+          machine.max_depth = self.max_depth;
           machine.follow_evaluated_subjects = false;
 
           if self.variable_bank.get(*variable).is_none() {
@@ -415,6 +525,9 @@ impl<'a> AbstractMachine<'a> {
         let direct_match = match_subject(subject, &fact.subject.subject);
 
         if direct_match {
+        } else if fact.subject.evaluated && self.follow_evaluated_subjects && depth_exceeded {
+          reset_frame = true;
+          reject_reason = ExplainConstraintEvaluationOutcomeReason::MaxDepthExceeded;
         } else if fact.subject.evaluated && self.follow_evaluated_subjects {
           let checkpoint_id = self.variable_bank.push_checkpoint();
 
@@ -429,6 +542,9 @@ impl<'a> AbstractMachine<'a> {
             self.variable_bank.clone(),
             self.variable_allocator.clone(),
           );
+          machine.limits = self.limits.clone();
+          machine.depth = depth + 1;
+          machine.max_depth = self.max_depth;
           machine.follow_evaluated_subjects = self.follow_evaluated_subjects;
 
           let evalutes_to_expected_subject = machine.any(|inner_fact| {
@@ -488,6 +604,97 @@ impl<'a> AbstractMachine<'a> {
           }
         }
       }
+      UnificationInstruction::CheckValueNot { value, property } => {
+        let frame = self.stack.last_mut().unwrap();
+        let fact = frame.current_investigated_fact.as_ref().unwrap().fact;
+
+        tracing_constraint_check!(
+          self,
+          frame,
+          created_trace_event,
+          Value,
+          Some(value.clone()),
+          fact.value.subject,
+          EvaluationType::Check,
+          System::CORE_OPERATOR_NOT
+        );
+
+        let matches_value = |selector: &SubjectSelector| {
+          match_subject(value, &System::CORE_WILDCARD_SUBJECT)
+            || (match_subject(value, &selector.subject)
+              && match (&selector.property, property) {
+                (None, None) => true,
+                (Some(a), Some(b)) => match_subject(a, b),
+                _ => false,
+              })
+        };
+
+        if matches_value(&fact.value) {
+          reset_frame = true;
+        } else if fact.value.evaluated || fact.value.property.is_some() {
+          if depth_exceeded {
+            // Without knowing the value it can't be told apart from the excluded one
+            reset_frame = true;
+            reject_reason = ExplainConstraintEvaluationOutcomeReason::MaxDepthExceeded;
+          } else {
+            // Unlike `CheckValue` the referenced value is resolved right away, the constraint
+            // passes if none of the facts it resolves to holds the excluded value.
+            let checkpoint_id = self.variable_bank.push_checkpoint();
+            let mut machine = self.query_engine.query(
+              self.database,
+              &Query {
+                subject: fact.value.subject.clone(),
+                evaluated: fact.value.evaluated,
+                meta: None,
+                property: fact.value.property.clone(),
+              },
+              self.variable_bank.clone(),
+              self.variable_allocator.clone(),
+            );
+            machine.limits = self.limits.clone();
+            machine.depth = depth + 1;
+            machine.max_depth = self.max_depth;
+            machine.follow_evaluated_subjects = self.follow_evaluated_subjects;
+
+            reset_frame = machine.any(|inner_fact| matches_value(&inner_fact.fact.value));
+            self.variable_bank.truncate_checkpoint(checkpoint_id);
+          }
+        }
+      }
+      UnificationInstruction::CheckValueOrdered { value, operator } => {
+        let frame = self.stack.last_mut().unwrap();
+        let fact = frame.current_investigated_fact.as_ref().unwrap().fact;
+
+        tracing_constraint_check!(
+          self,
+          frame,
+          created_trace_event,
+          Value,
+          Some(value.clone()),
+          fact.value.subject,
+          EvaluationType::Check,
+          operator
+        );
+
+        // Referenced values aren't resolved, only plain numbers can be ordered
+        let ordering = if fact.value.evaluated || fact.value.property.is_some() {
+          None
+        } else {
+          compare_numeric(&fact.value.subject, value)
+        };
+
+        let accepted = match ordering {
+          Some(ordering) if match_subject(operator, &System::CORE_OPERATOR_LT) => ordering.is_lt(),
+          Some(ordering) if match_subject(operator, &System::CORE_OPERATOR_GT) => ordering.is_gt(),
+          Some(ordering) if match_subject(operator, &System::CORE_OPERATOR_LE) => ordering.is_le(),
+          Some(ordering) if match_subject(operator, &System::CORE_OPERATOR_GE) => ordering.is_ge(),
+          _ => false,
+        };
+
+        if !accepted {
+          reset_frame = true;
+        }
+      }
       UnificationInstruction::CheckValue { value, property } => {
         let frame = self.stack.last_mut().unwrap();
         let fact = frame.current_investigated_fact.as_ref().unwrap().fact;
@@ -511,6 +718,22 @@ impl<'a> AbstractMachine<'a> {
 
         // Simple case, we have a direct match of the value as well as property
         if direct_match && property_match {
+        } else if (fact.value.evaluated || fact.value.property.is_some()) && depth_exceeded {
+          if let Some(constraint) = frame.tracing {
+            self
+              .explain_result
+              .fact_events
+              .push(ExplainFactEvent::EvaluatingSubQuery {
+                constraint_id: constraint,
+                target_query: fact.value.subject.clone(),
+                target: value.clone(),
+                outcome: ExplainConstraintEvaluationOutcome::Rejected(
+                  ExplainConstraintEvaluationOutcomeReason::MaxDepthExceeded,
+                ),
+              });
+          }
+          reset_frame = true;
+          reject_reason = ExplainConstraintEvaluationOutcomeReason::MaxDepthExceeded;
         } else if fact.value.evaluated || fact.value.property.is_some() {
           let mut machine = self.query_engine.query(
             self.database,
@@ -523,13 +746,40 @@ impl<'a> AbstractMachine<'a> {
             self.variable_bank.clone(),
             self.variable_allocator.clone(),
           );
+          machine.limits = self.limits.clone();
+          machine.depth = depth + 1;
+          machine.max_depth = self.max_depth;
           machine.follow_evaluated_subjects = self.follow_evaluated_subjects;
 
-          println!(
-            "Executing sub-query for fact: {}",
-            System::get_human_readable_fact(self.database, fact)
-          );
-          let new_frame = SearchFrame::new_sub_query(machine, instruction_index, false);
+          if self.log_instructions {
+            println!(
+              "Executing sub-query for fact: {}",
+              System::get_human_readable_fact(self.database, fact)
+            );
+          }
+          let mut new_frame = SearchFrame::new_sub_query(machine, instruction_index, false);
+          new_frame.sub_query_depth = frame.sub_query_depth + 1;
+          if let Some(constraint) = frame.tracing {
+            // A sub-query without results is dropped without ever checking the value, so it can
+            // be rejected right away. Otherwise the sub-query frame rejects it on reset.
+            let outcome = if new_frame.current_investigated_fact.is_none() {
+              ExplainConstraintEvaluationOutcome::Rejected(
+                ExplainConstraintEvaluationOutcomeReason::NotFound,
+              )
+            } else {
+              new_frame.waiting_for_subquery_trace = true;
+              ExplainConstraintEvaluationOutcome::Passed
+            };
+            self
+              .explain_result
+              .fact_events
+              .push(ExplainFactEvent::EvaluatingSubQuery {
+                constraint_id: constraint,
+                target_query: fact.value.subject.clone(),
+                target: value.clone(),
+                outcome,
+              });
+          }
           self.stack.push(new_frame);
         } else {
           reset_frame = true;
@@ -553,12 +803,14 @@ impl<'a> AbstractMachine<'a> {
           reset_frame = true;
         }
       }
-      UnificationInstruction::CheckMeta { skip_system } => {
+      UnificationInstruction::CheckMeta { skip_system, meta } => {
         let frame = self.stack.last_mut().unwrap();
         let fact = frame.current_investigated_fact.as_ref().unwrap().fact;
 
         if *skip_system && match_subject(&fact.meta, &System::CORE_META) {
           reset_frame = true;
+        } else if let Some(meta) = meta {
+          reset_frame = !match_subject(&fact.meta, meta);
         }
       }
       UnificationInstruction::SkipSubject { subject } => {
@@ -600,6 +852,17 @@ impl<'a> AbstractMachine<'a> {
             });
         }
       }
+      UnificationInstruction::TraceAnyFact { constraint } => {
+        let frame = self.stack.last_mut().unwrap();
+        frame.tracing = Some(*constraint);
+        self
+          .explain_result
+          .fact_events
+          .push(ExplainFactEvent::EvaluatingExpectedFact {
+            constraint_id: *constraint,
+            fact_id: frame.current_investigated_fact.as_ref().unwrap().fact_index,
+          });
+      }
       UnificationInstruction::TraceSubQuery { query, variable } => {
         let frame = self.stack.last_mut().unwrap();
         if let Some(constraint) = frame.tracing {
@@ -635,20 +898,37 @@ impl<'a> AbstractMachine<'a> {
               fact_id: *fact_index,
               subject_variable: yielded.subject_binding.clone(),
             });
+
+          // Sub-queries leading up to the yield passed, backtracking later must not reject them
+          for frame in &mut self.stack {
+            frame.waiting_for_subquery_trace = false;
+          }
         }
       }
     }
 
     self.capture_explain_variables();
 
+    // Nested sub-query frames aren't traced, so running out of depth below a traced sub-query
+    // rejects the sub-query which is still waiting for its outcome.
+    let depth_exceeded_in_sub_query = reset_frame
+      && matches!(
+        reject_reason,
+        ExplainConstraintEvaluationOutcomeReason::MaxDepthExceeded
+      )
+      && self
+        .stack
+        .iter()
+        .any(|frame| frame.waiting_for_subquery_trace);
+
     if let Some(frame) = self.stack.last_mut() {
       if reset_frame {
         if let Some(last_event) = self.explain_result.fact_events.last_mut()
-          && (created_trace_event || frame.waiting_for_subquery_trace)
+          && (created_trace_event
+            || frame.waiting_for_subquery_trace
+            || depth_exceeded_in_sub_query)
         {
-          last_event.update_outcome(ExplainConstraintEvaluationOutcome::Rejected(
-            ExplainConstraintEvaluationOutcomeReason::NotFound,
-          ));
+          last_event.update_outcome(ExplainConstraintEvaluationOutcome::Rejected(reject_reason));
           frame.waiting_for_subquery_trace = false;
         }
 
@@ -691,32 +971,73 @@ impl<'a> AbstractMachine<'a> {
 
   fn capture_explain_variables(&mut self) {
     if self.explain_enabled {
+      // Values which changed during this step were bound while investigating the current fact
+      let source = self.stack.last().and_then(|frame| {
+        frame
+          .current_investigated_fact
+          .as_ref()
+          .map(|fact| ExplainVariableSource {
+            constraint_id: frame.tracing,
+            fact_index: fact.fact_index,
+          })
+      });
+
       let subject_map = self.variable_allocator.get_subject_map();
       for (subject_id, variable) in subject_map {
         let subject_name =
-          System::get_subject_name(self.database, &Subject::Static { uuid: subject_id });
+          System::get_subject_name(self.database, &Subject::Static { uuid: subject_id })
+            .unwrap_or_else(|| "-".to_string());
         if let Some(variable_value) = self.variable_bank.get(variable) {
-          self.explain_result.variables.insert(
-            subject_name.unwrap_or_else(|| "-".to_string()),
-            variable_value,
-          );
+          let changed = self
+            .explain_result
+            .variables
+            .get(&subject_name)
+            .is_none_or(|previous| !match_subject(previous, &variable_value));
+          if changed {
+            match &source {
+              Some(source) => {
+                self
+                  .explain_result
+                  .variable_sources
+                  .insert(subject_name.clone(), source.clone());
+              }
+              None => {
+                self.explain_result.variable_sources.remove(&subject_name);
+              }
+            }
+          }
+
+          self
+            .explain_result
+            .variables
+            .insert(subject_name, variable_value);
         }
       }
     }
   }
 
   pub fn execute_until_yield(&mut self) -> Option<FoundFact<'a>> {
-    if let Some(fact) = self.yielded.pop_front() {
-      return Some(fact);
+    let mut fact = self.yielded.pop_front();
+    while fact.is_none() && self.step() {
+      fact = self.yielded.pop_front();
     }
 
-    while self.step() {
-      if let Some(fact) = self.yielded.pop_front() {
-        return Some(fact);
+    if self
+      .max_yields
+      .is_some_and(|max_yields| self.yield_count >= max_yields)
+    {
+      // Only a result beyond the limit makes the yielded facts partial, it is kept back
+      if let Some(fact) = fact {
+        self.limits.limit_reached.set(true);
+        self.yielded.push_front(fact);
       }
+      return None;
     }
 
-    None
+    if fact.is_some() {
+      self.yield_count += 1;
+    }
+    fact
   }
 }
 
@@ -739,15 +1060,15 @@ pub(crate) struct SearchFrame<'a> {
   debug: Option<Subject>,
   continue_marker: bool,
   waiting_for_subquery_trace: bool,
+  sub_query_depth: usize,
 }
 
 impl<'a> SearchFrame<'a> {
   pub fn new_static(
-    database: &'a Database,
+    mut iterator: FactIterator<'a>,
     start_instruction_index: usize,
     continue_marker: bool,
   ) -> Self {
-    let mut iterator = database.iter_naive_facts();
     let current_investigated_fact = iterator.next().map(|(fact_index, fact)| FoundFact {
       fact,
       fact_index,
@@ -758,6 +1079,7 @@ impl<'a> SearchFrame<'a> {
       tracing: None,
       start_instruction_index,
       waiting_for_subquery_trace: false,
+      sub_query_depth: 0,
       current_instruction_index: start_instruction_index,
       state: FrameState::Static { iterator },
       maybe_yielded: Vec::new(),
@@ -781,6 +1103,7 @@ impl<'a> SearchFrame<'a> {
       tracing: None,
       start_instruction_index,
       waiting_for_subquery_trace: false,
+      sub_query_depth: 0,
       continue_marker,
       debug: None,
       current_instruction_index: start_instruction_index,
@@ -812,6 +1135,7 @@ impl<'a> SearchFrame<'a> {
     let mut me = Self {
       tracing: None,
       waiting_for_subquery_trace: false,
+      sub_query_depth: 0,
       continue_marker,
       start_instruction_index,
       current_instruction_index: start_instruction_index,
@@ -838,6 +1162,7 @@ impl<'a> SearchFrame<'a> {
     Self {
       tracing: None,
       waiting_for_subquery_trace: false,
+      sub_query_depth: 0,
       continue_marker,
       start_instruction_index,
       current_instruction_index: start_instruction_index,
@@ -852,7 +1177,7 @@ impl<'a> SearchFrame<'a> {
 enum FrameState<'a> {
   Constant,
   Static {
-    iterator: NaiveFactIterator<'a>,
+    iterator: FactIterator<'a>,
   },
   SubQuery {
     machine: AbstractMachine<'a>,
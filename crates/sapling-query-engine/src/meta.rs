@@ -1,11 +1,16 @@
+use sapling_data_model::Subject;
+
 pub struct QueryMeta {
   pub include_system_meta: bool,
+  /// Only facts with exactly this meta match
+  pub required_meta: Option<Subject>,
 }
 
 impl Default for QueryMeta {
   fn default() -> Self {
     Self {
       include_system_meta: false,
+      required_meta: None,
     }
   }
 }
@@ -94,7 +94,9 @@ impl QueryEngine {
     if !query.evaluated {
       let mut instructions = vec![];
 
-      instructions.push(UnificationInstruction::AllocateFrame { size: 0 });
+      instructions.push(UnificationInstruction::AllocateSubjectFrame {
+        subject: query.subject.clone(),
+      });
       instructions.push(UnificationInstruction::CheckSubject {
         subject: query.subject.clone(),
       });
@@ -109,8 +111,11 @@ impl QueryEngine {
         }
       }
 
-      if !meta.include_system_meta {
-        instructions.push(UnificationInstruction::CheckMeta { skip_system: true });
+      if !meta.include_system_meta || meta.required_meta.is_some() {
+        instructions.push(UnificationInstruction::CheckMeta {
+          skip_system: !meta.include_system_meta,
+          meta: meta.required_meta.clone(),
+        });
       }
 
       if yield_facts {
@@ -229,6 +234,10 @@ impl QueryEngine {
             fact: *expected_fact_index,
             constraint: query_fact_index,
           });
+        } else if explain.trace_all {
+          instructions.push(UnificationInstruction::TraceAnyFact {
+            constraint: query_fact_index,
+          });
         }
       }
 
@@ -286,6 +295,16 @@ impl QueryEngine {
           variable,
         });
         instructions.extend(sub_fact_instructions);
+      } else if match_subject(&query_fact.operator, &System::CORE_OPERATOR_NOT) {
+        instructions.push(UnificationInstruction::CheckValueNot {
+          value: query_fact.value.subject.clone(),
+          property: query_fact.value.property.clone(),
+        });
+      } else if System::is_ordered_operator(&query_fact.operator) {
+        instructions.push(UnificationInstruction::CheckValueOrdered {
+          value: query_fact.value.subject.clone(),
+          operator: query_fact.operator.clone(),
+        });
       } else if !match_subject(&query_fact.value.subject, &System::CORE_WILDCARD_SUBJECT) {
         instructions.push(UnificationInstruction::CheckValue {
           value: query_fact.value.subject.clone(),
@@ -334,6 +353,21 @@ impl QueryEngine {
     AbstractMachine::new(instructions, database, self.clone(), bank, allocator)
   }
 
+  /// Like [`QueryEngine::query`] but stops after `max_yields` facts. Use
+  /// [`AbstractMachine::with_step_budget`] to also bound searches which never yield.
+  pub fn query_with_limit<'a>(
+    &self,
+    database: &'a Database,
+    query: &Query,
+    bank: SharedVariableBank,
+    allocator: SharedVariableAllocator,
+    max_yields: usize,
+  ) -> AbstractMachine<'a> {
+    self
+      .query(database, query, bank, allocator)
+      .with_max_yields(max_yields)
+  }
+
   fn explain_raw(
     &self,
     database: &Database,
@@ -349,7 +383,7 @@ impl QueryEngine {
       &Query {
         evaluated: true,
         meta: None,
-        property: None,
+        property: explain.property.clone(),
         subject: explain.query_subject.clone(),
       },
       true,
@@ -365,7 +399,17 @@ impl QueryEngine {
     //machine.log_instructions = true;
     while machine.next().is_some() {}
 
-    machine.explain_result
+    let mut result = machine.explain_result;
+    result.failures = [
+      Some(&explain.query_subject),
+      explain.target_subject.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|subject| database.get_failure(subject))
+    .cloned()
+    .collect();
+    result
   }
 
   pub fn explain(
@@ -375,14 +419,32 @@ impl QueryEngine {
     bank: SharedVariableBank,
     allocator: SharedVariableAllocator,
   ) -> ExplainResult {
-    let target_facts = database.get_facts_for_subject(
-      explain_subject,
-      &QueryMeta {
-        include_system_meta: false,
+    self.explain_query(
+      database,
+      &Query {
+        subject: explain_subject.clone(),
+        property: None,
+        meta: None,
+        evaluated: false,
       },
-      true,
-      &[],
-    );
+      bank,
+      allocator,
+    )
+  }
+
+  /// Like [`QueryEngine::explain`], but only facts with the query's property are yielded, so the
+  /// trace shows why a property-qualified query did or did not produce a fact. The query's
+  /// subject is the explain subject.
+  pub fn explain_query(
+    &self,
+    database: &Database,
+    query: &Query,
+    bank: SharedVariableBank,
+    allocator: SharedVariableAllocator,
+  ) -> ExplainResult {
+    let explain_subject = &query.subject;
+    let target_facts =
+      database.get_facts_for_subject(explain_subject, &QueryMeta::default(), true, &[]);
 
     let mut target_subject = None;
     let mut query_subject = None;
@@ -413,10 +475,32 @@ impl QueryEngine {
       facts,
       query_subject: query_subject.unwrap(),
       target_subject,
+      property: query.property.clone(),
+      trace_all: false,
     };
 
     self.explain_raw(database, &query, bank, allocator)
   }
+
+  /// Explains an evaluated query directly, without an explain subject describing it. There is no
+  /// target subject, every fact the constraints investigate is traced.
+  pub fn explain_rule(
+    &self,
+    database: &Database,
+    query: &Query,
+    bank: SharedVariableBank,
+    allocator: SharedVariableAllocator,
+  ) -> ExplainResult {
+    let explain = ExplainQuery {
+      facts: HashMap::new(),
+      query_subject: query.subject.clone(),
+      target_subject: None,
+      property: query.property.clone(),
+      trace_all: true,
+    };
+
+    self.explain_raw(database, &explain, bank, allocator)
+  }
 }
 
 #[derive(Debug)]
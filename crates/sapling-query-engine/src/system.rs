@@ -3,7 +3,7 @@ use std::{
   sync::{LazyLock, Mutex},
 };
 
-use sapling_data_model::{Fact, Subject, SubjectSelector};
+use sapling_data_model::{Fact, Query, Subject, SubjectSelector};
 
 use crate::{Database, database::match_subject};
 
@@ -23,6 +23,13 @@ impl System {
   pub const CORE_INTEGER_PROPERTY: Subject = Subject::Static { uuid: 7 };
   pub const CORE_QUERY_TARGET: Subject = Subject::Static { uuid: 8 };
   pub const CORE_SERIALIZATION_SOURCE: Subject = Subject::Static { uuid: 9 };
+  /// Constraint operator which only matches facts whose value is *not* the constraint's value.
+  pub const CORE_OPERATOR_NOT: Subject = Subject::Static { uuid: 10 };
+  /// Ordered constraint operators, only integer and float values can be compared with them.
+  pub const CORE_OPERATOR_LT: Subject = Subject::Static { uuid: 11 };
+  pub const CORE_OPERATOR_GT: Subject = Subject::Static { uuid: 12 };
+  pub const CORE_OPERATOR_LE: Subject = Subject::Static { uuid: 13 };
+  pub const CORE_OPERATOR_GE: Subject = Subject::Static { uuid: 14 };
 
   pub(crate) fn install(database: &mut Database) {
     Self::add_core_subject(database, "Core Metadata");
@@ -35,6 +42,23 @@ impl System {
     Self::add_core_subject(database, "SystemIntegerProperty");
     Self::add_core_subject(database, "SystemQueryTarget");
     Self::add_core_subject(database, "SystemSerializationSource");
+    Self::add_core_subject(database, "!=");
+    Self::add_core_subject(database, "<");
+    Self::add_core_subject(database, ">");
+    Self::add_core_subject(database, "<=");
+    Self::add_core_subject(database, ">=");
+  }
+
+  /// Whether the operator compares values by their numeric order.
+  pub fn is_ordered_operator(operator: &Subject) -> bool {
+    [
+      Self::CORE_OPERATOR_LT,
+      Self::CORE_OPERATOR_GT,
+      Self::CORE_OPERATOR_LE,
+      Self::CORE_OPERATOR_GE,
+    ]
+    .iter()
+    .any(|ordered| match_subject(operator, ordered))
   }
 
   pub fn get_named_subject(name: &str) -> Option<Subject> {
@@ -44,6 +68,7 @@ impl System {
 
   pub fn get_subject_name(database: &Database, subject: &Subject) -> Option<String> {
     match subject {
+      Subject::Boolean { value } => return Some(value.to_string()),
       Subject::Integer { value } => return Some(value.to_string()),
       Subject::Float { value } => return Some(value.to_string()),
       _ => {}
@@ -99,13 +124,61 @@ impl System {
         Subject::String { value } => value.clone(),
         Subject::Float { value } => value.to_string(),
         Subject::Integer { value } => value.to_string(),
+        Subject::Boolean { value } => value.to_string(),
         Subject::Static { .. } =>
           Self::get_subject_name(database, &fact.value.subject).unwrap_or_default(),
-        _ => "???".to_string(),
       }
     )
   }
 
+  /// Formats a query as `subject/property`, evaluated subjects are prefixed with `?`. Subjects
+  /// are written the way the spec files write them, see [`System::format_subject`].
+  pub fn get_human_readable_query(database: &Database, query: &Query) -> String {
+    format!(
+      "{}{}{}",
+      if query.evaluated { "?" } else { "" },
+      Self::format_subject(&query.subject, |subject| Self::get_subject_name(
+        database, subject
+      )),
+      match &query.property {
+        Some(property) => format!(
+          "/{}",
+          Self::format_subject(property, |subject| Self::get_subject_name(
+            database, subject
+          ))
+        ),
+        None => "".to_string(),
+      }
+    )
+  }
+
+  /// Formats a subject as it is written in spec files: strings are quoted, floats always keep a
+  /// decimal point and static subjects without a name fall back to `static_<uuid>`.
+  pub fn format_subject(
+    subject: &Subject,
+    name_of: impl FnOnce(&Subject) -> Option<String>,
+  ) -> String {
+    match subject {
+      Subject::Static { uuid } => name_of(subject).unwrap_or_else(|| format!("static_{}", uuid)),
+      Subject::Boolean { value } => value.to_string(),
+      Subject::Integer { value } => value.to_string(),
+      Subject::Float { value } => Self::format_float(*value),
+      Subject::String { value } => format!("\"{}\"", value),
+    }
+  }
+
+  /// Formats floats as the shortest representation that parses back to the same value, always
+  /// keeping a decimal point so they can't be confused with integers (`2.0` instead of `2`).
+  /// Exponent notation is never used as the spec grammar doesn't support it.
+  pub fn format_float(value: f64) -> String {
+    let formatted = value.to_string();
+    if value.is_finite() && !formatted.contains('.') {
+      format!("{}.0", formatted)
+    } else {
+      formatted
+    }
+  }
+
   pub(crate) fn add_core_subject(database: &mut Database, name: &'static str) -> Subject {
     let subject = database.new_static_subject();
 
@@ -139,3 +212,80 @@ impl System {
     subject
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use sapling_data_model::{Query, Subject};
+
+  use crate::{Database, System};
+
+  fn query(subject: Subject, property: Option<Subject>, evaluated: bool) -> Query {
+    Query {
+      subject,
+      property,
+      meta: None,
+      evaluated,
+    }
+  }
+
+  #[test]
+  fn test_human_readable_query() {
+    let mut database = Database::new();
+    let person = System::new_named_static(&mut database, "person");
+    let age = System::new_named_static(&mut database, "age");
+
+    assert_eq!(
+      System::get_human_readable_query(&database, &query(person.clone(), None, false)),
+      "person"
+    );
+    assert_eq!(
+      System::get_human_readable_query(&database, &query(person, Some(age.clone()), true)),
+      "?person/age"
+    );
+    assert_eq!(
+      System::get_human_readable_query(
+        &database,
+        &query(Subject::Integer { value: 42 }, Some(age.clone()), false)
+      ),
+      "42/age"
+    );
+    assert_eq!(
+      System::get_human_readable_query(
+        &database,
+        &query(
+          Subject::String {
+            value: "Ann".to_string()
+          },
+          Some(age.clone()),
+          false
+        )
+      ),
+      "\"Ann\"/age"
+    );
+    assert_eq!(
+      System::get_human_readable_query(
+        &database,
+        &query(Subject::Float { value: 2.0 }, Some(age), false)
+      ),
+      "2.0/age"
+    );
+
+    let unnamed = database.new_static_subject();
+    let Subject::Static { uuid } = unnamed else {
+      unreachable!()
+    };
+    assert_eq!(
+      System::get_human_readable_query(&database, &query(unnamed, None, false)),
+      format!("static_{}", uuid)
+    );
+  }
+
+  #[test]
+  fn test_format_float() {
+    assert_eq!(System::format_float(1.82), "1.82");
+    assert_eq!(System::format_float(2.0), "2.0");
+    assert_eq!(System::format_float(-3.0), "-3.0");
+    assert_eq!(System::format_float(0.1 + 0.2), "0.30000000000000004");
+    assert_eq!(System::format_float(1e21), "1000000000000000000000.0");
+  }
+}
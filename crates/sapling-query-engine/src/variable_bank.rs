@@ -1,4 +1,4 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, fmt::Write, rc::Rc};
 
 use sapling_data_model::Subject;
 
@@ -13,21 +13,41 @@ pub(crate) enum VariableBinding {
 pub struct VariableBank {
   variables: Vec<VariableBinding>,
   checkpoints: Vec<Checkpoint>,
+  /// Grow instead of panicking when a variable beyond the current size is written
+  growable: bool,
 }
 
+/// Initial size of a growable bank, enough for most queries to never grow.
+const GROWABLE_INITIAL_SIZE: usize = 64;
+
 #[derive(Debug)]
 pub struct Checkpoint {
   trail: Vec<usize>,
 }
 
 impl VariableBank {
-  fn new(size: usize) -> Self {
+  fn new(size: usize, growable: bool) -> Self {
     VariableBank {
       variables: vec![VariableBinding::Unbound; size],
       checkpoints: Vec::new(),
+      growable,
     }
   }
 
+  /// Makes sure `index` can be written, doubling the size of a growable bank as often as needed.
+  /// Fixed size banks are left as is and panic on the following access.
+  fn reserve(&mut self, index: usize) {
+    if !self.growable || index < self.variables.len() {
+      return;
+    }
+
+    let mut size = self.variables.len().max(1);
+    while size <= index {
+      size *= 2;
+    }
+    self.variables.resize(size, VariableBinding::Unbound);
+  }
+
   fn push_checkpoint(&mut self) -> usize {
     self.checkpoints.push(Checkpoint { trail: vec![] });
     self.checkpoints.len() - 1
@@ -55,14 +75,21 @@ impl VariableBank {
   }
 
   fn bind(&mut self, index: usize, subject: &Subject) {
+    self.reserve(index);
     self.variables[index] = VariableBinding::Bound(subject.clone());
   }
 
   fn unbind(&mut self, index: usize) {
+    self.reserve(index);
     self.variables[index] = VariableBinding::Unbound;
   }
 
   fn get(&self, index: usize) -> Option<&Subject> {
+    // Variables beyond the size of a growable bank were never bound
+    if self.growable && index >= self.variables.len() {
+      return None;
+    }
+
     match &self.variables[index] {
       VariableBinding::Unbound => None,
       VariableBinding::Bound(subject) => Some(subject),
@@ -70,6 +97,7 @@ impl VariableBank {
   }
 
   fn unify(&mut self, index: usize, subject: &Subject) -> bool {
+    self.reserve(index);
     match &self.variables[index] {
       VariableBinding::Unbound => {
         self.variables[index] = VariableBinding::Bound(subject.clone());
@@ -89,15 +117,19 @@ impl VariableBank {
     self.checkpoints.clear();
   }
 
-  fn debug_print(&self) {
-    println!("Variable Bank:");
-    println!("Checkpoints: {:#?}", self.checkpoints);
+  fn debug_string(&self) -> String {
+    let mut output = String::new();
+    writeln!(output, "Variable Bank:").unwrap();
+    writeln!(output, "Checkpoints: {:#?}", self.checkpoints).unwrap();
     for (index, binding) in self.variables.iter().enumerate() {
       match binding {
-        VariableBinding::Unbound => println!("  {}: Unbound", index),
-        VariableBinding::Bound(subject) => println!("  {}: Bound({:?})", index, subject),
+        VariableBinding::Unbound => writeln!(output, "  {}: Unbound", index).unwrap(),
+        VariableBinding::Bound(subject) => {
+          writeln!(output, "  {}: Bound({:?})", index, subject).unwrap()
+        }
       }
     }
+    output
   }
 }
 
@@ -109,7 +141,17 @@ pub struct SharedVariableBank {
 impl SharedVariableBank {
   pub fn new(size: usize) -> Self {
     SharedVariableBank {
-      instance: Rc::new(RefCell::new(VariableBank::new(size))),
+      instance: Rc::new(RefCell::new(VariableBank::new(size, false))),
+    }
+  }
+
+  /// Creates a bank which grows on demand instead of panicking once a query uses more variables
+  /// than it was sized for. Every write checks the size first and growing moves all bindings, so
+  /// prefer [`SharedVariableBank::new`] if an upper bound is known. Growing is kept across
+  /// [`SharedVariableBank::reset`], so a reused bank settles at the size of its largest query.
+  pub fn new_growable() -> Self {
+    SharedVariableBank {
+      instance: Rc::new(RefCell::new(VariableBank::new(GROWABLE_INITIAL_SIZE, true))),
     }
   }
 
@@ -146,10 +188,73 @@ impl SharedVariableBank {
   }
 
   pub fn debug_print(&self) {
-    self.instance.borrow().debug_print();
+    print!("{}", self.debug_string());
+  }
+
+  /// Same as [`SharedVariableBank::debug_print`] but returns the output, e.g. to assert on it.
+  pub fn debug_string(&self) -> String {
+    self.instance.borrow().debug_string()
   }
 
   pub fn reset(&self) {
     self.instance.borrow_mut().reset();
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use sapling_data_model::Subject;
+
+  use crate::SharedVariableBank;
+
+  #[test]
+  fn test_growable_bank_binds_beyond_initial_size() {
+    let bank = SharedVariableBank::new_growable();
+    let checkpoint = bank.push_checkpoint();
+
+    for index in 0..10_000 {
+      assert!(bank.unify(
+        index,
+        &Subject::Integer {
+          value: index as i64
+        }
+      ));
+    }
+    assert_eq!(bank.get(9_999), Some(Subject::Integer { value: 9_999 }));
+    assert_eq!(bank.get(20_000), None);
+
+    // Bindings of a grown bank are still undone by checkpoints
+    bank.truncate_checkpoint(checkpoint);
+    assert_eq!(bank.get(9_999), None);
+
+    bank.bind(30_000, &Subject::Boolean { value: true });
+    bank.reset();
+    assert_eq!(bank.get(30_000), None);
+  }
+
+  #[test]
+  fn test_debug_string() {
+    let bank = SharedVariableBank::new(3);
+    bank.unify(0, &Subject::Integer { value: 1 });
+    let checkpoint = bank.push_checkpoint();
+    bank.unify(2, &Subject::Boolean { value: true });
+    assert_eq!(
+      bank.debug_string(),
+      "Variable Bank:\n\
+       Checkpoints: [\n    Checkpoint {\n        trail: [\n            2,\n        ],\n    },\n]\n  \
+       0: Bound(Integer { value: 1 })\n  \
+       1: Unbound\n  \
+       2: Bound(Boolean { value: true })\n"
+    );
+
+    bank.truncate_checkpoint(checkpoint);
+    assert_eq!(
+      bank.debug_string(),
+      "Variable Bank:\n\
+       Checkpoints: []\n  \
+       0: Bound(Integer { value: 1 })\n  \
+       1: Unbound\n  \
+       2: Unbound\n"
+    );
+  }
+}
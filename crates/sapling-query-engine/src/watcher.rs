@@ -1,6 +1,7 @@
 use std::{
   fmt::Debug,
   hash::{DefaultHasher, Hash, Hasher},
+  ops::Range,
 };
 
 use sapling_data_model::Query;
@@ -17,11 +18,22 @@ pub trait QueryWatcher: Debug {
   );
 }
 
+/// Identifies a watcher registered with [`DatabaseWatcher::watch_polled`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WatcherId(pub usize);
+
+#[derive(Debug)]
+enum WatcherTarget {
+  Callback(Box<dyn QueryWatcher>),
+  /// Changes are collected until [`DatabaseWatcher::changed_watchers`] is called
+  Polled(WatcherId),
+}
+
 #[derive(Debug)]
 struct SingleWatcher {
   root_query: Query,
   last_hash: u64,
-  watcher: Box<dyn QueryWatcher>,
+  target: WatcherTarget,
 }
 
 impl SingleWatcher {
@@ -31,13 +43,13 @@ impl SingleWatcher {
     hasher.finish()
   }
 
-  fn new<T: QueryWatcher + 'static>(query: &Query, watcher: T) -> Self {
+  fn new(query: &Query, target: WatcherTarget) -> Self {
     let last_hash = Self::generate_result_hash(&[]);
 
     SingleWatcher {
       root_query: query.clone(),
       last_hash,
-      watcher: Box::new(watcher),
+      target,
     }
   }
 
@@ -76,72 +88,165 @@ impl SingleWatcher {
     }
   }
 
-  fn handle_new_fact(
-    &mut self,
-    database: &mut Database,
+  fn result_hash(
+    &self,
+    database: &Database,
     query_engine: &QueryEngine,
     variable_bank: SharedVariableBank,
     variable_allocator: SharedVariableAllocator,
-    _new_fact_index: usize,
-  ) {
+  ) -> u64 {
     let mut fact_ids = Vec::new();
     Self::recursive_gather_dependencies(
       database,
       query_engine,
       variable_bank.clone(),
-      variable_allocator.clone(),
+      variable_allocator,
       &self.root_query,
       &mut fact_ids,
     );
-    let hash = Self::generate_result_hash(&fact_ids);
+    variable_bank.truncate_checkpoint(0);
+    Self::generate_result_hash(&fact_ids)
+  }
 
-    if hash != self.last_hash {
-      self.watcher.on_change(
-        database,
-        query_engine,
-        variable_bank.clone(),
-        variable_allocator.clone(),
-      );
-      self.last_hash = hash;
+  /// Re-runs the root query and notifies the watcher if the matched facts changed. Returns the id
+  /// of a polled watcher whose result changed.
+  fn refresh(
+    &mut self,
+    database: &mut Database,
+    query_engine: &QueryEngine,
+    variable_bank: SharedVariableBank,
+    variable_allocator: SharedVariableAllocator,
+  ) -> Option<WatcherId> {
+    // The variables only live until the hash is computed, a private allocator keeps them from
+    // piling up in the shared one with every watcher and every new fact
+    let hash = self.result_hash(
+      database,
+      query_engine,
+      variable_bank.clone(),
+      SharedVariableAllocator::new(),
+    );
+    if hash == self.last_hash {
+      return None;
     }
+    self.last_hash = hash;
 
-    variable_bank.truncate_checkpoint(0);
+    match &mut self.target {
+      WatcherTarget::Callback(watcher) => {
+        watcher.on_change(
+          database,
+          query_engine,
+          variable_bank.clone(),
+          variable_allocator,
+        );
+        variable_bank.truncate_checkpoint(0);
+        None
+      }
+      WatcherTarget::Polled(id) => Some(*id),
+    }
   }
 }
 
 #[derive(Debug)]
 pub struct DatabaseWatcher {
   watchers: Vec<SingleWatcher>,
+  /// Polled watchers whose result changed since the last `changed_watchers` call
+  changed: Vec<WatcherId>,
 }
 
 impl DatabaseWatcher {
   pub fn new() -> Self {
     DatabaseWatcher {
       watchers: Vec::new(),
+      changed: Vec::new(),
     }
   }
 
   pub fn watch<'a, T: QueryWatcher + 'static>(&'a mut self, query: &Query, watcher: T) {
-    let watcher = SingleWatcher::new(query, watcher);
+    let watcher = SingleWatcher::new(query, WatcherTarget::Callback(Box::new(watcher)));
     self.watchers.push(watcher);
   }
 
+  /// Watches `query` without a callback, instead `id` is reported by
+  /// [`DatabaseWatcher::changed_watchers`] once the result of the query changed. The current
+  /// result is the baseline, so matching facts which already exist don't count as a change.
+  ///
+  /// For now every watched query is recomputed for each change of the database.
+  pub fn watch_polled(
+    &mut self,
+    database: &Database,
+    query_engine: &QueryEngine,
+    variable_bank: SharedVariableBank,
+    variable_allocator: SharedVariableAllocator,
+    query: &Query,
+    id: WatcherId,
+  ) {
+    let mut watcher = SingleWatcher::new(query, WatcherTarget::Polled(id));
+    watcher.last_hash =
+      watcher.result_hash(database, query_engine, variable_bank, variable_allocator);
+    self.watchers.push(watcher);
+  }
+
+  /// Ids of the polled watchers whose result changed since the last call, in the order they first
+  /// changed.
+  pub fn changed_watchers(&mut self) -> Vec<WatcherId> {
+    std::mem::take(&mut self.changed)
+  }
+
   pub fn handle_new_fact(
     &mut self,
     database: &mut Database,
     query_engine: &QueryEngine,
     variable_bank: SharedVariableBank,
     variable_allocator: SharedVariableAllocator,
-    new_fact_index: usize,
+    _new_fact_index: usize,
+  ) {
+    self.refresh(database, query_engine, variable_bank, variable_allocator);
+  }
+
+  /// Single reconciliation pass after a batch of facts was added, watchers are notified at most
+  /// once regardless of the number of facts.
+  pub fn handle_new_facts(
+    &mut self,
+    database: &mut Database,
+    query_engine: &QueryEngine,
+    variable_bank: SharedVariableBank,
+    variable_allocator: SharedVariableAllocator,
+    _new_fact_indices: Range<usize>,
+  ) {
+    self.refresh(database, query_engine, variable_bank, variable_allocator);
+  }
+
+  /// Called after the fact at `removed_fact_index` was removed from the database. As later facts
+  /// moved down by one index, watchers matching any of them are notified as well.
+  pub fn handle_removed_fact(
+    &mut self,
+    database: &mut Database,
+    query_engine: &QueryEngine,
+    variable_bank: SharedVariableBank,
+    variable_allocator: SharedVariableAllocator,
+    _removed_fact_index: usize,
+  ) {
+    self.refresh(database, query_engine, variable_bank, variable_allocator);
+  }
+
+  fn refresh(
+    &mut self,
+    database: &mut Database,
+    query_engine: &QueryEngine,
+    variable_bank: SharedVariableBank,
+    variable_allocator: SharedVariableAllocator,
   ) {
     for watcher in &mut self.watchers {
-      watcher.handle_new_fact(
+      let changed = watcher.refresh(
         database,
         query_engine,
         variable_bank.clone(),
         variable_allocator.clone(),
-        new_fact_index,
       );
+
+      if let Some(id) = changed.filter(|id| !self.changed.contains(id)) {
+        self.changed.push(id);
+      }
     }
   }
 }
@@ -4,14 +4,16 @@ use proc_macro_error::{abort, proc_macro_error};
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
 use syn::{
-  Attribute, Data, DeriveInput, Ident, LitStr, PathArguments, Type, parse_macro_input,
-  spanned::Spanned,
+  Attribute, Data, DataEnum, DataStruct, DeriveInput, Field, Fields, Ident, Index, LitStr, Member,
+  PathArguments, Type, Variant, parse_macro_input, spanned::Spanned,
 };
 
 #[derive(Debug, Default, FromMeta)]
 struct SaplingAttr {
   rename: Option<syn::LitStr>,
   indexed: Option<bool>,
+  skip: Option<bool>,
+  meta: Option<syn::LitStr>,
 }
 
 fn sapling_attr(attrs: &[Attribute]) -> syn::Result<SaplingAttr> {
@@ -28,13 +30,37 @@ fn sapling_attr(attrs: &[Attribute]) -> syn::Result<SaplingAttr> {
     if parsed.indexed.is_some() {
       out.indexed = parsed.indexed;
     }
+    if parsed.skip.is_some() {
+      out.skip = parsed.skip;
+    }
+    if parsed.meta.is_some() {
+      out.meta = parsed.meta;
+    }
   }
 
   Ok(out)
 }
 
-fn get_property_static_ident(struct_name: &Ident, property_ident: &Ident) -> Ident {
-  format_ident!("__LAZY_{}_{}_PROPERTY", struct_name, property_ident)
+fn get_property_static_ident(struct_name: &Ident, property: &Member) -> Ident {
+  match property {
+    Member::Named(ident) => format_ident!("__LAZY_{}_{}_PROPERTY", struct_name, ident),
+    Member::Unnamed(index) => format_ident!("__LAZY_{}_{}_PROPERTY", struct_name, index.index),
+  }
+}
+
+/// Returns the member used to access the field and its default property name, tuple struct
+/// fields are named after their position.
+fn get_field_member(index: usize, field: &Field) -> (Member, LitStr) {
+  match &field.ident {
+    Some(ident) => (
+      Member::Named(ident.clone()),
+      LitStr::new(&ident.to_string(), ident.span()),
+    ),
+    None => (
+      Member::Unnamed(Index::from(index)),
+      LitStr::new(&index.to_string(), field.span()),
+    ),
+  }
 }
 
 fn get_vect_inner_type(ty: &Type) -> Type {
@@ -55,24 +81,168 @@ fn get_vect_inner_type(ty: &Type) -> Type {
   }
 }
 
-fn parse_serialization_fact_fields(input: &DeriveInput) -> (TokenStream2, TokenStream2) {
-  let Data::Struct(struc) = &input.data else {
-    abort!(input.span(), "Only structs are supported as of now");
+/// Returns the inner type if the given type is an `Option<T>`.
+fn get_option_inner_type(ty: &Type) -> Option<Type> {
+  let Type::Path(path) = ty else {
+    return None;
+  };
+  let segment = path.path.segments.last()?;
+  if segment.ident != "Option" {
+    return None;
+  }
+  let PathArguments::AngleBracketed(generic) = &segment.arguments else {
+    return None;
   };
+  match generic.args.first() {
+    Some(syn::GenericArgument::Type(generic_ty)) => Some(generic_ty.clone()),
+    _ => None,
+  }
+}
+
+/// Meta of serialized facts unless overridden with `#[sapling(meta = "...")]`.
+fn default_meta() -> LitStr {
+  LitStr::new("default", proc_macro2::Span::call_site())
+}
+
+/// Adds a fact of the serialized subject, `subject` and `context` have to be in scope.
+fn fact_creation(
+  property_selector: TokenStream2,
+  value_selector: TokenStream2,
+  meta: &LitStr,
+) -> TokenStream2 {
+  quote! {
+      let fact = Fact {
+          subject: SubjectSelector {
+              evaluated: false,
+              subject: subject.clone(),
+              property: None,
+          },
+          property: SubjectSelector {
+              #property_selector
+              evaluated: false,
+              property: None,
+          },
+          operator: System::CORE_OPERATOR_IS.clone(),
+          value: SubjectSelector {
+              #value_selector
+              evaluated: false,
+              property: None,
+          },
+          meta: Subject::String { value: #meta.into() },
+      };
+      context.add_fact(fact);
+  }
+}
+
+fn get_variant_static_ident(enum_name: &Ident) -> Ident {
+  format_ident!("__LAZY_{}_VARIANT_PROPERTY", enum_name)
+}
+
+/// Returns the name stored as the discriminant of a variant.
+fn get_variant_name(variant: &Variant) -> LitStr {
+  let attributes = sapling_attr(&variant.attrs)
+    .unwrap_or_else(|err| abort!(err.span(), "Failed to parse attributes"));
+  attributes
+    .rename
+    .unwrap_or_else(|| LitStr::new(&variant.ident.to_string(), variant.ident.span()))
+}
+
+/// Returns the payload type of a variant, `None` for unit variants.
+fn get_variant_payload_type(variant: &Variant) -> Option<Type> {
+  match &variant.fields {
+    Fields::Unit => None,
+    Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Some(fields.unnamed[0].ty.clone()),
+    _ => abort!(
+      variant.span(),
+      "Only unit and single field tuple variants are supported as of now"
+    ),
+  }
+}
+
+/// Enums are stored as their variant name in the `variant` property, the payload of a variant
+/// with a single field is stored in the `0` property like a newtype struct.
+fn parse_serialization_enum_variants(
+  input: &DeriveInput,
+  data: &DataEnum,
+) -> (TokenStream2, TokenStream2) {
+  let enum_ident = &input.ident;
+  let variant_static = get_variant_static_ident(enum_ident);
+  let payload_static = get_property_static_ident(enum_ident, &Member::Unnamed(Index::from(0)));
+
+  let discriminant_creation = fact_creation(
+    quote! { subject: variant_property.clone(), },
+    quote! { subject: Subject::String { value: discriminant.into() }, },
+    &default_meta(),
+  );
+
+  let mut variants = vec![];
+  for variant in &data.variants {
+    let ident = &variant.ident;
+    let name = get_variant_name(variant);
+
+    match get_variant_payload_type(variant) {
+      None => variants.push(quote! {
+          Self::#ident => {
+              let discriminant = #name;
+              #discriminant_creation
+          }
+      }),
+      Some(ty) => {
+        let payload_creation = fact_creation(
+          quote! { subject: property_subject.clone(), },
+          quote! {
+              subject: <#ty as sapling_serialization::SaplingSerializable::<TSerializeContext>>::serialize_to_facts(value, context, #name),
+          },
+          &default_meta(),
+        );
+        variants.push(quote! {
+            Self::#ident(value) => {
+                let discriminant = #name;
+                #discriminant_creation
+                let property_subject = #payload_static.get_or_init(|| context.new_static_subject("0"));
+                #payload_creation
+            }
+        });
+      }
+    }
+  }
 
+  (
+    quote! {
+      let variant_property = #variant_static.get_or_init(|| context.new_static_subject("variant"));
+      match self {
+        #(#variants)*
+      }
+    },
+    quote! {
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals)]
+        static #variant_static: std::sync::OnceLock<sapling_data_model::Subject> = std::sync::OnceLock::new();
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals)]
+        static #payload_static: std::sync::OnceLock<sapling_data_model::Subject> = std::sync::OnceLock::new();
+    },
+  )
+}
+
+fn parse_serialization_fact_fields(
+  input: &DeriveInput,
+  struc: &DataStruct,
+) -> (TokenStream2, TokenStream2) {
   let mut fields = vec![];
   let mut global_fields = vec![];
 
-  for field in struc.fields.iter() {
-    let ident = field.ident.clone().unwrap();
-    let mut name = LitStr::new(&ident.to_string(), ident.span());
-    let real_name = field.ident.as_ref().unwrap().clone();
+  for (index, field) in struc.fields.iter().enumerate() {
+    let (real_name, mut name) = get_field_member(index, field);
     let mut ty = field.ty.clone();
     let mut indexed = false;
 
     let attributes = sapling_attr(&field.attrs)
       .unwrap_or_else(|err| abort!(err.span(), "Failed to parse attributes"));
 
+    if attributes.skip == Some(true) {
+      continue;
+    }
     if let Some(rename) = attributes.rename {
       name = rename;
     }
@@ -80,8 +250,17 @@ fn parse_serialization_fact_fields(input: &DeriveInput) -> (TokenStream2, TokenS
       indexed = attr_indexed;
       ty = get_vect_inner_type(&ty);
     }
+    let optional_inner_ty = if indexed {
+      None
+    } else {
+      get_option_inner_type(&ty)
+    };
+    let optional = optional_inner_ty.is_some();
+    if let Some(inner_ty) = optional_inner_ty {
+      ty = inner_ty;
+    }
 
-    let static_property = get_property_static_ident(&input.ident, field.ident.as_ref().unwrap());
+    let static_property = get_property_static_ident(&input.ident, &real_name);
 
     global_fields.push(quote! {
         #[doc(hidden)]
@@ -99,39 +278,20 @@ fn parse_serialization_fact_fields(input: &DeriveInput) -> (TokenStream2, TokenS
       }
     };
 
-    let value_selector = if indexed {
-      quote! {
-          subject: <#ty as sapling_serialization::SaplingSerializable::<TSerializeContext>>::serialize_to_facts(&self.#real_name[index], context, stringify!(#real_name)),
-      }
+    let value = if indexed {
+      quote! { &self.#real_name[index] }
+    } else if optional {
+      quote! { value }
     } else {
-      quote! {
-          subject: <#ty as sapling_serialization::SaplingSerializable::<TSerializeContext>>::serialize_to_facts(&self.#real_name, context, stringify!(#real_name)),
-      }
+      quote! { &self.#real_name }
     };
-
-    let fact_creation = quote! {
-        let fact = Fact {
-            subject: SubjectSelector {
-                evaluated: false,
-                subject: subject.clone(),
-                property: None,
-            },
-            property: SubjectSelector {
-                #property_selector
-                evaluated: false,
-                property: None,
-            },
-            operator: System::CORE_OPERATOR_IS.clone(),
-            value: SubjectSelector {
-                #value_selector
-                evaluated: false,
-                property: None,
-            },
-            meta: Subject::String { value: "default".into() },
-        };
-        context.add_fact(fact);
+    let value_selector = quote! {
+        subject: <#ty as sapling_serialization::SaplingSerializable::<TSerializeContext>>::serialize_to_facts(#value, context, stringify!(#real_name)),
     };
 
+    let meta = attributes.meta.unwrap_or_else(default_meta);
+    let fact_creation = fact_creation(property_selector, value_selector, &meta);
+
     if indexed {
       fields.push(quote! {
             {
@@ -141,6 +301,14 @@ fn parse_serialization_fact_fields(input: &DeriveInput) -> (TokenStream2, TokenS
                 }
             }
         });
+    } else if optional {
+      // `None` values are represented by the absence of the fact
+      fields.push(quote! {
+            if let Some(value) = &self.#real_name {
+                let property_subject = #static_property.get_or_init(|| context.new_static_subject(#name));
+                #fact_creation
+            }
+        });
     } else {
       fields.push(quote! {
             {
@@ -166,7 +334,14 @@ fn parse_serialization_fact_fields(input: &DeriveInput) -> (TokenStream2, TokenS
 pub fn sapling_serialization_derive(input: TokenStream) -> TokenStream {
   let input = parse_macro_input!(input as DeriveInput);
 
-  let (fields, global_fields) = parse_serialization_fact_fields(&input);
+  let (fields, global_fields) = match &input.data {
+    Data::Struct(struc) => parse_serialization_fact_fields(&input, struc),
+    Data::Enum(data) => parse_serialization_enum_variants(&input, data),
+    Data::Union(_) => abort!(
+      input.span(),
+      "Only structs and enums are supported as of now"
+    ),
+  };
 
   let ident = input.ident;
   let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
@@ -214,23 +389,133 @@ pub fn sapling_serialization_derive(input: TokenStream) -> TokenStream {
   TokenStream::from(expanded)
 }
 
-fn parse_deserialization_fact_fields(input: &DeriveInput) -> (TokenStream2, TokenStream2) {
-  let Data::Struct(struc) = &input.data else {
-    abort!(input.span(), "Only structs are supported as of now");
-  };
+fn parse_deserialization_enum_variants(
+  input: &DeriveInput,
+  data: &DataEnum,
+) -> (TokenStream2, TokenStream2) {
+  let enum_ident = &input.ident;
+  let variant_static = get_variant_static_ident(enum_ident);
+  let payload_static = get_property_static_ident(enum_ident, &Member::Unnamed(Index::from(0)));
+
+  let mut has_payload = false;
+  let mut variants = vec![];
+  for variant in &data.variants {
+    let ident = &variant.ident;
+    let name = get_variant_name(variant);
+
+    if get_variant_payload_type(variant).is_none() {
+      variants.push(quote! {
+          #name => Ok(Self::#ident),
+      });
+      continue;
+    }
 
+    has_payload = true;
+    variants.push(quote! {
+        #name => {
+            let property_subject = #payload_static.get_or_init(|| context.new_static_subject("0"));
+            let query = sapling_data_model::Query {
+                subject: subject.clone(),
+                evaluated: false,
+                meta: None,
+                property: Some(property_subject.clone()),
+            };
+            let value = sapling_serialization::__macro_query_deep(context, &query).map_err(|err| DeserializeError::Field {
+                field: format!("{}::{}.0", stringify!(#enum_ident), #name),
+                source: Box::new(err),
+            })?;
+            Ok(Self::#ident(value))
+        }
+    });
+  }
+
+  let mut queries = vec![quote! {
+      {
+        let property_subject = #variant_static.get_or_init(|| context.new_static_subject("variant"));
+        sapling_data_model::Query {
+            subject: subject.clone(),
+            evaluated: false,
+            meta: None,
+            property: Some(property_subject.clone()),
+        }
+      }
+  }];
+  if has_payload {
+    queries.push(quote! {
+        {
+          let property_subject = #payload_static.get_or_init(|| context.new_static_subject("0"));
+          sapling_data_model::Query {
+              subject: subject.clone(),
+              evaluated: false,
+              meta: None,
+              property: Some(property_subject.clone()),
+          }
+        }
+    });
+  }
+
+  (
+    quote! {
+      let variant: String = {
+          let property_subject = #variant_static.get_or_init(|| context.new_static_subject("variant"));
+          let query = sapling_data_model::Query {
+              subject: subject.clone(),
+              evaluated: false,
+              meta: None,
+              property: Some(property_subject.clone()),
+          };
+          sapling_serialization::__macro_query_deep(context, &query).map_err(|err| DeserializeError::Field {
+              field: format!("{}.variant", stringify!(#enum_ident)),
+              source: Box::new(err),
+          })?
+      };
+
+      match variant.as_str() {
+          #(#variants)*
+          _ => Err(DeserializeError::UnknownVariant {
+              enum_name: stringify!(#enum_ident).to_string(),
+              variant,
+          }),
+      }
+    },
+    quote! {
+        vec![#(#queries),*]
+    },
+  )
+}
+
+fn parse_deserialization_fact_fields(
+  input: &DeriveInput,
+  struc: &DataStruct,
+) -> (TokenStream2, TokenStream2) {
+  let struct_ident = &input.ident;
   let mut fields = vec![];
   let mut field_names = vec![];
   let mut queries = vec![];
 
-  for field in struc.fields.iter() {
-    let ident = field.ident.clone().unwrap();
-    let mut name = LitStr::new(&ident.to_string(), ident.span());
+  for (index, field) in struc.fields.iter().enumerate() {
+    let (member, mut name) = get_field_member(index, field);
+    // Property renames don't apply to error paths, they refer to the Rust field
+    let field_name = name.clone();
+    let ident = match &member {
+      Member::Named(ident) => ident.clone(),
+      Member::Unnamed(index) => format_ident!("__field{}", index.index),
+    };
     let mut indexed = false;
 
     let attributes = sapling_attr(&field.attrs)
       .unwrap_or_else(|err| abort!(err.span(), "Failed to parse attributes"));
 
+    field_names.push(ident.clone());
+
+    // Skipped fields are not stored as facts and fall back to their default value
+    if attributes.skip == Some(true) {
+      fields.push(quote! {
+        let #ident = ::core::default::Default::default();
+      });
+      continue;
+    }
+
     if let Some(rename) = attributes.rename {
       name = rename;
     }
@@ -238,16 +523,21 @@ fn parse_deserialization_fact_fields(input: &DeriveInput) -> (TokenStream2, Toke
     if let Some(attr_indexed) = attributes.indexed {
       indexed = attr_indexed;
     }
+    let optional = !indexed && get_option_inner_type(&field.ty).is_some();
+    // Facts with a custom meta are only looked up with that meta
+    let query_meta = match &attributes.meta {
+      Some(meta) => quote! { Some(sapling_data_model::Subject::String { value: #meta.into() }) },
+      None => quote! { None },
+    };
 
-    let static_property = get_property_static_ident(&input.ident, field.ident.as_ref().unwrap());
-    field_names.push(ident.clone());
+    let static_property = get_property_static_ident(&input.ident, &member);
 
     if indexed {
       queries.push(quote! {
           sapling_data_model::Query {
               subject: subject.clone(),
               evaluated: false,
-              meta: None,
+              meta: #query_meta,
               property: Some(System::CORE_INTEGER_PROPERTY.clone()),
           }
       });
@@ -259,14 +549,22 @@ fn parse_deserialization_fact_fields(input: &DeriveInput) -> (TokenStream2, Toke
                 let query = sapling_data_model::Query {
                     subject: subject.clone(),
                     evaluated: false,
-                    meta: None,
+                    meta: #query_meta,
                     property: Some(Subject::Integer { value: index }),
                 };
-                if let Ok(value) = sapling_serialization::__macro_query_deep(context, &query) {
-                    result.push(value);
-                    index += 1;
-                } else {
-                    break;
+                // The first missing index ends the list, any other error is an invalid item
+                match sapling_serialization::__macro_query_deep(context, &query) {
+                    Ok(value) => {
+                        result.push(value);
+                        index += 1;
+                    }
+                    Err(DeserializeError::MissingFact { .. }) => break,
+                    Err(err) => {
+                        return Err(DeserializeError::Field {
+                            field: format!("{}.{}[{}]", stringify!(#struct_ident), #field_name, index),
+                            source: Box::new(err),
+                        });
+                    }
                 }
             }
             result
@@ -279,33 +577,50 @@ fn parse_deserialization_fact_fields(input: &DeriveInput) -> (TokenStream2, Toke
             sapling_data_model::Query {
                 subject: subject.clone(),
                 evaluated: false,
-                meta: None,
+                meta: #query_meta,
                 property: Some(property_subject.clone()),
             }
           }
       });
+      let query_deep = if optional {
+        quote! { sapling_serialization::__macro_query_deep_optional }
+      } else {
+        quote! { sapling_serialization::__macro_query_deep }
+      };
       fields.push(quote! {
             let #ident = {
                 let property_subject = #static_property.get_or_init(|| context.new_static_subject(#name));
                 let query = sapling_data_model::Query {
                     subject: subject.clone(),
                     evaluated: false,
-                    meta: None,
+                    meta: #query_meta,
                     property: Some(property_subject.clone()),
                 };
-                sapling_serialization::__macro_query_deep(context, &query)?
+                #query_deep(context, &query).map_err(|err| DeserializeError::Field {
+                    field: format!("{}.{}", stringify!(#struct_ident), #field_name),
+                    source: Box::new(err),
+                })?
             };
         });
     }
   }
 
+  let construction = match &struc.fields {
+    Fields::Unnamed(_) => quote! {
+      Ok(Self(#(#field_names,)*))
+    },
+    _ => quote! {
+      Ok(Self {
+        #(#field_names,)*
+      })
+    },
+  };
+
   (
     quote! {
       #(#fields)*
 
-      Ok(Self {
-        #(#field_names,)*
-      })
+      #construction
     },
     quote! {
         vec![#(#queries),*]
@@ -318,7 +633,14 @@ fn parse_deserialization_fact_fields(input: &DeriveInput) -> (TokenStream2, Toke
 pub fn sapling_deserialization_derive(input: TokenStream) -> TokenStream {
   let input = parse_macro_input!(input as DeriveInput);
 
-  let (fields, queries) = parse_deserialization_fact_fields(&input);
+  let (fields, queries) = match &input.data {
+    Data::Struct(struc) => parse_deserialization_fact_fields(&input, struc),
+    Data::Enum(data) => parse_deserialization_enum_variants(&input, data),
+    Data::Union(_) => abort!(
+      input.span(),
+      "Only structs and enums are supported as of now"
+    ),
+  };
 
   let ident = input.ident;
 
@@ -340,8 +662,18 @@ pub fn sapling_deserialization_derive(input: TokenStream) -> TokenStream {
             #fields
         }
 
-        fn deserialize_all(_context: &mut T) -> Vec<Result<Self, sapling_serialization::DeserializeError>> {
-          todo!("not supported on integers")
+        fn deserialize_all(context: &mut T) -> Vec<Result<Self, sapling_serialization::DeserializeError>> {
+            use sapling_data_model::Subject;
+            use sapling_query_engine::System;
+
+            // Subjects of this type are tagged by the serialization derive
+            let crate_name: &str = env!("CARGO_PKG_NAME");
+            let source = Subject::String { value: format!("{}::{}::{}", crate_name, module_path!(), stringify!(#ident)) };
+            context
+              .find_subjects(&System::CORE_SERIALIZATION_SOURCE, &source)
+              .iter()
+              .map(|subject| Self::deserialize_subject(subject, context))
+              .collect()
         }
       }
   };
@@ -7,7 +7,8 @@ pub trait SaplingSerializable<T: SerializerContext> {
 
 pub trait SerializerContext {
   fn new_static_subject(&mut self, name: &str) -> Subject;
-  fn add_fact(&mut self, fact: Fact);
+  /// Adds the fact and returns its index, the same index `Database::add_fact` assigns.
+  fn add_fact(&mut self, fact: Fact) -> usize;
 }
 
 macro_rules! impl_serializable_integer {
@@ -46,6 +47,27 @@ macro_rules! impl_serializable_string {
 impl_serializable_string!(String);
 impl_serializable_string!(str);
 
+macro_rules! impl_serializable_float {
+  ($type:ty) => {
+    impl<T: SerializerContext> SaplingSerializable<T> for $type {
+      fn serialize_to_facts(&self, _context: &mut T, _name: &str) -> Subject {
+        Subject::Float {
+          value: *self as f64,
+        }
+      }
+    }
+  };
+}
+
+impl_serializable_float!(f32);
+impl_serializable_float!(f64);
+
+impl<T: SerializerContext> SaplingSerializable<T> for bool {
+  fn serialize_to_facts(&self, _context: &mut T, _name: &str) -> Subject {
+    Subject::Boolean { value: *self }
+  }
+}
+
 pub trait SaplingDeserializable<T: DeserializerContext>: Sized {
   fn first_level_queries(subject: &sapling_data_model::Subject, context: &mut T) -> Vec<Query>;
   fn deserialize_subject(subject: &Subject, context: &mut T) -> Result<Self, DeserializeError>;
@@ -56,6 +78,8 @@ pub trait DeserializerContext {
   fn query<'db, 'q>(&'db mut self, query: &'q Query) -> Vec<&'db Fact>;
   fn get_subject_name(&mut self, subject: &Subject) -> String;
   fn new_static_subject(&mut self, name: &str) -> Subject;
+  /// Returns all subjects which have `value` assigned to `property`.
+  fn find_subjects(&mut self, property: &Subject, value: &Subject) -> Vec<Subject>;
 }
 
 #[derive(Error, Debug)]
@@ -64,6 +88,21 @@ pub enum DeserializeError {
   InvalidType { expected: String, actual: String },
   #[error("Property '{property}' is missing for subject '{subject}'")]
   MissingFact { subject: String, property: String },
+  #[error("Unknown variant '{variant}' of enum '{enum_name}'")]
+  UnknownVariant { enum_name: String, variant: String },
+  #[error("{field}{}{source}", field_path_separator(.source))]
+  Field {
+    field: String,
+    source: Box<DeserializeError>,
+  },
+}
+
+/// Nested field errors continue the path, any other error terminates it with its message.
+fn field_path_separator(source: &DeserializeError) -> &'static str {
+  match source {
+    DeserializeError::Field { .. } => " -> ",
+    _ => ": ",
+  }
 }
 
 macro_rules! impl_deserializable_integer {
@@ -80,13 +119,13 @@ macro_rules! impl_deserializable_integer {
         subject: &Subject,
         _context: &mut T,
       ) -> Result<Self, DeserializeError> {
-        match subject {
-          Subject::Integer { value } => Ok(*value as $type),
-          _ => Err(DeserializeError::InvalidType {
+        subject
+          .as_integer()
+          .map(|value| value as $type)
+          .ok_or_else(|| DeserializeError::InvalidType {
             expected: Subject::Integer { value: 0 }.type_name().to_string(),
             actual: subject.type_name().to_string(),
-          }),
-        }
+          })
       }
 
       fn deserialize_all(_context: &mut T) -> Vec<Result<Self, DeserializeError>> {
@@ -118,17 +157,17 @@ macro_rules! impl_deserializable_string {
         subject: &Subject,
         _context: &mut T,
       ) -> Result<Self, DeserializeError> {
-        match subject {
-          Subject::String { value } => Ok(value.to_string()),
-          _ => Err(DeserializeError::InvalidType {
+        subject
+          .as_string()
+          .map(|value| value.to_string())
+          .ok_or_else(|| DeserializeError::InvalidType {
             expected: Subject::String {
               value: String::new(),
             }
             .type_name()
             .to_string(),
             actual: subject.type_name().to_string(),
-          }),
-        }
+          })
       }
 
       fn deserialize_all(_context: &mut T) -> Vec<Result<Self, DeserializeError>> {
@@ -140,6 +179,66 @@ macro_rules! impl_deserializable_string {
 
 impl_deserializable_string!(String);
 
+macro_rules! impl_deserializable_float {
+  ($type:ty) => {
+    impl<T: DeserializerContext> SaplingDeserializable<T> for $type {
+      fn first_level_queries(
+        _subject: &sapling_data_model::Subject,
+        _context: &mut T,
+      ) -> Vec<Query> {
+        vec![]
+      }
+
+      fn deserialize_subject(
+        subject: &Subject,
+        _context: &mut T,
+      ) -> Result<Self, DeserializeError> {
+        subject
+          .as_float()
+          // Whole numbers are commonly written without a fraction
+          .or_else(|| subject.as_integer().map(|value| value as f64))
+          .map(|value| value as $type)
+          .ok_or_else(|| DeserializeError::InvalidType {
+            expected: Subject::Float { value: 0.0 }.type_name().to_string(),
+            actual: subject.type_name().to_string(),
+          })
+      }
+
+      /// Floats are plain values without subjects of their own, there is nothing to collect.
+      fn deserialize_all(_context: &mut T) -> Vec<Result<Self, DeserializeError>> {
+        Vec::new()
+      }
+    }
+  };
+}
+
+impl_deserializable_float!(f32);
+impl_deserializable_float!(f64);
+
+impl<T: DeserializerContext> SaplingDeserializable<T> for bool {
+  fn first_level_queries(_subject: &sapling_data_model::Subject, _context: &mut T) -> Vec<Query> {
+    vec![]
+  }
+
+  fn deserialize_subject(subject: &Subject, _context: &mut T) -> Result<Self, DeserializeError> {
+    match subject {
+      Subject::Boolean { value } => Ok(*value),
+      // Booleans used to be stored as `0` or `1` integers
+      Subject::Integer { value: 0 } => Ok(false),
+      Subject::Integer { value: 1 } => Ok(true),
+      _ => Err(DeserializeError::InvalidType {
+        expected: "boolean".to_string(),
+        actual: subject.type_name().to_string(),
+      }),
+    }
+  }
+
+  /// Like floats, booleans have no subjects of their own to collect.
+  fn deserialize_all(_context: &mut T) -> Vec<Result<Self, DeserializeError>> {
+    Vec::new()
+  }
+}
+
 pub fn __macro_query_deep<T: DeserializerContext, TOut: SaplingDeserializable<T>>(
   context: &mut T,
   query: &Query,
@@ -172,3 +271,14 @@ pub fn __macro_query_deep<T: DeserializerContext, TOut: SaplingDeserializable<T>
 
   TOut::deserialize_subject(&first_fact.value.subject, context)
 }
+
+/// Like [`__macro_query_deep`], but a missing fact for the queried property yields `None`.
+pub fn __macro_query_deep_optional<T: DeserializerContext, TOut: SaplingDeserializable<T>>(
+  context: &mut T,
+  query: &Query,
+) -> Result<Option<TOut>, DeserializeError> {
+  if context.query(query).is_empty() {
+    return Ok(None);
+  }
+  __macro_query_deep(context, query).map(Some)
+}
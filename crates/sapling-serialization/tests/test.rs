@@ -1,12 +1,53 @@
-use sapling_data_model::Fact;
+use sapling_data_model::{Fact, Subject, SubjectSelector};
 use sapling_query_engine::{
   Database, QueryEngine, SharedVariableAllocator, SharedVariableBank, System,
 };
 use sapling_serialization::{
-  DeserializerContext, SaplingDeserializable, SaplingSerializable, SerializerContext,
+  DeserializeError, DeserializerContext, SaplingDeserializable, SaplingSerializable,
+  SerializerContext,
 };
 use sapling_serialization_macro::{SaplingDeserialization, SaplingSerialization};
 
+struct TestSerializerContext<'a> {
+  database: &'a mut Database,
+  output: Vec<Fact>,
+}
+
+impl<'a> SerializerContext for TestSerializerContext<'a> {
+  fn new_static_subject(&mut self, name: &str) -> sapling_data_model::Subject {
+    System::new_named_static(self.database, name)
+  }
+  fn add_fact(&mut self, fact: Fact) -> usize {
+    self.output.push(fact.clone());
+    self.database.add_fact(fact)
+  }
+}
+
+struct TestDeserializerContext {
+  database: Database,
+}
+
+impl DeserializerContext for TestDeserializerContext {
+  fn new_static_subject(&mut self, name: &str) -> sapling_data_model::Subject {
+    System::new_named_static(&mut self.database, name)
+  }
+  fn get_subject_name(&mut self, subject: &sapling_data_model::Subject) -> String {
+    System::get_subject_name(&self.database, subject).unwrap_or_else(|| "unknown".to_string())
+  }
+  fn find_subjects(&mut self, property: &Subject, value: &Subject) -> Vec<Subject> {
+    self.database.get_subjects_with_value(property, value)
+  }
+  fn query(&mut self, query: &sapling_data_model::Query) -> Vec<&Fact> {
+    let query_engine = QueryEngine::new();
+    let bank = SharedVariableBank::new(128);
+    let allocator = SharedVariableAllocator::new();
+    query_engine
+      .query(&self.database, query, bank, allocator)
+      .map(|fact| fact.fact)
+      .collect::<Vec<_>>()
+  }
+}
+
 #[test]
 fn test_struct_serialization() {
   #[derive(SaplingSerialization, SaplingDeserialization)]
@@ -28,21 +69,6 @@ fn test_struct_serialization() {
 
   let mut database = Database::new();
 
-  struct TestSerializerContext<'a> {
-    database: &'a mut Database,
-    output: Vec<Fact>,
-  }
-
-  impl<'a> SerializerContext for TestSerializerContext<'a> {
-    fn new_static_subject(&mut self, name: &str) -> sapling_data_model::Subject {
-      System::new_named_static(self.database, name)
-    }
-    fn add_fact(&mut self, fact: Fact) {
-      self.output.push(fact.clone());
-      self.database.add_fact(fact);
-    }
-  }
-
   let mut context = TestSerializerContext {
     database: &mut database,
     output: Vec::new(),
@@ -81,28 +107,6 @@ fn test_struct_serialization() {
     "test_name/2 = 3"
   );
 
-  struct TestDeserializerContext {
-    database: Database,
-  }
-
-  impl DeserializerContext for TestDeserializerContext {
-    fn new_static_subject(&mut self, name: &str) -> sapling_data_model::Subject {
-      System::new_named_static(&mut self.database, name)
-    }
-    fn get_subject_name(&mut self, subject: &sapling_data_model::Subject) -> String {
-      System::get_subject_name(&self.database, subject).unwrap_or_else(|| "unknown".to_string())
-    }
-    fn query(&mut self, query: &sapling_data_model::Query) -> Vec<&Fact> {
-      let query_engine = QueryEngine::new();
-      let bank = SharedVariableBank::new(128);
-      let allocator = SharedVariableAllocator::new();
-      query_engine
-        .query(&self.database, query, bank, allocator)
-        .map(|fact| fact.fact)
-        .collect::<Vec<_>>()
-    }
-  }
-
   let result =
     TestStruct::deserialize_subject(&test_subject, &mut TestDeserializerContext { database })
       .unwrap();
@@ -112,3 +116,495 @@ fn test_struct_serialization() {
   assert_eq!(result.something, 3);
   assert_eq!(result.indexed, vec![1, 2, 3]);
 }
+
+#[test]
+fn test_newtype_serialization() {
+  #[derive(SaplingSerialization, SaplingDeserialization)]
+  struct Id(i64);
+
+  let mut database = Database::new();
+  let mut context = TestSerializerContext {
+    database: &mut database,
+    output: Vec::new(),
+  };
+
+  let test_subject = Id(42).serialize_to_facts(&mut context, "id");
+
+  assert_eq!(context.output.len(), 2);
+  assert_eq!(
+    System::get_human_readable_fact(context.database, &context.output[1]),
+    "id/0 = 42"
+  );
+
+  let result =
+    Id::deserialize_subject(&test_subject, &mut TestDeserializerContext { database }).unwrap();
+  assert_eq!(result.0, 42);
+}
+
+#[test]
+fn test_tuple_struct_serialization() {
+  #[derive(SaplingSerialization, SaplingDeserialization)]
+  struct Pair(i64, #[sapling(rename = "second")] String);
+
+  let mut database = Database::new();
+  let mut context = TestSerializerContext {
+    database: &mut database,
+    output: Vec::new(),
+  };
+
+  let test_subject = Pair(1, "two".to_string()).serialize_to_facts(&mut context, "pair");
+
+  assert_eq!(context.output.len(), 3);
+  assert_eq!(
+    System::get_human_readable_fact(context.database, &context.output[1]),
+    "pair/0 = 1"
+  );
+  assert_eq!(
+    System::get_human_readable_fact(context.database, &context.output[2]),
+    "pair/second = two"
+  );
+
+  let result =
+    Pair::deserialize_subject(&test_subject, &mut TestDeserializerContext { database }).unwrap();
+  assert_eq!(result.0, 1);
+  assert_eq!(result.1, "two");
+}
+
+#[test]
+fn test_skipped_field_serialization() {
+  #[derive(SaplingSerialization, SaplingDeserialization)]
+  struct Cached {
+    value: i64,
+    #[sapling(skip)]
+    cache: Vec<i64>,
+  }
+
+  let mut database = Database::new();
+  let mut context = TestSerializerContext {
+    database: &mut database,
+    output: Vec::new(),
+  };
+
+  let test_subject = Cached {
+    value: 7,
+    cache: vec![1, 2, 3],
+  }
+  .serialize_to_facts(&mut context, "cached");
+
+  assert_eq!(context.output.len(), 2);
+  assert_eq!(
+    System::get_human_readable_fact(context.database, &context.output[1]),
+    "cached/value = 7"
+  );
+
+  let result =
+    Cached::deserialize_subject(&test_subject, &mut TestDeserializerContext { database }).unwrap();
+  assert_eq!(result.value, 7);
+  assert!(result.cache.is_empty());
+}
+
+#[test]
+fn test_optional_field_serialization() {
+  #[derive(SaplingSerialization, SaplingDeserialization)]
+  struct Person {
+    name: String,
+    nickname: Option<String>,
+  }
+
+  let mut database = Database::new();
+  let mut context = TestSerializerContext {
+    database: &mut database,
+    output: Vec::new(),
+  };
+
+  let with_nickname = Person {
+    name: "Rene".to_string(),
+    nickname: Some("Ren".to_string()),
+  }
+  .serialize_to_facts(&mut context, "with_nickname");
+  assert_eq!(context.output.len(), 3);
+  assert_eq!(
+    System::get_human_readable_fact(context.database, &context.output[2]),
+    "with_nickname/nickname = Ren"
+  );
+
+  let without_nickname = Person {
+    name: "John".to_string(),
+    nickname: None,
+  }
+  .serialize_to_facts(&mut context, "without_nickname");
+  assert_eq!(context.output.len(), 5);
+
+  let mut context = TestDeserializerContext { database };
+  let result = Person::deserialize_subject(&with_nickname, &mut context).unwrap();
+  assert_eq!(result.name, "Rene");
+  assert_eq!(result.nickname.as_deref(), Some("Ren"));
+
+  let result = Person::deserialize_subject(&without_nickname, &mut context).unwrap();
+  assert_eq!(result.name, "John");
+  assert_eq!(result.nickname, None);
+}
+
+#[test]
+fn test_bool_and_float_serialization() {
+  #[derive(SaplingSerialization, SaplingDeserialization)]
+  struct Settings {
+    enabled: bool,
+    scale: f32,
+    ratio: f64,
+  }
+
+  let mut database = Database::new();
+  let mut context = TestSerializerContext {
+    database: &mut database,
+    output: Vec::new(),
+  };
+
+  let test_subject = Settings {
+    enabled: true,
+    scale: 1.5,
+    ratio: 0.25,
+  }
+  .serialize_to_facts(&mut context, "settings");
+  assert_eq!(
+    context.output[1].value.subject,
+    Subject::Boolean { value: true }
+  );
+  assert_eq!(
+    context.output[2].value.subject,
+    Subject::Float { value: 1.5 }
+  );
+  assert_eq!(
+    context.output[3].value.subject,
+    Subject::Float { value: 0.25 }
+  );
+
+  let mut context = TestDeserializerContext { database };
+  let result = Settings::deserialize_subject(&test_subject, &mut context).unwrap();
+  assert!(result.enabled);
+  assert_eq!(result.scale, 1.5);
+  assert_eq!(result.ratio, 0.25);
+
+  // Integers are accepted for floats, booleans stored as integers only accept 0 and 1
+  assert_eq!(
+    f64::deserialize_subject(&Subject::Integer { value: 3 }, &mut context).unwrap(),
+    3.0
+  );
+  assert!(!bool::deserialize_subject(&Subject::Integer { value: 0 }, &mut context).unwrap());
+  match bool::deserialize_subject(&Subject::Integer { value: 2 }, &mut context) {
+    Err(DeserializeError::InvalidType { actual, .. }) => {
+      assert_eq!(actual, Subject::Integer { value: 2 }.type_name())
+    }
+    _ => panic!("expected an invalid type error"),
+  }
+  assert!(matches!(
+    f32::deserialize_subject(
+      &Subject::String {
+        value: "1.0".to_string()
+      },
+      &mut context
+    ),
+    Err(DeserializeError::InvalidType { .. })
+  ));
+
+  // Plain values have no subjects to collect
+  assert!(bool::deserialize_all(&mut context).is_empty());
+  assert!(f64::deserialize_all(&mut context).is_empty());
+}
+
+#[test]
+fn test_deserialize_all() {
+  #[derive(SaplingSerialization, SaplingDeserialization)]
+  struct Point {
+    x: i64,
+    y: i64,
+  }
+
+  #[derive(SaplingSerialization, SaplingDeserialization)]
+  struct Label {
+    text: String,
+  }
+
+  let mut database = Database::new();
+  let mut context = TestSerializerContext {
+    database: &mut database,
+    output: Vec::new(),
+  };
+
+  Point { x: 1, y: 2 }.serialize_to_facts(&mut context, "first");
+  Label {
+    text: "unrelated".to_string(),
+  }
+  .serialize_to_facts(&mut context, "label");
+  Point { x: 3, y: 4 }.serialize_to_facts(&mut context, "second");
+
+  let points = Point::deserialize_all(&mut TestDeserializerContext { database })
+    .into_iter()
+    .map(|point| point.map(|point| (point.x, point.y)).unwrap())
+    .collect::<Vec<_>>();
+  assert_eq!(points, vec![(1, 2), (3, 4)]);
+}
+
+#[test]
+fn test_nested_deserialization_error_path() {
+  #[derive(SaplingSerialization, SaplingDeserialization)]
+  struct Friend {
+    age: i64,
+  }
+
+  #[derive(SaplingSerialization, SaplingDeserialization)]
+  struct Person {
+    name: String,
+    best_friend: Friend,
+  }
+
+  let mut database = Database::new();
+  let mut context = TestSerializerContext {
+    database: &mut database,
+    output: Vec::new(),
+  };
+
+  let subject = Person {
+    name: "Alice".to_string(),
+    best_friend: Friend { age: 30 },
+  }
+  .serialize_to_facts(&mut context, "alice");
+
+  let age_index = (0..)
+    .map_while(|index| database.get_fact(index))
+    .position(|fact| System::get_human_readable_fact(&database, fact).ends_with("/age = 30"))
+    .unwrap();
+  database.facts_mut().remove(age_index);
+
+  let error = Person::deserialize_subject(&subject, &mut TestDeserializerContext { database })
+    .err()
+    .unwrap();
+  assert!(matches!(error, DeserializeError::Field { .. }));
+  assert!(
+    error
+      .to_string()
+      .starts_with("Person.best_friend -> Friend.age: Property 'age' is missing"),
+    "{error}"
+  );
+}
+
+#[test]
+fn test_invalid_indexed_item() {
+  #[derive(SaplingSerialization)]
+  struct Labels {
+    #[sapling(indexed = true)]
+    items: Vec<String>,
+  }
+
+  #[derive(SaplingDeserialization)]
+  struct Counts {
+    #[sapling(indexed = true)]
+    items: Vec<i64>,
+  }
+
+  let mut database = Database::new();
+  let mut context = TestSerializerContext {
+    database: &mut database,
+    output: Vec::new(),
+  };
+
+  let subject = Labels {
+    items: vec!["one".to_string()],
+  }
+  .serialize_to_facts(&mut context, "labels");
+
+  let error = Counts::deserialize_subject(&subject, &mut TestDeserializerContext { database })
+    .map(|counts| counts.items)
+    .unwrap_err();
+  assert_eq!(
+    error.to_string(),
+    "Counts.items[0]: Invalid type expected 'integer' got 'string'"
+  );
+}
+
+#[test]
+fn test_enum_serialization() {
+  #[derive(Debug, PartialEq, SaplingSerialization, SaplingDeserialization)]
+  enum Limit {
+    Unlimited,
+    #[sapling(rename = "max")]
+    Max(i64),
+  }
+
+  let mut database = Database::new();
+  let mut context = TestSerializerContext {
+    database: &mut database,
+    output: Vec::new(),
+  };
+
+  let unlimited = Limit::Unlimited.serialize_to_facts(&mut context, "unlimited");
+  assert_eq!(context.output.len(), 2);
+  assert_eq!(
+    System::get_human_readable_fact(context.database, &context.output[1]),
+    "unlimited/variant = Unlimited"
+  );
+
+  let max = Limit::Max(10).serialize_to_facts(&mut context, "max");
+  assert_eq!(context.output.len(), 5);
+  assert_eq!(
+    System::get_human_readable_fact(context.database, &context.output[3]),
+    "max/variant = max"
+  );
+  assert_eq!(
+    System::get_human_readable_fact(context.database, &context.output[4]),
+    "max/0 = 10"
+  );
+
+  let mut context = TestDeserializerContext { database };
+  assert_eq!(
+    Limit::deserialize_subject(&unlimited, &mut context).unwrap(),
+    Limit::Unlimited
+  );
+  assert_eq!(
+    Limit::deserialize_subject(&max, &mut context).unwrap(),
+    Limit::Max(10)
+  );
+}
+
+#[test]
+fn test_unknown_enum_variant() {
+  #[derive(Debug, SaplingSerialization, SaplingDeserialization)]
+  enum Toggle {
+    On,
+  }
+
+  let mut database = Database::new();
+  let mut context = TestSerializerContext {
+    database: &mut database,
+    output: Vec::new(),
+  };
+  let subject = Toggle::On.serialize_to_facts(&mut context, "toggle");
+  let variant_index = (0..)
+    .map_while(|index| database.get_fact(index))
+    .position(|fact| System::get_human_readable_fact(&database, fact).ends_with("/variant = On"))
+    .unwrap();
+  database.facts_mut()[variant_index].value.subject = Subject::String {
+    value: "Maybe".to_string(),
+  };
+
+  let error = Toggle::deserialize_subject(&subject, &mut TestDeserializerContext { database })
+    .err()
+    .unwrap();
+  assert!(
+    matches!(&error, DeserializeError::UnknownVariant { variant, .. } if variant == "Maybe"),
+    "{error}"
+  );
+}
+
+#[test]
+fn test_custom_meta_serialization() {
+  #[derive(SaplingSerialization, SaplingDeserialization)]
+  struct Invoice {
+    amount: i64,
+    #[sapling(meta = "computed")]
+    total: i64,
+  }
+
+  let mut database = Database::new();
+  let mut context = TestSerializerContext {
+    database: &mut database,
+    output: Vec::new(),
+  };
+
+  let subject = Invoice {
+    amount: 2,
+    total: 4,
+  }
+  .serialize_to_facts(&mut context, "invoice");
+  assert_eq!(
+    context.output[1].meta,
+    Subject::String {
+      value: "default".to_string()
+    }
+  );
+  assert_eq!(
+    context.output[2].meta,
+    Subject::String {
+      value: "computed".to_string()
+    }
+  );
+
+  // Only the fact with the custom meta is considered for the field
+  let mut stale_total = context.output[2].clone();
+  stale_total.meta = Subject::String {
+    value: "default".to_string(),
+  };
+  stale_total.value.subject = Subject::Integer { value: 0 };
+  database.facts_mut().insert(0, stale_total);
+
+  let result =
+    Invoice::deserialize_subject(&subject, &mut TestDeserializerContext { database }).unwrap();
+  assert_eq!(result.amount, 2);
+  assert_eq!(result.total, 4);
+}
+
+#[test]
+fn test_add_fact_returns_index() {
+  /// Writes a note and a second fact referring back to the note by its fact index.
+  struct Annotated(String);
+
+  impl<T: SerializerContext> SaplingSerializable<T> for Annotated {
+    fn serialize_to_facts(&self, context: &mut T, name: &str) -> Subject {
+      let subject = context.new_static_subject(name);
+      let note = context.new_static_subject("note");
+      let note_source = context.new_static_subject("note source");
+      let selector = |subject: &Subject| SubjectSelector {
+        evaluated: false,
+        subject: subject.clone(),
+        property: None,
+      };
+      let fact = |property: &Subject, value: Subject| Fact {
+        subject: selector(&subject),
+        property: selector(property),
+        operator: System::CORE_OPERATOR_IS,
+        value: selector(&value),
+        meta: Subject::String {
+          value: "default".into(),
+        },
+      };
+
+      let note_index = context.add_fact(fact(
+        &note,
+        Subject::String {
+          value: self.0.clone(),
+        },
+      ));
+      context.add_fact(fact(
+        &note_source,
+        Subject::Integer {
+          value: note_index as i64,
+        },
+      ));
+      subject
+    }
+  }
+
+  let mut database = Database::new();
+  let mut context = TestSerializerContext {
+    database: &mut database,
+    output: Vec::new(),
+  };
+  Annotated("hello".to_string()).serialize_to_facts(&mut context, "annotated");
+
+  let Subject::Integer { value: note_index } = context.output[1].value.subject else {
+    panic!("expected the index of the note fact");
+  };
+  assert_eq!(
+    System::get_human_readable_fact(context.database, &context.output[1]),
+    format!("annotated/note source = {note_index}")
+  );
+  let note = context.output[0].clone();
+  assert_eq!(
+    System::get_human_readable_fact(&database, database.get_fact(note_index as usize).unwrap()),
+    System::get_human_readable_fact(&database, &note)
+  );
+  assert_eq!(
+    System::get_human_readable_fact(&database, &note),
+    "annotated/note = hello"
+  );
+}
@@ -1,14 +1,46 @@
 use sapling_app::{AppPlugin, AppPluginInstallContext};
 
-use crate::math::std_math_operation_add;
+use crate::math::{
+  std_math_operation_add, std_math_operation_difference, std_math_operation_negate,
+  std_math_operation_product, std_math_range,
+};
+use crate::string::{std_string_concat, std_string_length};
 
 mod math;
+mod string;
+
+/// Interop inputs are only deserialized from facts, tests build them from their operands instead.
+#[cfg(test)]
+pub(crate) mod test_fixtures {
+  pub trait IndexedOperands {
+    type Operand;
+
+    fn from_indexed(indexed: Vec<Self::Operand>) -> Self;
+  }
+
+  pub fn operands<T: IndexedOperands>(
+    indexed: impl IntoIterator<Item = impl Into<T::Operand>>,
+  ) -> T {
+    T::from_indexed(indexed.into_iter().map(Into::into).collect())
+  }
+}
 
 #[derive(Default)]
 pub struct StandardLibrary;
 
 impl AppPlugin for StandardLibrary {
   fn install_plugin(&mut self, context: &mut AppPluginInstallContext) {
-    context.add_interop_fn("MathSum", "Result", std_math_operation_add);
+    context.add_interop_fn("MathSum", "Result", None, std_math_operation_add);
+    context.add_interop_fn("MathProduct", "Result", None, std_math_operation_product);
+    context.add_interop_relation(
+      "MathDifference",
+      "Result",
+      Some(2),
+      std_math_operation_difference,
+    );
+    context.add_interop_relation("MathNegate", "Result", Some(1), std_math_operation_negate);
+    context.add_interop_relation("Range", "Result", Some(2), std_math_range);
+    context.add_interop_relation("StringConcat", "Result", Some(2), std_string_concat);
+    context.add_interop_relation("StringLength", "Result", Some(1), std_string_length);
   }
 }
@@ -1,3 +1,8 @@
+use sapling_data_model::{Query, Subject};
+use sapling_serialization::{
+  DeserializeError, DeserializerContext, SaplingDeserializable, SaplingSerializable,
+  SerializerContext,
+};
 use sapling_serialization_macro::{SaplingDeserialization, SaplingSerialization};
 
 #[derive(SaplingSerialization, SaplingDeserialization)]
@@ -9,3 +14,164 @@ pub struct Operations {
 pub fn std_math_operation_add(input: &Operations) -> i64 {
   input.indexed.iter().sum()
 }
+
+/// Integer or float operand, any other subject fails to deserialize.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+  Integer(i64),
+  Float(f64),
+}
+
+impl Number {
+  fn as_float(self) -> f64 {
+    match self {
+      Number::Integer(value) => value as f64,
+      Number::Float(value) => value,
+    }
+  }
+
+  /// Applies the integer operation if both operands are integers, otherwise promotes both to
+  /// floats.
+  fn combine(
+    self,
+    other: Number,
+    integer: fn(i64, i64) -> i64,
+    float: fn(f64, f64) -> f64,
+  ) -> Self {
+    match (self, other) {
+      (Number::Integer(a), Number::Integer(b)) => Number::Integer(integer(a, b)),
+      (a, b) => Number::Float(float(a.as_float(), b.as_float())),
+    }
+  }
+}
+
+impl<T: SerializerContext> SaplingSerializable<T> for Number {
+  fn serialize_to_facts(&self, _context: &mut T, _name: &str) -> Subject {
+    match *self {
+      Number::Integer(value) => Subject::Integer { value },
+      Number::Float(value) => Subject::Float { value },
+    }
+  }
+}
+
+impl<T: DeserializerContext> SaplingDeserializable<T> for Number {
+  fn first_level_queries(_subject: &Subject, _context: &mut T) -> Vec<Query> {
+    vec![]
+  }
+
+  fn deserialize_subject(subject: &Subject, _context: &mut T) -> Result<Self, DeserializeError> {
+    match subject {
+      Subject::Integer { value } => Ok(Number::Integer(*value)),
+      Subject::Float { value } => Ok(Number::Float(*value)),
+      _ => Err(DeserializeError::InvalidType {
+        expected: "number".to_string(),
+        actual: subject.type_name().to_string(),
+      }),
+    }
+  }
+
+  /// Numbers are values rather than subjects with facts, there is nothing to collect.
+  fn deserialize_all(_context: &mut T) -> Vec<Result<Self, DeserializeError>> {
+    Vec::new()
+  }
+}
+
+#[derive(SaplingDeserialization)]
+pub struct NumericOperations {
+  #[sapling(indexed = true)]
+  indexed: Vec<Number>,
+}
+
+/// Multiplies all operands, the result is a float as soon as one operand is a float.
+pub fn std_math_operation_product(input: &NumericOperations) -> Number {
+  input
+    .indexed
+    .iter()
+    .fold(Number::Integer(1), |product, &operand| {
+      product.combine(operand, |a, b| a * b, |a, b| a * b)
+    })
+}
+
+/// Subtracts the second operand from the first, yields nothing until both operands are present.
+pub fn std_math_operation_difference(input: &NumericOperations) -> Option<Number> {
+  match input.indexed.as_slice() {
+    [minuend, subtrahend] => Some(minuend.combine(*subtrahend, |a, b| a - b, |a, b| a - b)),
+    _ => None,
+  }
+}
+
+pub fn std_math_operation_negate(input: &NumericOperations) -> Option<Number> {
+  match input.indexed.as_slice() {
+    [Number::Integer(value)] => Some(Number::Integer(-value)),
+    [Number::Float(value)] => Some(Number::Float(-value)),
+    _ => None,
+  }
+}
+
+#[derive(SaplingDeserialization)]
+pub struct RangeBounds {
+  #[sapling(indexed = true)]
+  indexed: Vec<i64>,
+}
+
+/// Yields the integers from the first bound (inclusive) to the second bound (exclusive).
+pub fn std_math_range(input: &RangeBounds) -> Vec<i64> {
+  match input.indexed.as_slice() {
+    [start, end] => (*start..*end).collect(),
+    _ => Vec::new(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_fixtures::{IndexedOperands, operands};
+
+  impl IndexedOperands for NumericOperations {
+    type Operand = Number;
+
+    fn from_indexed(indexed: Vec<Number>) -> Self {
+      Self { indexed }
+    }
+  }
+
+  #[test]
+  fn test_product() {
+    let integers: NumericOperations =
+      operands([Number::Integer(2), Number::Integer(3), Number::Integer(4)]);
+    assert_eq!(std_math_operation_product(&integers), Number::Integer(24));
+
+    let mixed = operands([Number::Integer(2), Number::Float(1.5)]);
+    assert_eq!(std_math_operation_product(&mixed), Number::Float(3.0));
+  }
+
+  #[test]
+  fn test_difference() {
+    let integers = operands([Number::Integer(5), Number::Integer(7)]);
+    assert_eq!(
+      std_math_operation_difference(&integers),
+      Some(Number::Integer(-2))
+    );
+
+    let mixed = operands([Number::Float(5.5), Number::Integer(2)]);
+    assert_eq!(
+      std_math_operation_difference(&mixed),
+      Some(Number::Float(3.5))
+    );
+
+    let incomplete = operands([Number::Integer(5)]);
+    assert_eq!(std_math_operation_difference(&incomplete), None);
+  }
+
+  #[test]
+  fn test_negate() {
+    let integer = operands([Number::Integer(5)]);
+    assert_eq!(
+      std_math_operation_negate(&integer),
+      Some(Number::Integer(-5))
+    );
+
+    let float = operands([Number::Float(-2.5)]);
+    assert_eq!(std_math_operation_negate(&float), Some(Number::Float(2.5)));
+  }
+}
@@ -0,0 +1,52 @@
+use sapling_serialization_macro::SaplingDeserialization;
+
+#[derive(SaplingDeserialization)]
+pub struct StringOperands {
+  #[sapling(indexed = true)]
+  indexed: Vec<String>,
+}
+
+/// Joins both operands, yields nothing until both operands are present.
+pub fn std_string_concat(input: &StringOperands) -> Option<String> {
+  match input.indexed.as_slice() {
+    [first, second] => Some(format!("{first}{second}")),
+    _ => None,
+  }
+}
+
+/// Number of characters of the operand.
+pub fn std_string_length(input: &StringOperands) -> Option<i64> {
+  match input.indexed.as_slice() {
+    [value] => Some(value.chars().count() as i64),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_fixtures::{IndexedOperands, operands};
+
+  impl IndexedOperands for StringOperands {
+    type Operand = String;
+
+    fn from_indexed(indexed: Vec<String>) -> Self {
+      Self { indexed }
+    }
+  }
+
+  #[test]
+  fn test_concat() {
+    assert_eq!(
+      std_string_concat(&operands(["Hello, ", "World"])),
+      Some("Hello, World".to_string())
+    );
+    assert_eq!(std_string_concat(&operands(["Hello"])), None);
+  }
+
+  #[test]
+  fn test_length() {
+    assert_eq!(std_string_length(&operands(["Grüße"])), Some(5));
+    assert_eq!(std_string_length(&operands([""])), Some(0));
+  }
+}